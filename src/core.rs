@@ -1,9 +1,23 @@
+// Shared by the Arrow-based sources and sinks; each feature uses only some helpers.
+#[cfg(any(feature = "arrow", feature = "flight", feature = "iceberg", feature = "parquet"))]
+#[allow(dead_code)]
+pub(crate) mod arrow;
+pub mod compression;
+pub mod crypto;
 pub mod error;
+pub mod progress;
 pub mod record;
+pub mod retry;
 pub mod schema;
 pub mod traits;
+pub mod value;
 
+pub use self::compression::*;
+pub use self::crypto::*;
 pub use self::error::*;
+pub use self::progress::*;
 pub use self::record::*;
+pub use self::retry::*;
 pub use self::schema::*;
-pub use self::traits::*;
\ No newline at end of file
+pub use self::traits::*;
+pub use self::value::*;
\ No newline at end of file
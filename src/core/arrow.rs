@@ -0,0 +1,114 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema};
+use arrow_array::RecordBatch;
+use arrow_json::writer::{JsonArray, WriterBuilder};
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType as ArrowType, Field as ArrowField, SchemaRef, TimeUnit};
+use serde_json::{Map, Value};
+
+pub(crate) fn schema_from_arrow(schema: &arrow_schema::Schema) -> Schema {
+    Schema::new(
+        schema
+            .fields()
+            .iter()
+            .map(|field| Field {
+                name: field.name().clone(),
+                data_type: data_type_from_arrow(field.data_type()),
+                nullable: field.is_nullable(),
+                description: None,
+            })
+            .collect(),
+    )
+}
+
+/// The Arrow schema records of `schema` are written with. JSON values are
+/// stored as their JSON text and bytes as base64 strings.
+pub(crate) fn schema_to_arrow(schema: &Schema) -> arrow_schema::Schema {
+    arrow_schema::Schema::new(
+        schema
+            .fields
+            .iter()
+            .map(|field| ArrowField::new(&field.name, data_type_to_arrow(&field.data_type), field.nullable))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn data_type_to_arrow(data_type: &DataType) -> ArrowType {
+    match data_type {
+        DataType::String | DataType::Json | DataType::Bytes => ArrowType::Utf8,
+        DataType::Integer => ArrowType::Int64,
+        DataType::Float => ArrowType::Float64,
+        DataType::Boolean => ArrowType::Boolean,
+        DataType::DateTime => ArrowType::Timestamp(TimeUnit::Microsecond, None),
+        DataType::Timestamp => ArrowType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        DataType::Date => ArrowType::Date32,
+        DataType::Array(item) => ArrowType::List(ArrowField::new("item", data_type_to_arrow(item), true).into()),
+    }
+}
+
+fn data_type_from_arrow(data_type: &ArrowType) -> DataType {
+    match data_type {
+        ArrowType::Boolean => DataType::Boolean,
+        t if t.is_integer() => DataType::Integer,
+        t if t.is_floating() || matches!(t, ArrowType::Decimal128(..) | ArrowType::Decimal256(..)) => {
+            DataType::Float
+        }
+        ArrowType::Utf8 | ArrowType::LargeUtf8 | ArrowType::Utf8View => DataType::String,
+        ArrowType::Timestamp(_, Some(_)) => DataType::Timestamp,
+        ArrowType::Timestamp(_, None) | ArrowType::Date64 => DataType::DateTime,
+        ArrowType::Date32 => DataType::Date,
+        ArrowType::Binary | ArrowType::LargeBinary | ArrowType::BinaryView | ArrowType::FixedSizeBinary(_) => {
+            DataType::Bytes
+        }
+        ArrowType::Dictionary(_, values) => data_type_from_arrow(values),
+        ArrowType::List(item) | ArrowType::LargeList(item) | ArrowType::FixedSizeList(item, _) => {
+            DataType::Array(Box::new(data_type_from_arrow(item.data_type())))
+        }
+        _ => DataType::Json,
+    }
+}
+
+/// Converts each row of `batch` to a record, using Arrow's JSON encoding for
+/// the values: timestamps and dates become ISO 8601 strings, nested types
+/// become arrays and objects, and nulls are kept as `Value::Null`.
+pub(crate) fn records_from_batch(batch: &RecordBatch) -> Result<Vec<Record>> {
+    let mut writer = WriterBuilder::new().with_explicit_nulls(true).build::<_, JsonArray>(Vec::new());
+    writer
+        .write(batch)
+        .and_then(|_| writer.finish())
+        .map_err(|e| PipelineError::Source(anyhow::anyhow!("Failed to convert Arrow batch: {}", e)))?;
+    let bytes = writer.into_inner();
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let rows: Vec<Map<String, Value>> = serde_json::from_slice(&bytes)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let mut record = Record::new();
+            for (name, value) in row {
+                record.set_field(name, value);
+            }
+            record
+        })
+        .collect())
+}
+
+/// Builds a batch with `schema` from `records`, the inverse of
+/// [`records_from_batch`]. Fields missing from a record are null, fields not
+/// in `schema` are ignored, and numbers and booleans are accepted for string
+/// columns.
+pub(crate) fn batch_from_records(records: &[Record], schema: SchemaRef) -> Result<RecordBatch> {
+    let error = |e: arrow_schema::ArrowError| PipelineError::Sink(format!("Failed to build Arrow batch: {}", e));
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(records.len().max(1))
+        .with_coerce_primitive(true)
+        .build_decoder()
+        .map_err(error)?;
+    let rows: Vec<_> = records.iter().map(|record| &record.data).collect();
+    decoder.serialize(&rows).map_err(error)?;
+    Ok(decoder
+        .flush()
+        .map_err(error)?
+        .unwrap_or_else(|| RecordBatch::new_empty(schema)))
+}
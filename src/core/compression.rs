@@ -0,0 +1,44 @@
+use async_compression::tokio::bufread::GzipDecoder;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncRead, BufReader};
+
+/// Compression applied to a file source's bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl Compression {
+    /// Infers the compression from the file name: `.gz` means gzip.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("gz") => Compression::Gzip,
+            _ => Compression::None,
+        }
+    }
+}
+
+pub(crate) type DecompressedReader = BufReader<Box<dyn AsyncRead + Send + Unpin>>;
+
+/// Opens `path` for buffered reading, decompressing on the fly so only the
+/// bytes actually read are ever inflated.
+pub(crate) async fn open_decompressed(path: &str, compression: Compression) -> std::io::Result<DecompressedReader> {
+    Ok(decompress(File::open(path).await?, compression))
+}
+
+/// Wraps any byte stream, such as an object body, the way
+/// [`open_decompressed`] wraps a file.
+pub(crate) fn decompress<R: AsyncRead + Send + Unpin + 'static>(reader: R, compression: Compression) -> DecompressedReader {
+    let inner: Box<dyn AsyncRead + Send + Unpin> = match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => {
+            let mut decoder = GzipDecoder::new(BufReader::new(reader));
+            decoder.multiple_members(true);
+            Box::new(decoder)
+        }
+    };
+    BufReader::new(inner)
+}
@@ -0,0 +1,176 @@
+use crate::core::{PipelineError, Result};
+use aes_gcm::aead::{self, Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+const MAGIC: &[u8; 5] = b"DPEC\x01";
+const CHUNK_SIZE: usize = 64 * 1024;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// The record encoding inside an encrypted file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EncryptedFormat {
+    JsonLines,
+    Csv { delimiter: u8, has_header: bool },
+}
+
+// Binding each chunk's index and final flag into the authenticated data
+// makes reordered, dropped or truncated chunks fail to decrypt.
+fn associated_data(index: u64, last: bool) -> [u8; 9] {
+    let mut aad = [0u8; 9];
+    aad[..8].copy_from_slice(&index.to_le_bytes());
+    aad[8] = last as u8;
+    aad
+}
+
+/// Writes plaintext as a sequence of AES-256-GCM chunks, each stored as
+/// `len (u32 LE) || nonce || ciphertext` after a short magic header.
+pub(crate) struct EncryptWriter {
+    cipher: Aes256Gcm,
+    writer: BufWriter<File>,
+    buffer: Vec<u8>,
+    index: u64,
+}
+
+impl EncryptWriter {
+    pub(crate) async fn create<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path).await?);
+        writer.write_all(MAGIC).await?;
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            writer,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+            index: 0,
+        })
+    }
+
+    pub(crate) async fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.buffer.extend_from_slice(bytes);
+        // Keep at least one byte back so the final chunk is never empty
+        // unless the whole file is.
+        while self.buffer.len() > CHUNK_SIZE {
+            let rest = self.buffer.split_off(CHUNK_SIZE);
+            let chunk = std::mem::replace(&mut self.buffer, rest);
+            self.write_chunk(&chunk, false).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the final chunk and flushes the file.
+    pub(crate) async fn finish(&mut self) -> Result<()> {
+        let chunk = std::mem::take(&mut self.buffer);
+        self.write_chunk(&chunk, true).await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, chunk: &[u8], last: bool) -> Result<()> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = associated_data(self.index, last);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: chunk, aad: &aad })
+            .map_err(|e| PipelineError::Sink(format!("Encryption failed: {}", e)))?;
+
+        self.writer.write_all(&(ciphertext.len() as u32).to_le_bytes()).await?;
+        self.writer.write_all(&nonce).await?;
+        self.writer.write_all(&ciphertext).await?;
+        self.index += 1;
+        Ok(())
+    }
+}
+
+/// Reads a file written by [`EncryptWriter`] back as lines of plaintext.
+pub(crate) struct DecryptReader {
+    cipher: Aes256Gcm,
+    reader: BufReader<File>,
+    index: u64,
+    finished: bool,
+    pending: Vec<u8>,
+}
+
+impl DecryptReader {
+    pub(crate) async fn open<P: AsRef<Path>>(path: P, key: &[u8; 32]) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path).await?);
+        let mut magic = [0u8; MAGIC.len()];
+        if reader.read_exact(&mut magic).await.is_err() || &magic != MAGIC {
+            return Err(decrypt_error("not an encrypted pipeline file"));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            reader,
+            index: 0,
+            finished: false,
+            pending: Vec::new(),
+        })
+    }
+
+    pub(crate) async fn next_line(&mut self) -> Result<Option<String>> {
+        loop {
+            if let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+                let rest = self.pending.split_off(end + 1);
+                let line = std::mem::replace(&mut self.pending, rest);
+                return to_line(line).map(Some);
+            }
+            if self.finished {
+                if self.pending.is_empty() {
+                    return Ok(None);
+                }
+                return to_line(std::mem::take(&mut self.pending)).map(Some);
+            }
+            self.read_chunk().await?;
+        }
+    }
+
+    async fn read_chunk(&mut self) -> Result<()> {
+        let mut len = [0u8; 4];
+        if self.reader.read_exact(&mut len).await.is_err() {
+            return Err(decrypt_error("file is truncated"));
+        }
+        let len = u32::from_le_bytes(len) as usize;
+        if !(TAG_LEN..=CHUNK_SIZE + TAG_LEN).contains(&len) {
+            return Err(decrypt_error("invalid chunk length"));
+        }
+
+        let mut frame = vec![0u8; NONCE_LEN + len];
+        if self.reader.read_exact(&mut frame).await.is_err() {
+            return Err(decrypt_error("file is truncated"));
+        }
+        let (nonce, ciphertext) = frame.split_at(NONCE_LEN);
+        let nonce = aead::Nonce::<Aes256Gcm>::from_slice(nonce);
+
+        // The final chunk is whichever one authenticates as final.
+        let (plaintext, last) = match self.decrypt(nonce, ciphertext, false) {
+            Some(plaintext) => (plaintext, false),
+            None => (
+                self.decrypt(nonce, ciphertext, true)
+                    .ok_or_else(|| decrypt_error("wrong key or corrupted chunk"))?,
+                true,
+            ),
+        };
+
+        self.pending.extend_from_slice(&plaintext);
+        self.index += 1;
+        self.finished = last;
+        Ok(())
+    }
+
+    fn decrypt(&self, nonce: &aead::Nonce<Aes256Gcm>, ciphertext: &[u8], last: bool) -> Option<Vec<u8>> {
+        let aad = associated_data(self.index, last);
+        self.cipher.decrypt(nonce, Payload { msg: ciphertext, aad: &aad }).ok()
+    }
+}
+
+fn to_line(mut bytes: Vec<u8>) -> Result<String> {
+    while matches!(bytes.last(), Some(b'\n' | b'\r')) {
+        bytes.pop();
+    }
+    String::from_utf8(bytes).map_err(|_| decrypt_error("plaintext is not valid UTF-8"))
+}
+
+fn decrypt_error(reason: &str) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Cannot decrypt file: {}", reason))
+}
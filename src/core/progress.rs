@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::mpsc::error::TrySendError;
+
+/// Running totals of a pipeline run, sent periodically to the channel given
+/// to [`Pipeline::with_progress`](crate::pipeline::Pipeline::with_progress).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    /// Records produced by the source so far.
+    pub records_read: u64,
+    /// Records handed to a sink so far, summed over branches.
+    pub records_written: u64,
+    /// Time since the run started.
+    pub elapsed: Duration,
+    /// Set on the last event of a run, sent once every sink has closed.
+    pub finished: bool,
+}
+
+/// Sends a [`ProgressEvent`] once `every_records` records have been read or
+/// `every` has passed since the previous one, whichever comes first.
+pub(crate) struct ProgressReporter {
+    tx: Option<Sender<ProgressEvent>>,
+    pub(crate) every_records: u64,
+    pub(crate) every: Duration,
+    last_records: u64,
+    last_sent: Option<Instant>,
+}
+
+impl Default for ProgressReporter {
+    fn default() -> Self {
+        Self {
+            tx: None,
+            every_records: 1000,
+            every: Duration::from_secs(1),
+            last_records: 0,
+            last_sent: None,
+        }
+    }
+}
+
+impl ProgressReporter {
+    pub(crate) fn set_channel(&mut self, tx: Sender<ProgressEvent>) {
+        self.tx = Some(tx);
+    }
+
+    /// Called after each record or batch is processed; `records_written` is
+    /// only evaluated when an event is due.
+    pub(crate) fn tick(&mut self, run_started: Instant, records_read: u64, records_written: impl FnOnce() -> u64) {
+        if self.tx.is_none() {
+            return;
+        }
+        let last_sent = *self.last_sent.get_or_insert(run_started);
+        if records_read - self.last_records < self.every_records && last_sent.elapsed() < self.every {
+            return;
+        }
+        self.send(ProgressEvent {
+            records_read,
+            records_written: records_written(),
+            elapsed: run_started.elapsed(),
+            finished: false,
+        });
+    }
+
+    /// Sends the final event, waiting for room in the channel rather than
+    /// dropping it, so a consumer waiting for `finished` always gets it.
+    pub(crate) async fn finish(&mut self, records_read: u64, records_written: u64, elapsed: Duration) {
+        if let Some(tx) = self.tx.take() {
+            // A closed channel means nobody is listening any more.
+            let _ = tx.send(ProgressEvent { records_read, records_written, elapsed, finished: true }).await;
+        }
+    }
+
+    /// Events before the last are dropped if the receiver has fallen
+    /// behind, so a slow consumer does not hold up the run.
+    fn send(&mut self, event: ProgressEvent) {
+        let Some(ref tx) = self.tx else {
+            return;
+        };
+        self.last_records = event.records_read;
+        self.last_sent = Some(Instant::now());
+        if let Err(TrySendError::Closed(_)) = tx.try_send(event) {
+            self.tx = None;
+        }
+    }
+}
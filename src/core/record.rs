@@ -1,26 +1,28 @@
 use crate::core::{Schema, PipelineError, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
+/// Fields keep the order they were inserted in, so sinks that derive columns
+/// from a record write them in a stable order.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Record {
-    pub data: HashMap<String, Value>,
-    pub metadata: HashMap<String, String>,
+    pub data: IndexMap<String, Value>,
+    pub metadata: IndexMap<String, String>,
 }
 
 impl Record {
     pub fn new() -> Self {
         Self {
-            data: HashMap::new(),
-            metadata: HashMap::new(),
+            data: IndexMap::new(),
+            metadata: IndexMap::new(),
         }
     }
     
-    pub fn with_data(data: HashMap<String, Value>) -> Self {
+    pub fn with_data(data: IndexMap<String, Value>) -> Self {
         Self {
             data,
-            metadata: HashMap::new(),
+            metadata: IndexMap::new(),
         }
     }
     
@@ -32,6 +34,39 @@ impl Record {
         self.data.get(name)
     }
     
+    /// Returns the value at a dotted `path` such as `user.address.city`,
+    /// where a numeric segment indexes into an array, as in `items.0.sku`.
+    /// Returns `None` if any segment is missing or its parent is neither an
+    /// object nor an array.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        let mut segments = path.split('.');
+        let first = self.data.get(segments.next()?)?;
+        segments.try_fold(first, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// Sets the value at a dotted `path`, as read by [`Record::get_path`],
+    /// creating missing or null intermediate values as objects. Indexing
+    /// one past the end of an array appends to it; any further is an error.
+    /// A segment whose parent is
+    /// some other value is an error unless `overwrite` is set, in which case
+    /// that value is replaced by an object.
+    pub fn set_path(&mut self, path: &str, value: Value, overwrite: bool) -> Result<()> {
+        let mut segments = path.split('.');
+        let first = segments.next().unwrap_or_default();
+        let mut current = self.data.entry(first.to_string()).or_insert(Value::Null);
+        let mut parent_path = first.to_string();
+        for segment in segments {
+            current = path_child(current, segment, overwrite, &parent_path)?;
+            parent_path = format!("{}.{}", parent_path, segment);
+        }
+        *current = value;
+        Ok(())
+    }
+
     pub fn set_metadata(&mut self, key: String, value: String) {
         self.metadata.insert(key, value);
     }
@@ -50,12 +85,11 @@ impl Record {
                 ));
             }
             
-            if let Some(value) = value {
-                if !self.is_value_compatible_with_type(value, &field.data_type) {
-                    return Err(PipelineError::Schema(
-                        format!("Field '{}' has incompatible type", field.name)
-                    ));
-                }
+            if let Some(value) = value
+                && !self.is_value_compatible_with_type(value, &field.data_type) {
+                return Err(PipelineError::Schema(
+                    format!("Field '{}' has incompatible type", field.name)
+                ));
             }
         }
         Ok(())
@@ -69,15 +103,114 @@ impl Record {
             (Value::Number(n), DataType::Float) if n.is_f64() => true,
             (Value::Bool(_), DataType::Boolean) => true,
             (Value::String(_), DataType::DateTime) => true, // Assume string represents datetime
+            (Value::String(s), DataType::Date) => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+            (Value::String(s), DataType::Timestamp) => chrono::DateTime::parse_from_rfc3339(s).is_ok(),
             (_, DataType::Json) => true, // Any JSON value is acceptable
             (Value::String(_), DataType::Bytes) => true, // Base64 encoded bytes
+            (Value::Array(items), DataType::Array(item_type)) => items
+                .iter()
+                .all(|item| item.is_null() || self.is_value_compatible_with_type(item, item_type)),
             _ => false,
         }
     }
 }
 
+/// Returns the slot for `segment` within `container`, creating it if needed.
+fn path_child<'a>(container: &'a mut Value, segment: &str, overwrite: bool, container_path: &str) -> Result<&'a mut Value> {
+    let index = segment.parse::<usize>().ok();
+    let replace = match container {
+        Value::Null => true,
+        Value::Object(_) => false,
+        Value::Array(_) if index.is_some() => false,
+        _ if overwrite => true,
+        _ => {
+            return Err(PipelineError::Schema(format!(
+                "Cannot set '{}' in field '{}', which is not an object or array",
+                segment, container_path
+            )));
+        }
+    };
+    if replace {
+        *container = Value::Object(serde_json::Map::new());
+    }
+
+    match container {
+        Value::Object(map) => Ok(map.entry(segment).or_insert(Value::Null)),
+        Value::Array(items) => {
+            let index = index.unwrap_or_default();
+            if index > items.len() {
+                return Err(PipelineError::Schema(format!(
+                    "Cannot set index {} of field '{}', which has {} items",
+                    index,
+                    container_path,
+                    items.len()
+                )));
+            }
+            if index == items.len() {
+                items.push(Value::Null);
+            }
+            Ok(&mut items[index])
+        }
+        _ => unreachable!("container was made an object or array above"),
+    }
+}
+
 impl Default for Record {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    #[test]
+    fn get_path_follows_objects_and_array_indices() {
+        let record = record(json!({"order": {"items": [{"sku": "a"}, {"sku": "b"}], "total": 3}}));
+
+        assert_eq!(record.get_path("order.total"), Some(&json!(3)));
+        assert_eq!(record.get_path("order.items.1.sku"), Some(&json!("b")));
+        assert_eq!(record.get_path("order.items.2.sku"), None);
+        assert_eq!(record.get_path("order.missing"), None);
+        // A scalar has no children.
+        assert_eq!(record.get_path("order.total.value"), None);
+    }
+
+    #[test]
+    fn set_path_creates_intermediate_objects() {
+        let mut record = record(json!({"id": 1, "meta": null}));
+
+        record.set_path("customer.address.city", json!("Oslo"), false).unwrap();
+        record.set_path("meta.source", json!("api"), false).unwrap();
+        assert_eq!(record.get_path("customer.address.city"), Some(&json!("Oslo")));
+        assert_eq!(record.get_field("meta"), Some(&json!({"source": "api"})));
+    }
+
+    #[test]
+    fn set_path_indexes_into_arrays_and_appends_at_the_end() {
+        let mut record = record(json!({"items": [{"sku": "a"}]}));
+
+        record.set_path("items.0.qty", json!(2), false).unwrap();
+        record.set_path("items.1", json!({"sku": "b"}), false).unwrap();
+        assert_eq!(record.get_field("items"), Some(&json!([{"sku": "a", "qty": 2}, {"sku": "b"}])));
+
+        let error = record.set_path("items.99999999999", json!("far"), false).unwrap_err();
+        assert!(matches!(error, PipelineError::Schema(ref e) if e.contains("99999999999")), "{}", error);
+        assert_eq!(record.get_path("items").and_then(Value::as_array).map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn set_path_under_a_scalar_needs_overwrite() {
+        let mut record = record(json!({"status": "active"}));
+
+        let error = record.set_path("status.code", json!(200), false).unwrap_err();
+        assert!(matches!(error, PipelineError::Schema(ref e) if e.contains("'status'")), "{}", error);
+        assert_eq!(record.get_field("status"), Some(&json!("active")));
+
+        record.set_path("status.code", json!(200), true).unwrap();
+        assert_eq!(record.get_field("status"), Some(&json!({"code": 200})));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::core::{PipelineError, Record, Result};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -7,9 +10,57 @@ pub enum DataType {
     Integer,
     Float,
     Boolean,
+    /// A date and time, with or without a UTC offset.
     DateTime,
+    /// A calendar date with no time component, as `YYYY-MM-DD`.
+    Date,
+    /// An instant, as an RFC 3339 string with a UTC offset.
+    Timestamp,
     Json,
     Bytes,
+    /// A JSON array whose elements all have the inner type.
+    Array(Box<DataType>),
+}
+
+impl DataType {
+    /// The narrowest type that holds values of both types, or `None` when
+    /// they conflict. Integers widen to floats, dates to date-times, any
+    /// date or time type to a string, and anything to `Json`.
+    pub fn widen(&self, other: &DataType) -> Option<DataType> {
+        use DataType::*;
+        match (self, other) {
+            (a, b) if a == b => Some(a.clone()),
+            (Json, _) | (_, Json) => Some(Json),
+            (Integer, Float) | (Float, Integer) => Some(Float),
+            (Date | DateTime | Timestamp, Date | DateTime | Timestamp) => Some(DateTime),
+            (String, Date | DateTime | Timestamp | Bytes) | (Date | DateTime | Timestamp | Bytes, String) => {
+                Some(String)
+            }
+            (Array(a), Array(b)) => a.widen(b).map(|item| Array(Box::new(item))),
+            _ => None,
+        }
+    }
+}
+
+/// The type a value fits without parsing it, or `None` for null. Strings
+/// stay `String` even if they hold a number or date.
+fn value_data_type(value: &Value) -> Option<DataType> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(_) => DataType::Boolean,
+        Value::Number(n) if n.is_f64() => DataType::Float,
+        Value::Number(_) => DataType::Integer,
+        Value::String(_) => DataType::String,
+        Value::Array(items) => {
+            let item_type = items
+                .iter()
+                .filter_map(value_data_type)
+                .reduce(|a, b| a.widen(&b).unwrap_or(DataType::Json))
+                .unwrap_or(DataType::Json);
+            DataType::Array(Box::new(item_type))
+        }
+        Value::Object(_) => DataType::Json,
+    })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,4 +97,247 @@ impl Schema {
     pub fn field_names(&self) -> Vec<&str> {
         self.fields.iter().map(|f| f.name.as_str()).collect()
     }
-}
\ No newline at end of file
+
+    /// Unions the fields of both schemas: this schema's fields in order,
+    /// then those only `other` has. A field in both gets the type
+    /// [`DataType::widen`] gives, and a field missing from either side is
+    /// nullable. Types that cannot be widened are an error.
+    pub fn merge(&self, other: &Schema) -> Result<Schema> {
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let Some(theirs) = other.get_field(&field.name) else {
+                fields.push(Field { nullable: true, ..field.clone() });
+                continue;
+            };
+            let data_type = field.data_type.widen(&theirs.data_type).ok_or_else(|| {
+                PipelineError::Schema(format!(
+                    "Field '{}' has conflicting types {:?} and {:?}",
+                    field.name, field.data_type, theirs.data_type
+                ))
+            })?;
+            fields.push(Field {
+                name: field.name.clone(),
+                data_type,
+                nullable: field.nullable || theirs.nullable,
+                description: field.description.clone().or_else(|| theirs.description.clone()),
+            });
+        }
+        for field in &other.fields {
+            if self.get_field(&field.name).is_none() {
+                fields.push(Field { nullable: true, ..field.clone() });
+            }
+        }
+
+        let mut metadata = other.metadata.clone();
+        metadata.extend(self.metadata.clone());
+        Ok(Schema { fields, metadata })
+    }
+
+    /// Infers a schema from sample records, listing fields in the order
+    /// they first appear. Each field gets the narrowest type every non-null
+    /// value fits, combining types with [`DataType::widen`]: integers with
+    /// floats are `Float`, and types that do not widen, such as strings with
+    /// numbers, fall back to `Json`, as do objects and fields that are only
+    /// ever null; an empty array has no item type to go on, so it counts as
+    /// `Array(Json)`. A field is nullable if any record lacks it or has
+    /// null.
+    pub fn infer_from_records(records: &[Record]) -> Schema {
+        // field -> (type of the non-null values so far, whether nullable)
+        let mut inferred: IndexMap<&str, (Option<DataType>, bool)> = IndexMap::new();
+        for (i, record) in records.iter().enumerate() {
+            for (name, value) in &record.data {
+                // A field first seen after the first record is missing from
+                // the earlier ones.
+                let (data_type, nullable) = inferred.entry(name.as_str()).or_insert((None, i > 0));
+                match value_data_type(value) {
+                    None => *nullable = true,
+                    Some(found) => {
+                        *data_type = Some(match data_type.take() {
+                            None => found,
+                            Some(current) => current.widen(&found).unwrap_or(DataType::Json),
+                        });
+                    }
+                }
+            }
+            for (name, (_, nullable)) in inferred.iter_mut() {
+                *nullable |= !record.data.contains_key(*name);
+            }
+        }
+
+        let fields = inferred
+            .into_iter()
+            .map(|(name, (data_type, nullable))| Field {
+                name: name.to_string(),
+                data_type: data_type.unwrap_or(DataType::Json),
+                nullable,
+                description: None,
+            })
+            .collect();
+        Schema::new(fields)
+    }
+
+    /// Reports how `other` differs from this schema, in field order.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        SchemaDiff {
+            added: other
+                .fields
+                .iter()
+                .filter(|field| self.get_field(&field.name).is_none())
+                .cloned()
+                .collect(),
+            removed: self
+                .fields
+                .iter()
+                .filter(|field| other.get_field(&field.name).is_none())
+                .cloned()
+                .collect(),
+            retyped: self
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    let theirs = other.get_field(&field.name)?;
+                    (theirs.data_type != field.data_type).then(|| FieldChange {
+                        name: field.name.clone(),
+                        from: field.data_type.clone(),
+                        to: theirs.data_type.clone(),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A field whose type differs between two schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub name: String,
+    pub from: DataType,
+    pub to: DataType,
+}
+
+/// The result of [`Schema::diff`].
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDiff {
+    /// Fields only the other schema has.
+    pub added: Vec<Field>,
+    /// Fields only this schema has.
+    pub removed: Vec<Field>,
+    pub retyped: Vec<FieldChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.retyped.is_empty()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn field(name: &str, data_type: DataType, nullable: bool) -> Field {
+        Field {
+            name: name.to_string(),
+            data_type,
+            nullable,
+            description: None,
+        }
+    }
+
+    fn names_and_types(schema: &Schema) -> Vec<(&str, DataType, bool)> {
+        schema.fields.iter().map(|f| (f.name.as_str(), f.data_type.clone(), f.nullable)).collect()
+    }
+
+    #[test]
+    fn merge_widens_conflicting_types() {
+        let ours = Schema::new(vec![
+            field("amount", DataType::Integer, false),
+            field("seen", DataType::Date, false),
+        ]);
+        let theirs = Schema::new(vec![
+            field("amount", DataType::Float, false),
+            field("seen", DataType::Timestamp, true),
+        ]);
+
+        let merged = ours.merge(&theirs).unwrap();
+        assert_eq!(
+            names_and_types(&merged),
+            [("amount", DataType::Float, false), ("seen", DataType::DateTime, true)]
+        );
+    }
+
+    #[test]
+    fn merge_rejects_types_that_do_not_widen() {
+        let ours = Schema::new(vec![field("id", DataType::Integer, false)]);
+        let theirs = Schema::new(vec![field("id", DataType::Boolean, false)]);
+
+        let error = ours.merge(&theirs).unwrap_err();
+        assert!(matches!(error, PipelineError::Schema(ref e) if e.contains("'id'")), "{}", error);
+    }
+
+    #[test]
+    fn merge_makes_fields_missing_from_one_side_nullable() {
+        let ours = Schema::new(vec![field("id", DataType::Integer, false), field("name", DataType::String, false)]);
+        let theirs = Schema::new(vec![field("id", DataType::Integer, false), field("email", DataType::String, false)]);
+
+        let merged = ours.merge(&theirs).unwrap();
+        assert_eq!(
+            names_and_types(&merged),
+            [
+                ("id", DataType::Integer, false),
+                ("name", DataType::String, true),
+                ("email", DataType::String, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn infer_widens_numbers_and_marks_missing_fields_nullable() {
+        let records = [
+            record(json!({"id": 1, "ratio": 1, "score": 1, "tags": ["a"]})),
+            record(json!({"id": 2, "ratio": 0.5, "score": 2.5, "tags": ["b"], "note": null})),
+            record(json!({"id": 3, "ratio": 2, "score": "high", "tags": [], "note": "late"})),
+        ];
+
+        let schema = Schema::infer_from_records(&records);
+        assert_eq!(
+            names_and_types(&schema),
+            [
+                ("id", DataType::Integer, false),
+                ("ratio", DataType::Float, false),
+                ("score", DataType::Json, false),
+                // The empty array counts as `Array(Json)`.
+                ("tags", DataType::Array(Box::new(DataType::Json)), false),
+                ("note", DataType::String, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_retyped_fields() {
+        let before = Schema::new(vec![
+            field("id", DataType::Integer, false),
+            field("name", DataType::String, true),
+            field("legacy", DataType::String, true),
+        ]);
+        let after = Schema::new(vec![
+            field("id", DataType::Float, false),
+            field("name", DataType::String, false),
+            field("email", DataType::String, true),
+        ]);
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["email"]);
+        assert_eq!(diff.removed.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["legacy"]);
+        assert_eq!(
+            diff.retyped,
+            [FieldChange {
+                name: "id".to_string(),
+                from: DataType::Integer,
+                to: DataType::Float,
+            }]
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+}
@@ -36,20 +36,81 @@ pub trait Sink: Send + Sync {
     }
 }
 
+/// A sink whose writes can be grouped into an all-or-nothing transaction,
+/// typically backed by a database transaction. See
+/// [`TransactionalSink`](crate::sink::transactional::TransactionalSink).
+#[async_trait]
+pub trait Transactional: Sink {
+    /// Starts a transaction; writes and flushes until `commit` or `rollback`
+    /// belong to it.
+    async fn begin(&mut self) -> Result<()>;
+
+    async fn commit(&mut self) -> Result<()>;
+
+    /// Discards everything written since `begin`.
+    async fn rollback(&mut self) -> Result<()>;
+}
+
 #[async_trait]
 pub trait Transform: Send + Sync {
     async fn transform(&self, record: Record) -> Result<Vec<Record>>;
+
+    /// Transforms a batch of records at once, as `Pipeline::run_batched`
+    /// does. The default calls `transform` on each record in turn;
+    /// transforms with a per-call cost, such as a remote lookup, can
+    /// override it to make one call per batch.
+    async fn transform_batch(&self, records: Vec<Record>) -> Result<Vec<Record>> {
+        let mut outputs = Vec::with_capacity(records.len());
+        for record in records {
+            outputs.extend(self.transform(record).await?);
+        }
+        Ok(outputs)
+    }
     
     async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema>;
+
+    /// Called once after the last input record; returns any records the
+    /// transform was still holding back, such as a buffered window.
+    async fn flush(&self) -> Result<Vec<Record>> {
+        Ok(vec![])
+    }
+
+    /// Whether the transform holds records back to release them later, from
+    /// `transform` or `flush`, as a sort or a window does. An input such a
+    /// transform returns nothing for is held rather than filtered out, so the
+    /// pipeline does not count it in `records_filtered`.
+    fn buffers(&self) -> bool {
+        false
+    }
+    
+    fn name(&self) -> String {
+        let type_name = std::any::type_name::<Self>();
+        let base = type_name.split('<').next().unwrap_or(type_name);
+        base.rsplit("::").next().unwrap_or(base).to_string()
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SourceMode {
     Batch,
     Stream,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SinkMode {
     Append,
     Overwrite,
     Update,
+}
+
+/// What a transform does with a record it cannot process.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ErrorPolicy {
+    /// Return a `PipelineError`, stopping the pipeline.
+    #[default]
+    Fail,
+    /// Set the fields the transform would have produced to `Value::Null`.
+    NullFill,
+    /// Pass the record through unchanged with the reason in its `error` metadata.
+    DeadLetter,
 }
\ No newline at end of file
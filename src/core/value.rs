@@ -0,0 +1,80 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Total ordering over JSON values used for sorting and merging: nulls sort
+/// first, then booleans, numbers (compared numerically), strings, arrays and
+/// objects. Values of the same kind compare naturally; arrays element by
+/// element and objects entry by entry in key order, then by length, so
+/// objects compare the same whatever order their fields were set in.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a
+                .as_f64()
+                .unwrap_or(f64::NAN)
+                .total_cmp(&b.as_f64().unwrap_or(f64::NAN)),
+        },
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => {
+            for (a, b) in a.iter().zip(b.iter()) {
+                let ordering = compare_values(a, b);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            let mut a: Vec<_> = a.iter().collect();
+            let mut b: Vec<_> = b.iter().collect();
+            a.sort_by(|x, y| x.0.cmp(y.0));
+            b.sort_by(|x, y| x.0.cmp(y.0));
+            for ((a_key, a_value), (b_key, b_value)) in a.iter().zip(b.iter()) {
+                let ordering = a_key.cmp(b_key).then_with(|| compare_values(a_value, b_value));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Reads a timestamp as milliseconds since the Unix epoch. Numbers are taken
+/// to already be epoch milliseconds; strings may be RFC 3339, or a naive
+/// `YYYY-MM-DD[T| ]HH:MM:SS[.fff]` or `YYYY-MM-DD` interpreted as UTC.
+pub fn timestamp_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+                return Some(dt.timestamp_millis());
+            }
+            for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+                if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, format) {
+                    return Some(dt.and_utc().timestamp_millis());
+                }
+            }
+            chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc().timestamp_millis())
+        }
+        _ => None,
+    }
+}
@@ -1,7 +1,13 @@
 pub mod core;
 pub mod source;
 pub mod sink;
+pub mod transform;
 pub mod pipeline;
 
 pub use crate::core::*;
-pub use crate::pipeline::Pipeline;
\ No newline at end of file
+pub use crate::pipeline::{Pipeline, PipelineStats, ValidationMode};
+
+// Which helpers are used depends on the features enabled.
+#[cfg(test)]
+#[allow(dead_code)]
+mod test_support;
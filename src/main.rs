@@ -8,9 +8,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let pipeline = Pipeline::new(source, vec![], sink);
     
-    pipeline.run().await?;
+    let stats = pipeline.run().await?;
     
-    println!("Data pipeline completed successfully!");
+    println!("Data pipeline completed successfully: {}", stats);
     
     Ok(())
 }
@@ -1,9 +1,571 @@
-use crate::core::{Source, Sink, Transform, Result};
+use crate::core::{
+    PipelineError, ProgressEvent, ProgressReporter, Record, RecordStream, RetryPolicy, Schema, Source, Sink, Transform,
+    Result,
+};
+use futures::{Stream, StreamExt};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
-pub struct Pipeline {
-    source: Box<dyn Source>,
+#[derive(Debug, Clone, Default)]
+pub struct PipelineStats {
+    /// Records produced by the source.
+    pub records_read: u64,
+    /// Records handed to a sink, summed over branches.
+    pub records_written: u64,
+    /// Inputs, per branch, of which no record survived the transforms. An
+    /// input a buffering transform holds back (see [`Transform::buffers`])
+    /// is not filtered, though records it releases later may be.
+    pub records_filtered: u64,
+    /// Records a transform dead-lettered by setting `error` metadata and
+    /// passing them on, plus, with a dead-letter sink, records a transform
+    /// returned an error for. Without one such an error stops the run and
+    /// is returned instead.
+    pub transform_errors: u64,
+    /// Records written to the dead-letter sink.
+    pub records_dead_lettered: u64,
+    /// Wall-clock time of the whole run.
+    pub elapsed: Duration,
+    /// Cumulative time spent in each stage, in pipeline order: the source
+    /// read, every transform, then the sink. With several branches each
+    /// branch's transform and sink stages are prefixed with `branch[i].`.
+    pub stage_durations: Vec<(String, Duration)>,
+}
+
+impl PipelineStats {
+    pub fn stage_duration(&self, stage_name: &str) -> Option<Duration> {
+        self.stage_durations
+            .iter()
+            .find(|(name, _)| name == stage_name)
+            .map(|(_, duration)| *duration)
+    }
+
+    pub fn slowest_stage(&self) -> Option<&(String, Duration)> {
+        self.stage_durations.iter().max_by_key(|(_, duration)| *duration)
+    }
+}
+
+impl fmt::Display for PipelineStats {
+    /// A one-line summary, such as `read 100, wrote 85, filtered 15, 0
+    /// transform errors in 2.54s`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "read {}, wrote {}, filtered {}, {} transform errors in {:.2?}",
+            self.records_read, self.records_written, self.records_filtered, self.transform_errors, self.elapsed
+        )
+    }
+}
+
+/// One branch of a pipeline: the transforms a record passes through and the
+/// sink it ends up in.
+pub type BranchSpec = (Vec<Box<dyn Transform>>, Box<dyn Sink>);
+
+struct Branch {
     transforms: Vec<Box<dyn Transform>>,
+    /// Each transform's name in the stats and tracing spans.
+    stage_names: Vec<String>,
+    output: BranchOutput,
+}
+
+/// Everything a branch updates once a record's transforms have run, kept
+/// apart from the transforms so those can be shared by concurrent records.
+struct BranchOutput {
+    sink: Box<dyn Sink>,
+    sink_name: String,
+    transform_times: Vec<Duration>,
+    sink_time: Duration,
+    records_written: u64,
+    records_filtered: u64,
+    transform_errors: u64,
+    retry: Option<RetryPolicy>,
+}
+
+impl Branch {
+    /// `prefix` qualifies the stage names when there are several branches.
+    fn new(transforms: Vec<Box<dyn Transform>>, sink: Box<dyn Sink>, prefix: &str) -> Self {
+        Self {
+            stage_names: transforms
+                .iter()
+                .enumerate()
+                .map(|(i, transform)| format!("{}{}[{}]", prefix, transform.name(), i))
+                .collect(),
+            output: BranchOutput {
+                sink,
+                sink_name: format!("{}sink", prefix),
+                transform_times: vec![Duration::ZERO; transforms.len()],
+                sink_time: Duration::ZERO,
+                records_written: 0,
+                records_filtered: 0,
+                transform_errors: 0,
+                retry: None,
+            },
+            transforms,
+        }
+    }
+
+    async fn process(&mut self, record: Record, trace: Option<TraceScope>, dead_letter: Option<&mut DeadLetter>) -> Result<()> {
+        self.process_from(0, record, trace, dead_letter).await
+    }
+
+    /// Runs `record` through the transforms from index `start` on, then
+    /// writes whatever survives to the sink.
+    async fn process_from(
+        &mut self,
+        start: usize,
+        record: Record,
+        trace: Option<TraceScope>,
+        dead_letter: Option<&mut DeadLetter>,
+    ) -> Result<()> {
+        let chain = Chain { transforms: &self.transforms[start..], stage_names: &self.stage_names[start..] };
+        let chain_output = apply_transforms(chain, record, dead_letter.is_some(), trace).await?;
+        self.output.accept(start, chain_output, trace, dead_letter).await
+    }
+
+    /// Runs `records` through the transforms from index `start` on as one
+    /// batch, then writes whatever survives to the sink in one batch. When
+    /// dead-lettering, a transform error sets aside the whole batch it
+    /// failed on.
+    async fn process_batch_from(
+        &mut self,
+        start: usize,
+        records: Vec<Record>,
+        trace: Option<TraceScope>,
+        dead_letter: Option<&mut DeadLetter>,
+    ) -> Result<()> {
+        let already_failed = count_failed(&records);
+        let mut records = records;
+        let mut failed = Vec::new();
+        // Records are filtered by how many fewer leave each run of
+        // transforms than enter it, where a buffering transform starts a new
+        // run since what it releases are not the records it was given.
+        let mut filtered = 0;
+        let mut run_len = records.len() as u64;
+        for (i, transform) in self.transforms.iter().enumerate().skip(start) {
+            if transform.buffers() {
+                filtered += run_len.saturating_sub(records.len() as u64);
+                run_len = records.len() as u64;
+            }
+            let started = Instant::now();
+            let input = if dead_letter.is_some() {
+                records.clone()
+            } else {
+                std::mem::take(&mut records)
+            };
+            let result = traced(trace, &self.stage_names[i], transform.transform_batch(input)).await;
+            self.output.transform_times[i] += started.elapsed();
+            match result {
+                Ok(transformed) => {
+                    records = transformed;
+                    if transform.buffers() {
+                        run_len = records.len() as u64;
+                    }
+                }
+                Err(e) if dead_letter.is_some() => {
+                    let error = format!("{}: {}", transform.name(), e);
+                    for mut record in std::mem::take(&mut records) {
+                        record.set_metadata("error".to_string(), error.clone());
+                        failed.push(record);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+            if records.is_empty() {
+                break;
+            }
+        }
+
+        filtered += run_len.saturating_sub((records.len() + failed.len()) as u64);
+        self.output.transform_errors += count_failed(&records).saturating_sub(already_failed);
+        self.output.accept_batch(filtered, records, failed, trace, dead_letter).await
+    }
+
+    /// Drains records held back by each transform, in order, through the
+    /// stages after it, as one batch per transform when `batched`.
+    async fn flush_transforms(
+        &mut self,
+        mut dead_letter: Option<&mut DeadLetter>,
+        batched: bool,
+        tracing: bool,
+    ) -> Result<()> {
+        for i in 0..self.transforms.len() {
+            let started = Instant::now();
+            let trace = tracing.then_some(TraceScope::flushed(0));
+            let flushed = traced(trace, &self.stage_names[i], self.transforms[i].flush()).await?;
+            self.output.transform_times[i] += started.elapsed();
+            if batched {
+                if !flushed.is_empty() {
+                    let trace = tracing.then_some(TraceScope::flushed(flushed.len()));
+                    self.process_batch_from(i + 1, flushed, trace, dead_letter.as_deref_mut()).await?;
+                }
+                continue;
+            }
+            for record in flushed {
+                let trace = tracing.then_some(TraceScope::flushed(1));
+                self.process_from(i + 1, record, trace, dead_letter.as_deref_mut()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self, tracing: bool) -> Result<()> {
+        let started = Instant::now();
+        let trace = tracing.then_some(TraceScope::flushed(0));
+        traced(trace, &self.output.sink_name, self.output.sink.close()).await?;
+        self.output.sink_time += started.elapsed();
+        Ok(())
+    }
+}
+
+impl BranchOutput {
+    /// Records the outcome of the transforms from index `start` on and
+    /// writes the surviving records to the sink. With a dead-letter sink,
+    /// records carrying `error` metadata and records the sink rejects go
+    /// there instead.
+    async fn accept(
+        &mut self,
+        start: usize,
+        chain_output: ChainOutput,
+        trace: Option<TraceScope>,
+        mut dead_letter: Option<&mut DeadLetter>,
+    ) -> Result<()> {
+        for (total, elapsed) in self.transform_times[start..].iter_mut().zip(chain_output.durations) {
+            *total += elapsed;
+        }
+        self.transform_errors += chain_output.dead_lettered + chain_output.failed.len() as u64;
+        if chain_output.records.is_empty() && chain_output.failed.is_empty() {
+            if !chain_output.held {
+                self.records_filtered += 1;
+            }
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        if let Some(ref mut dead_letter) = dead_letter {
+            for record in chain_output.failed {
+                dead_letter.write(record).await?;
+            }
+        }
+        for record in chain_output.records {
+            let Some(ref mut dead_letter) = dead_letter else {
+                self.traced_write(record, trace).await?;
+                self.records_written += 1;
+                continue;
+            };
+
+            if record.get_metadata("error").is_some() {
+                dead_letter.write(record).await?;
+                continue;
+            }
+            match self.traced_write(record.clone(), trace).await {
+                Ok(()) => self.records_written += 1,
+                Err(e) => {
+                    let mut record = record;
+                    record.set_metadata("error".to_string(), e.to_string());
+                    dead_letter.write(record).await?;
+                }
+            }
+        }
+        self.sink_time += started.elapsed();
+        Ok(())
+    }
+
+    /// Like `accept`, for a batch whose transforms filtered out `filtered`
+    /// records and left `records`, with `failed` set aside by a transform
+    /// error. When the sink rejects the batch, every record in it is
+    /// dead-lettered.
+    async fn accept_batch(
+        &mut self,
+        filtered: u64,
+        records: Vec<Record>,
+        failed: Vec<Record>,
+        trace: Option<TraceScope>,
+        dead_letter: Option<&mut DeadLetter>,
+    ) -> Result<()> {
+        self.transform_errors += failed.len() as u64;
+        self.records_filtered += filtered;
+
+        let started = Instant::now();
+        let Some(dead_letter) = dead_letter else {
+            if !records.is_empty() {
+                let count = records.len() as u64;
+                self.traced_write_batch(records, trace).await?;
+                self.records_written += count;
+            }
+            self.sink_time += started.elapsed();
+            return Ok(());
+        };
+
+        let (rejected, records): (Vec<Record>, Vec<Record>) =
+            records.into_iter().partition(|record| record.get_metadata("error").is_some());
+        for record in failed.into_iter().chain(rejected) {
+            dead_letter.write(record).await?;
+        }
+        if !records.is_empty() {
+            match self.traced_write_batch(records.clone(), trace).await {
+                Ok(()) => self.records_written += records.len() as u64,
+                Err(e) => {
+                    let error = e.to_string();
+                    for mut record in records {
+                        record.set_metadata("error".to_string(), error.clone());
+                        dead_letter.write(record).await?;
+                    }
+                }
+            }
+        }
+        self.sink_time += started.elapsed();
+        Ok(())
+    }
+
+    async fn traced_write(&mut self, record: Record, trace: Option<TraceScope>) -> Result<()> {
+        let Some(trace) = trace else {
+            return self.write(record).await;
+        };
+        let sink_name = self.sink_name.clone();
+        traced(Some(trace), &sink_name, self.write(record)).await
+    }
+
+    async fn traced_write_batch(&mut self, records: Vec<Record>, trace: Option<TraceScope>) -> Result<()> {
+        let Some(trace) = trace else {
+            return self.write_batch(records).await;
+        };
+        let sink_name = self.sink_name.clone();
+        traced(Some(trace), &sink_name, self.write_batch(records)).await
+    }
+
+    /// Writes `records` with one `write_batch`, retrying as `write` does.
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        let Some(ref retry) = self.retry else {
+            return self.sink.write_batch(records).await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self.sink.write_batch(records.clone()).await {
+                Err(PipelineError::Sink(reason)) if attempt < retry.max_attempts => {
+                    tracing::warn!("Sink batch write failed (attempt {}): {}", attempt, reason);
+                    tokio::time::sleep(retry.delay_for(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Writes `record`, retrying `PipelineError::Sink` failures under the
+    /// retry policy, if any. Other errors, such as schema errors, would only
+    /// fail again and are returned at once.
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let Some(ref retry) = self.retry else {
+            return self.sink.write(record).await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self.sink.write(record.clone()).await {
+                Err(PipelineError::Sink(reason)) if attempt < retry.max_attempts => {
+                    tracing::warn!("Sink write failed (attempt {}): {}", attempt, reason);
+                    tokio::time::sleep(retry.delay_for(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// The sink that records a transform or branch sink failed on are written
+/// to, with the failure in their `error` metadata.
+struct DeadLetter {
     sink: Box<dyn Sink>,
+    records: u64,
+}
+
+impl DeadLetter {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.sink.write(record).await?;
+        self.records += 1;
+        Ok(())
+    }
+}
+
+struct ChainOutput {
+    records: Vec<Record>,
+    /// Records a transform returned an error for, with the error in their
+    /// `error` metadata. Only collected when dead-lettering.
+    failed: Vec<Record>,
+    /// Time spent in each transform that ran.
+    durations: Vec<Duration>,
+    /// Records that picked up `error` metadata along the way.
+    dead_lettered: u64,
+    /// Whether the records ran out at a transform that holds records back,
+    /// so the input was held rather than filtered out.
+    held: bool,
+}
+
+fn records_written(branches: &[Branch]) -> u64 {
+    branches.iter().map(|branch| branch.output.records_written).sum()
+}
+
+fn count_failed(records: &[Record]) -> u64 {
+    records.iter().filter(|record| record.get_metadata("error").is_some()).count() as u64
+}
+
+/// A run of a branch's transforms with their stage names.
+#[derive(Clone, Copy)]
+struct Chain<'a> {
+    transforms: &'a [Box<dyn Transform>],
+    stage_names: &'a [String],
+}
+
+/// The records a traced stage call works on, for its span: `records` of
+/// them starting at zero-based source position `record`, which is unknown
+/// for records a transform released on flush.
+#[derive(Clone, Copy)]
+struct TraceScope {
+    record: Option<u64>,
+    records: usize,
+}
+
+impl TraceScope {
+    fn record(index: u64) -> Self {
+        Self { record: Some(index), records: 1 }
+    }
+
+    fn batch(first: u64, records: usize) -> Self {
+        Self { record: Some(first), records }
+    }
+
+    fn flushed(records: usize) -> Self {
+        Self { record: None, records }
+    }
+}
+
+/// Awaits `future`, inside a span for `stage` when tracing, then emits an
+/// event with its duration in microseconds. The span is attached with
+/// `Instrument`, so it is entered only while the future is polled and never
+/// held across an await.
+async fn traced<F: Future>(trace: Option<TraceScope>, stage: &str, future: F) -> F::Output {
+    let Some(scope) = trace else {
+        return future.await;
+    };
+    let span = tracing::debug_span!("stage", stage, record = tracing::field::Empty, records = scope.records);
+    if let Some(record) = scope.record {
+        span.record("record", record);
+    }
+    let started = Instant::now();
+    let output = future.instrument(span.clone()).await;
+    let duration_us = started.elapsed().as_micros() as u64;
+    span.in_scope(|| tracing::debug!(stage, duration_us, "stage finished"));
+    output
+}
+
+/// Applies each transform to every record the previous one produced, so one
+/// input may fan out into many outputs or be filtered out entirely. With
+/// `catch_errors` a transform error sets aside the record it failed on
+/// rather than ending the run.
+async fn apply_transforms(
+    chain: Chain<'_>,
+    record: Record,
+    catch_errors: bool,
+    trace: Option<TraceScope>,
+) -> Result<ChainOutput> {
+    let already_failed = record.get_metadata("error").is_some();
+    let mut records = vec![record];
+    let mut failed = Vec::new();
+    let mut durations = Vec::with_capacity(chain.transforms.len());
+    let mut held = false;
+    for (transform, stage_name) in chain.transforms.iter().zip(chain.stage_names) {
+        let started = Instant::now();
+        let mut outputs = Vec::with_capacity(records.len());
+        for record in records {
+            if !catch_errors {
+                outputs.extend(traced(trace, stage_name, transform.transform(record)).await?);
+                continue;
+            }
+            match traced(trace, stage_name, transform.transform(record.clone())).await {
+                Ok(transformed) => outputs.extend(transformed),
+                Err(e) => {
+                    let mut record = record;
+                    record.set_metadata("error".to_string(), format!("{}: {}", transform.name(), e));
+                    failed.push(record);
+                }
+            }
+        }
+        durations.push(started.elapsed());
+        records = outputs;
+        if records.is_empty() {
+            held = transform.buffers();
+            break;
+        }
+    }
+
+    let dead_lettered = if already_failed {
+        0
+    } else {
+        count_failed(&records)
+    };
+    Ok(ChainOutput { records, failed, durations, dead_lettered, held })
+}
+
+/// Adds the time spent polling `inner` to `elapsed`.
+struct TimedStream<'a, S> {
+    inner: S,
+    elapsed: &'a mut Duration,
+}
+
+impl<S: Stream + Unpin> Stream for TimedStream<'_, S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        let started = Instant::now();
+        let next = self.inner.poll_next_unpin(cx);
+        *self.elapsed += started.elapsed();
+        next
+    }
+}
+
+/// Whether `run` checks each record against the source schema.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ValidationMode {
+    #[default]
+    Off,
+    /// Log a warning and skip records that do not match.
+    WarnAndContinue,
+    /// Stop the run with the first mismatch.
+    FailFast,
+}
+
+impl ValidationMode {
+    /// Returns whether `record` should continue through the pipeline.
+    fn check(self, record: &Record, schema: Option<&Schema>) -> Result<bool> {
+        let Some(schema) = schema else {
+            return Ok(true);
+        };
+        match (record.validate_against_schema(schema), self) {
+            (Ok(()), _) | (Err(_), ValidationMode::Off) => Ok(true),
+            (Err(e), ValidationMode::WarnAndContinue) => {
+                tracing::warn!("Skipping record that does not match the source schema: {}", e);
+                Ok(false)
+            }
+            (Err(e), ValidationMode::FailFast) => Err(e),
+        }
+    }
+}
+
+pub struct Pipeline {
+    source: Box<dyn Source>,
+    branches: Vec<Branch>,
+    contract: Option<Schema>,
+    validation: ValidationMode,
+    dead_letter: Option<DeadLetter>,
+    progress: ProgressReporter,
+    tracing: bool,
 }
 
 impl Pipeline {
@@ -12,34 +574,1099 @@ impl Pipeline {
         transforms: Vec<Box<dyn Transform>>,
         sink: Box<dyn Sink>,
     ) -> Self {
+        Self::with_branches(source, vec![(transforms, sink)])
+    }
+
+    /// Reads `source` once and feeds a copy of every record through each
+    /// branch's transforms into that branch's sink.
+    pub fn with_branches(
+        source: Box<dyn Source>,
+        branches: Vec<BranchSpec>,
+    ) -> Self {
+        let multiple = branches.len() > 1;
         Self {
             source,
-            transforms,
-            sink,
+            branches: branches
+                .into_iter()
+                .enumerate()
+                .map(|(b, (transforms, sink))| {
+                    let prefix = if multiple { format!("branch[{}].", b) } else { String::new() };
+                    Branch::new(transforms, sink, &prefix)
+                })
+                .collect(),
+            contract: None,
+            validation: ValidationMode::Off,
+            dead_letter: None,
+            progress: ProgressReporter::default(),
+            tracing: false,
+        }
+    }
+
+    /// Requires the schema each branch produces, as computed from the
+    /// source schema and every transform's `get_output_schema`, to match
+    /// `expected`. `run` checks this before reading any data.
+    pub fn with_contract(mut self, expected: Schema) -> Self {
+        self.contract = Some(expected);
+        self
+    }
+
+    /// Checks every record read against the source schema, which is fetched
+    /// once when the run starts.
+    pub fn with_validation(mut self, mode: ValidationMode) -> Self {
+        self.validation = mode;
+        self
+    }
+
+    /// Retries sink writes that fail with `PipelineError::Sink`, backing off
+    /// between attempts. Once `max_attempts` writes have failed the last
+    /// error stops the run.
+    ///
+    /// A write can only be retried safely if, when it fails, the sink has
+    /// not kept its record. The buffering sinks in this crate implement
+    /// `BufferedWrite` (in `sink::buffered`), which keeps the buffer when
+    /// sending it fails and hands back the record that triggered the send,
+    /// so a retried write sends every buffered record and adds its own once;
+    /// a custom buffering sink must do the same to be retried safely. A
+    /// `write_batch` that fails partway may already have sent part of the
+    /// batch, which its retry then sends again.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        for branch in self.branches.iter_mut() {
+            branch.output.retry = Some(retry.clone());
+        }
+        self
+    }
+
+    /// Writes records that fail instead of stopping the run: a record a
+    /// transform returns an error for, or the branch sink rejects (after
+    /// any retries), goes to `sink` with the failure in its `error`
+    /// metadata, as do records a transform dead-lettered itself. An error
+    /// from `sink` still stops the run.
+    pub fn with_dead_letter(mut self, sink: Box<dyn Sink>) -> Self {
+        self.dead_letter = Some(DeadLetter { sink, records: 0 });
+        self
+    }
+
+    /// Sends running totals to `tx` while the pipeline runs, by default
+    /// every 1000 records or every second, and a final event once it has
+    /// finished. Events during the run are sent with `try_send` and dropped
+    /// when the channel is full, so a slow receiver does not hold up the
+    /// run; the final event waits for room instead, so the run only returns
+    /// once the receiver has taken it or been dropped.
+    pub fn with_progress(mut self, tx: mpsc::Sender<ProgressEvent>) -> Self {
+        self.progress.set_channel(tx);
+        self
+    }
+
+    /// Sends progress events every `records` records read or every
+    /// `interval`, whichever comes first.
+    pub fn with_progress_interval(mut self, records: u64, interval: Duration) -> Self {
+        self.progress.every_records = records.max(1);
+        self.progress.every = interval;
+        self
+    }
+
+    /// Runs every transform call and sink write inside a `debug`-level
+    /// `tracing` span named `stage`, with the stage name as in the stats
+    /// and the zero-based source position of the record, then emits a
+    /// `stage finished` event carrying `duration_us`, which a subscriber can
+    /// feed into a histogram. Batched runs give the first record of the
+    /// batch and the batch size in `records`; records released on flush have
+    /// no position. Without this, no spans are created at all.
+    pub fn with_tracing(mut self) -> Self {
+        self.tracing = true;
+        self
+    }
+
+    async fn validation_schema(&self) -> Result<Option<Schema>> {
+        match self.validation {
+            ValidationMode::Off => Ok(None),
+            _ => self.source.get_schema().await.map(Some),
+        }
+    }
+
+    /// Checks the output schema contract, if any, without running the
+    /// pipeline. The error lists every difference found.
+    pub async fn validate(&self) -> Result<()> {
+        let Some(ref contract) = self.contract else {
+            return Ok(());
+        };
+
+        let source_schema = self.source.get_schema().await?;
+        let multiple = self.branches.len() > 1;
+        let mut problems = Vec::new();
+        for (b, branch) in self.branches.iter().enumerate() {
+            let mut schema = source_schema.clone();
+            for transform in &branch.transforms {
+                schema = transform.get_output_schema(&schema).await?;
+            }
+            let prefix = if multiple { format!("branch[{}]: ", b) } else { String::new() };
+            problems.extend(contract_mismatches(contract, &schema).into_iter().map(|p| format!("{}{}", prefix, p)));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(PipelineError::Schema(format!(
+                "Output schema does not match the contract:\n  {}",
+                problems.join("\n  ")
+            )))
         }
     }
     
-    pub async fn run(mut self) -> Result<()> {
-        let mut stream = self.source.read().await?;
+    pub async fn run(self) -> Result<PipelineStats> {
+        self.run_stream(|stream| stream).await
+    }
+
+    /// Like `run`, but stops reading from the source once `token` is
+    /// cancelled. The record being processed at that point is still
+    /// written, and the transforms are drained and the sinks flushed and
+    /// closed as at the end of the source, so the stats cover a clean,
+    /// partial run.
+    pub async fn run_with_cancel(self, token: CancellationToken) -> Result<PipelineStats> {
+        self.run_stream(|stream| stream.take_until(token.cancelled_owned()).boxed()).await
+    }
+
+    /// Like `run`, but reads at most `limit` records from the source. Unlike
+    /// a [`LimitTransform`](crate::transform::limit::LimitTransform), which
+    /// can only discard records once they have been read, this stops pulling
+    /// from the source, so an expensive source such as a paginated API or a
+    /// large file is not read to the end.
+    pub async fn run_limited(self, limit: usize) -> Result<PipelineStats> {
+        self.run_stream(|stream| stream.take(limit).boxed()).await
+    }
+
+    /// Runs the pipeline over the source stream as adapted by `adapt`.
+    async fn run_stream(mut self, adapt: impl FnOnce(RecordStream) -> RecordStream) -> Result<PipelineStats> {
+        let run_started = Instant::now();
+        self.validate().await?;
+        let source_schema = self.validation_schema().await?;
+        let mut source_time = Duration::ZERO;
+        let mut records_read = 0;
+
+        let started = Instant::now();
+        let mut stream = adapt(self.source.read().await?);
+        source_time += started.elapsed();
         
-        while let Some(record_result) = futures::StreamExt::next(&mut stream).await {
-            let mut record = record_result?;
-            
-            for transform in &self.transforms {
-                let transformed = transform.transform(record.clone()).await?;
-                if let Some(first_record) = transformed.into_iter().next() {
-                    record = first_record;
-                } else {
-                    continue;
+        loop {
+            let started = Instant::now();
+            let next = stream.next().await;
+            source_time += started.elapsed();
+
+            let Some(record_result) = next else {
+                break;
+            };
+            let record = record_result?;
+            let trace = self.tracing.then_some(TraceScope::record(records_read));
+            records_read += 1;
+            if !self.validation.check(&record, source_schema.as_ref())? {
+                continue;
+            }
+
+            if let Some((last, rest)) = self.branches.split_last_mut() {
+                for branch in rest {
+                    branch.process(record.clone(), trace, self.dead_letter.as_mut()).await?;
                 }
+                last.process(record, trace, self.dead_letter.as_mut()).await?;
             }
-            
-            self.sink.write(record).await?;
+            self.progress.tick(run_started, records_read, || records_written(&self.branches));
+        }
+
+        self.finish(run_started, source_time, records_read, false).await
+    }
+
+    /// Like `run`, but reads the source in batches of up to `batch_size`
+    /// records, passes each batch through every transform's
+    /// `transform_batch` and writes what comes out with one `write_batch`,
+    /// which suits sinks that write in bulk such as SQL, Kafka or Arrow. The
+    /// last batch may be smaller, and records transforms hold back are
+    /// flushed as a batch per transform at the end.
+    ///
+    /// `records_filtered` counts how many fewer records a batch produced
+    /// than went in, leaving out records a buffering transform holds back.
+    /// A source error ends the run without writing the rest of
+    /// its batch.
+    pub async fn run_batched(mut self, batch_size: usize) -> Result<PipelineStats> {
+        let run_started = Instant::now();
+        self.validate().await?;
+        let source_schema = self.validation_schema().await?;
+        let mut source_time = Duration::ZERO;
+        let mut records_read = 0;
+
+        let started = Instant::now();
+        let mut chunks = self.source.read().await?.chunks(batch_size.max(1));
+        source_time += started.elapsed();
+
+        loop {
+            let started = Instant::now();
+            let next = chunks.next().await;
+            source_time += started.elapsed();
+
+            let Some(chunk) = next else {
+                break;
+            };
+            let trace = self.tracing.then_some(TraceScope::batch(records_read, chunk.len()));
+            let mut batch = Vec::with_capacity(chunk.len());
+            for record_result in chunk {
+                let record = record_result?;
+                records_read += 1;
+                if self.validation.check(&record, source_schema.as_ref())? {
+                    batch.push(record);
+                }
+            }
+            if batch.is_empty() {
+                continue;
+            }
+
+            if let Some((last, rest)) = self.branches.split_last_mut() {
+                for branch in rest {
+                    branch.process_batch_from(0, batch.clone(), trace, self.dead_letter.as_mut()).await?;
+                }
+                last.process_batch_from(0, batch, trace, self.dead_letter.as_mut()).await?;
+            }
+            self.progress.tick(run_started, records_read, || records_written(&self.branches));
+        }
+
+        self.finish(run_started, source_time, records_read, true).await
+    }
+
+    /// Like `run`, but runs the transforms of up to `max_in_flight` records
+    /// at once, which pays off when transforms wait on I/O. Sink writes stay
+    /// one at a time and in source order.
+    ///
+    /// Transforms are called concurrently, so a stateful transform such as
+    /// a session or reorder window may see records out of order. Stage
+    /// durations for transforms add up the time of overlapping calls.
+    pub async fn run_concurrent(self, max_in_flight: usize) -> Result<PipelineStats> {
+        self.run_with_concurrency(max_in_flight, true).await
+    }
+
+    /// Like [`run_concurrent`](Self::run_concurrent), but writes each
+    /// record's output as soon as its transforms finish, so records reach the
+    /// sink in completion order rather than source order. One slow record
+    /// then no longer holds back the ones behind it.
+    pub async fn run_concurrent_unordered(self, max_in_flight: usize) -> Result<PipelineStats> {
+        self.run_with_concurrency(max_in_flight, false).await
+    }
+
+    async fn run_with_concurrency(mut self, max_in_flight: usize, ordered: bool) -> Result<PipelineStats> {
+        let run_started = Instant::now();
+        self.validate().await?;
+        let source_schema = self.validation_schema().await?;
+        let validation = self.validation;
+        let catch_errors = self.dead_letter.is_some();
+        let tracing = self.tracing;
+        let max_in_flight = max_in_flight.max(1);
+        let mut source_time = Duration::ZERO;
+        let mut records_read = 0;
+
+        let started = Instant::now();
+        let stream = self.source.read().await?;
+        source_time += started.elapsed();
+
+        {
+            let (chains, mut outputs): (Vec<_>, Vec<_>) = self
+                .branches
+                .iter_mut()
+                .map(|branch| {
+                    let chain = Chain { transforms: &branch.transforms, stage_names: &branch.stage_names };
+                    (chain, &mut branch.output)
+                })
+                .collect();
+            let chains = &chains;
+            let source_schema = source_schema.as_ref();
+
+            let source = TimedStream { inner: stream, elapsed: &mut source_time };
+            let processed = source.enumerate().map(|(index, record_result)| async move {
+                let record = record_result?;
+                let trace = tracing.then_some(TraceScope::record(index as u64));
+                let mut chain_outputs = Vec::with_capacity(chains.len());
+                if !validation.check(&record, source_schema)? {
+                    return Ok((trace, chain_outputs));
+                }
+                for chain in chains {
+                    chain_outputs.push(apply_transforms(*chain, record.clone(), catch_errors, trace).await?);
+                }
+                Ok::<_, PipelineError>((trace, chain_outputs))
+            });
+            let mut processed: Pin<Box<dyn Stream<Item = Result<_>> + '_>> = if ordered {
+                Box::pin(processed.buffered(max_in_flight))
+            } else {
+                Box::pin(processed.buffer_unordered(max_in_flight))
+            };
+
+            while let Some(processed_record) = processed.next().await {
+                records_read += 1;
+                let (trace, chain_outputs) = processed_record?;
+                for (chain_output, output) in chain_outputs.into_iter().zip(outputs.iter_mut()) {
+                    output.accept(0, chain_output, trace, self.dead_letter.as_mut()).await?;
+                }
+                self.progress
+                    .tick(run_started, records_read, || outputs.iter().map(|output| output.records_written).sum());
+            }
+        }
+
+        self.finish(run_started, source_time, records_read, false).await
+    }
+
+    /// Drains and closes every branch, then the source, and collects the
+    /// stats.
+    async fn finish(
+        mut self,
+        run_started: Instant,
+        source_time: Duration,
+        records_read: u64,
+        batched: bool,
+    ) -> Result<PipelineStats> {
+        for branch in self.branches.iter_mut() {
+            branch.flush_transforms(self.dead_letter.as_mut(), batched, self.tracing).await?;
+            branch.close(self.tracing).await?;
+        }
+        if let Some(ref mut dead_letter) = self.dead_letter {
+            dead_letter.sink.close().await?;
         }
-        
-        self.sink.close().await?;
         self.source.close().await?;
         
-        Ok(())
+        let mut stats = PipelineStats {
+            records_read,
+            records_dead_lettered: self.dead_letter.as_ref().map_or(0, |dead_letter| dead_letter.records),
+            ..PipelineStats::default()
+        };
+        stats.stage_durations.push(("source".to_string(), source_time));
+        for branch in &self.branches {
+            let output = &branch.output;
+            stats.records_written += output.records_written;
+            stats.records_filtered += output.records_filtered;
+            stats.transform_errors += output.transform_errors;
+
+            for (stage_name, elapsed) in branch.stage_names.iter().zip(&output.transform_times) {
+                stats.stage_durations.push((stage_name.clone(), *elapsed));
+            }
+            stats.stage_durations.push((output.sink_name.clone(), output.sink_time));
+        }
+        stats.elapsed = run_started.elapsed();
+        self.progress.finish(stats.records_read, stats.records_written, stats.elapsed).await;
+
+        Ok(stats)
+    }
+}
+
+/// Describes how `actual` falls short of `expected`. A non-nullable output
+/// field satisfies a nullable contract field, but not the other way round.
+fn contract_mismatches(expected: &Schema, actual: &Schema) -> Vec<String> {
+    let mut problems = Vec::new();
+    for field in &expected.fields {
+        match actual.get_field(&field.name) {
+            None => problems.push(format!("missing field '{}' ({:?})", field.name, field.data_type)),
+            Some(found) => {
+                if found.data_type != field.data_type {
+                    problems.push(format!(
+                        "field '{}' is {:?}, expected {:?}",
+                        field.name, found.data_type, field.data_type
+                    ));
+                }
+                if found.nullable && !field.nullable {
+                    problems.push(format!("field '{}' is nullable, expected non-nullable", field.name));
+                }
+            }
+        }
+    }
+    for field in &actual.fields {
+        if expected.get_field(&field.name).is_none() {
+            problems.push(format!("unexpected field '{}' ({:?})", field.name, field.data_type));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::file::CsvSink;
+    use crate::sink::memory::VecSink;
+    use crate::source::memory::VecSource;
+    use crate::core::{DataType, Field};
+    use crate::transform::filter::FilterTransform;
+    use crate::transform::limit::LimitTransform;
+    use crate::transform::sort::{SortKey, SortStage};
+    use crate::transform::split::SplitToArrayTransform;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn records(n: i64) -> Vec<Record> {
+        (0..n)
+            .map(|id| {
+                let mut record = Record::new();
+                record.set_field("id".to_string(), json!(id));
+                record
+            })
+            .collect()
+    }
+
+    fn field(name: &str, data_type: DataType, nullable: bool) -> Field {
+        Field {
+            name: name.to_string(),
+            data_type,
+            nullable,
+            description: None,
+        }
+    }
+
+    fn ids(records: &[Record]) -> Vec<i64> {
+        records.iter().map(|r| r.get_field("id").unwrap().as_i64().unwrap()).collect()
+    }
+
+    /// Passes records through after sleeping for `delay`.
+    struct SlowTransform {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Transform for SlowTransform {
+        async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+            tokio::time::sleep(self.delay).await;
+            Ok(vec![record])
+        }
+
+        async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+            Ok(input_schema.clone())
+        }
+    }
+
+    /// A [`VecSource`] that counts how often it is read.
+    struct CountingSource {
+        inner: VecSource,
+        reads: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Source for CountingSource {
+        async fn get_schema(&self) -> Result<Schema> {
+            self.inner.get_schema().await
+        }
+
+        async fn read(&self) -> Result<RecordStream> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.read().await
+        }
+    }
+
+    /// A [`VecSource`] that counts the records pulled from its stream.
+    struct PulledSource {
+        inner: VecSource,
+        pulled: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Source for PulledSource {
+        async fn get_schema(&self) -> Result<Schema> {
+            self.inner.get_schema().await
+        }
+
+        async fn read(&self) -> Result<RecordStream> {
+            let pulled = self.pulled.clone();
+            let stream = self.inner.read().await?.inspect(move |_| {
+                pulled.fetch_add(1, Ordering::SeqCst);
+            });
+            Ok(Box::pin(stream))
+        }
+    }
+
+    /// Field values as strings, by field name.
+    #[derive(Default)]
+    struct Fields(std::collections::HashMap<String, String>);
+
+    impl tracing::field::Visit for Fields {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    /// Collects the fields of every span, including those recorded after it
+    /// was created, and of every event.
+    #[derive(Clone, Default)]
+    struct CapturedTrace {
+        spans: Arc<std::sync::Mutex<Vec<Fields>>>,
+        events: Arc<std::sync::Mutex<Vec<Fields>>>,
+    }
+
+    impl tracing::Subscriber for CapturedTrace {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Fields::default();
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(fields);
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            values.record(&mut self.spans.lock().unwrap()[span.into_u64() as usize - 1]);
+        }
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    /// Emits each record followed by a copy with `id + 10`.
+    struct Twice;
+
+    #[async_trait]
+    impl Transform for Twice {
+        async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+            let mut copy = record.clone();
+            let id = record.get_field("id").unwrap().as_i64().unwrap();
+            copy.set_field("id".to_string(), json!(id + 10));
+            Ok(vec![record, copy])
+        }
+
+        async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+            Ok(input_schema.clone())
+        }
+    }
+
+    /// Cancels `token` once it has passed `after` records through.
+    struct CancelAfter {
+        after: usize,
+        seen: AtomicUsize,
+        token: CancellationToken,
+    }
+
+    #[async_trait]
+    impl Transform for CancelAfter {
+        async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+            if self.seen.fetch_add(1, Ordering::SeqCst) + 1 == self.after {
+                self.token.cancel();
+            }
+            Ok(vec![record])
+        }
+
+        async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+            Ok(input_schema.clone())
+        }
+    }
+
+    /// Records the size of every `write_batch` call.
+    #[derive(Clone, Default)]
+    struct BatchSizes {
+        sizes: Arc<std::sync::Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl Sink for BatchSizes {
+        async fn write(&mut self, _record: Record) -> Result<()> {
+            self.sizes.lock().unwrap().push(1);
+            Ok(())
+        }
+
+        async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+            self.sizes.lock().unwrap().push(records.len());
+            Ok(())
+        }
+    }
+
+    struct PassThrough;
+
+    #[async_trait]
+    impl Transform for PassThrough {
+        async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+            Ok(vec![record])
+        }
+
+        async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+            Ok(input_schema.clone())
+        }
+    }
+
+    /// Fails on the record with id `id`.
+    struct FailOn {
+        id: i64,
+    }
+
+    #[async_trait]
+    impl Transform for FailOn {
+        async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+            if record.get_field("id") == Some(&json!(self.id)) {
+                return Err(PipelineError::Transform("bad record".to_string()));
+            }
+            Ok(vec![record])
+        }
+
+        async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+            Ok(input_schema.clone())
+        }
+    }
+
+    /// Rejects the record with id `id`, and any batch holding it, passing
+    /// everything else to `inner`.
+    struct RejectId {
+        id: i64,
+        inner: VecSink,
+    }
+
+    #[async_trait]
+    impl Sink for RejectId {
+        async fn write(&mut self, record: Record) -> Result<()> {
+            if record.get_field("id") == Some(&json!(self.id)) {
+                return Err(PipelineError::Sink("rejected".to_string()));
+            }
+            self.inner.write(record).await
+        }
+
+        async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+            if records.iter().any(|record| record.get_field("id") == Some(&json!(self.id))) {
+                return Err(PipelineError::Sink("rejected".to_string()));
+            }
+            self.inner.write_batch(records).await
+        }
+    }
+
+    /// Fails its first `failures` calls with `error`, recording the size of
+    /// every call.
+    #[derive(Clone)]
+    struct FlakySink {
+        failures: Arc<AtomicUsize>,
+        error: fn() -> PipelineError,
+        calls: Arc<std::sync::Mutex<Vec<usize>>>,
+    }
+
+    impl FlakySink {
+        fn new(failures: usize, error: fn() -> PipelineError) -> Self {
+            Self { failures: Arc::new(AtomicUsize::new(failures)), error, calls: Arc::default() }
+        }
+
+        fn call(&self, records: usize) -> Result<()> {
+            self.calls.lock().unwrap().push(records);
+            match self.failures.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1)) {
+                Ok(_) => Err((self.error)()),
+                Err(_) => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Sink for FlakySink {
+        async fn write(&mut self, _record: Record) -> Result<()> {
+            self.call(1)
+        }
+
+        async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+            self.call(records.len())
+        }
+    }
+
+    fn unavailable() -> PipelineError {
+        PipelineError::Sink("unavailable".to_string())
+    }
+
+    fn retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            jitter: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_transform_is_the_slowest_stage() {
+        let sink = VecSink::new();
+        let pipeline = Pipeline::new(
+            Box::new(VecSource::new(records(5))),
+            vec![Box::new(PassThrough), Box::new(SlowTransform { delay: Duration::from_millis(10) })],
+            Box::new(sink.clone()),
+        );
+        let stats = pipeline.run().await.unwrap();
+
+        let stages: Vec<&str> = stats.stage_durations.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(stages, ["source", "PassThrough[0]", "SlowTransform[1]", "sink"]);
+        assert_eq!(stats.slowest_stage().unwrap().0, "SlowTransform[1]");
+        assert!(stats.stage_duration("SlowTransform[1]").unwrap() >= Duration::from_millis(50));
+        assert_eq!(ids(&sink.records()), [0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn branches_share_one_source_read() {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let source = CountingSource { inner: VecSource::new(records(6)), reads: reads.clone() };
+        let (all, even) = (VecSink::new(), VecSink::new());
+        let is_even = |r: &Record| r.get_field("id").and_then(|v| v.as_i64()).is_some_and(|id| id % 2 == 0);
+
+        let stats = Pipeline::with_branches(
+            Box::new(source),
+            vec![
+                (vec![], Box::new(all.clone()) as Box<dyn Sink>),
+                (vec![Box::new(FilterTransform::new(is_even)) as Box<dyn Transform>], Box::new(even.clone())),
+            ],
+        )
+        .run()
+        .await
+        .unwrap();
+
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+        assert_eq!(ids(&all.records()), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(ids(&even.records()), [0, 2, 4]);
+        assert_eq!((stats.records_read, stats.records_written, stats.records_filtered), (6, 9, 3));
+        let stages: Vec<&str> = stats.stage_durations.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(stages, ["source", "branch[0].sink", "branch[1].FilterTransform[0]", "branch[1].sink"]);
+    }
+
+    #[tokio::test]
+    async fn records_held_by_a_sort_are_not_filtered() {
+        let is_even = |r: &Record| r.get_field("id").and_then(|v| v.as_i64()).is_some_and(|id| id % 2 == 0);
+        let pipeline = |sink: &VecSink| {
+            Pipeline::new(
+                Box::new(VecSource::new(records(6))),
+                vec![Box::new(FilterTransform::new(is_even)), Box::new(SortStage::new(vec![SortKey::desc("id")]))],
+                Box::new(sink.clone()),
+            )
+        };
+
+        let (sink, batched) = (VecSink::new(), VecSink::new());
+        let stats = pipeline(&sink).run().await.unwrap();
+        let batched_stats = pipeline(&batched).run_batched(4).await.unwrap();
+
+        assert_eq!(ids(&sink.records()), [4, 2, 0]);
+        assert_eq!(ids(&batched.records()), [4, 2, 0]);
+        for stats in [stats, batched_stats] {
+            assert_eq!((stats.records_read, stats.records_written, stats.records_filtered), (6, 3, 3));
+        }
+    }
+
+    #[tokio::test]
+    async fn batched_run_writes_chunks_of_the_batch_size() {
+        let sink = BatchSizes::default();
+        let stats = Pipeline::new(
+            Box::new(VecSource::new(records(10))),
+            vec![Box::new(PassThrough)],
+            Box::new(sink.clone()),
+        )
+        .run_batched(4)
+        .await
+        .unwrap();
+
+        assert_eq!(*sink.sizes.lock().unwrap(), [4, 4, 2]);
+        assert_eq!((stats.records_read, stats.records_written), (10, 10));
+    }
+
+    #[tokio::test]
+    async fn contract_mismatch_fails_validate_with_a_diff() {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), json!(1));
+        record.set_field("tags".to_string(), json!("a|b"));
+        let pipeline = |sink: &VecSink, contract: Schema| {
+            Pipeline::new(
+                Box::new(VecSource::new(vec![record.clone()])),
+                vec![Box::new(SplitToArrayTransform::new("tags", "|"))],
+                Box::new(sink.clone()),
+            )
+            .with_contract(contract)
+        };
+        let sink = VecSink::new();
+
+        let matching = Schema::new(vec![
+            field("id", DataType::Integer, true),
+            field("tags", DataType::Array(Box::new(DataType::String)), true),
+        ]);
+        pipeline(&sink, matching).validate().await.unwrap();
+
+        let stale = Schema::new(vec![
+            field("id", DataType::Integer, true),
+            field("tags", DataType::String, true),
+            field("email", DataType::String, true),
+        ]);
+        let error = pipeline(&sink, stale.clone()).validate().await.unwrap_err().to_string();
+        assert!(error.contains("Output schema does not match the contract"), "{}", error);
+        assert!(error.contains("field 'tags' is Array(String), expected String"), "{}", error);
+        assert!(error.contains("missing field 'email' (String)"), "{}", error);
+
+        assert!(pipeline(&sink, stale).run().await.is_err());
+        assert!(sink.records().is_empty());
+    }
+
+    #[tokio::test]
+    async fn every_fanned_out_record_reaches_the_sink() {
+        let sink = VecSink::new();
+        let is_even = |r: &Record| r.get_field("id").and_then(|v| v.as_i64()).is_some_and(|id| id % 2 == 0);
+        let stats = Pipeline::new(
+            Box::new(VecSource::new(records(3))),
+            vec![Box::new(Twice), Box::new(FilterTransform::new(is_even))],
+            Box::new(sink.clone()),
+        )
+        .run()
+        .await
+        .unwrap();
+
+        assert_eq!(ids(&sink.records()), [0, 10, 2, 12]);
+        assert_eq!((stats.records_read, stats.records_written), (3, 4));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn concurrent_transforms_overlap() {
+        let delay = Duration::from_millis(5);
+        let pipeline = |sink: &VecSink| {
+            Pipeline::new(
+                Box::new(VecSource::new(records(100))),
+                vec![Box::new(SlowTransform { delay })],
+                Box::new(sink.clone()),
+            )
+        };
+
+        let (sequential, concurrent, unordered) = (VecSink::new(), VecSink::new(), VecSink::new());
+        let started = Instant::now();
+        pipeline(&sequential).run().await.unwrap();
+        let sequential_time = started.elapsed();
+        let started = Instant::now();
+        pipeline(&concurrent).run_concurrent(20).await.unwrap();
+        let concurrent_time = started.elapsed();
+        pipeline(&unordered).run_concurrent_unordered(20).await.unwrap();
+
+        assert!(sequential_time >= delay * 100);
+        assert!(concurrent_time * 4 < sequential_time, "{:?} vs {:?}", concurrent_time, sequential_time);
+        assert_eq!(ids(&concurrent.records()), (0..100).collect::<Vec<_>>());
+        let mut unordered_ids = ids(&unordered.records());
+        unordered_ids.sort();
+        assert_eq!(unordered_ids, (0..100).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn cancelled_run_writes_what_it_read_and_closes_the_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.csv");
+        let token = CancellationToken::new();
+        let cancel = CancelAfter {
+            after: 7,
+            seen: AtomicUsize::new(0),
+            token: token.clone(),
+        };
+        let pipeline = Pipeline::new(
+            Box::new(VecSource::new(records(100))),
+            vec![Box::new(cancel)],
+            Box::new(CsvSink::new(&path)),
+        );
+        let stats = pipeline.run_with_cancel(token).await.unwrap();
+
+        assert_eq!((stats.records_read, stats.records_written), (7, 7));
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "id\n0\n1\n2\n3\n4\n5\n6\n");
+    }
+
+    fn dead_letter_pipeline(sink: &VecSink, dead_letter: &VecSink, n: i64, reject: i64) -> Pipeline {
+        Pipeline::new(
+            Box::new(VecSource::new(records(n))),
+            vec![Box::new(FailOn { id: 1 })],
+            Box::new(RejectId { id: reject, inner: sink.clone() }),
+        )
+        .with_dead_letter(Box::new(dead_letter.clone()))
+    }
+
+    #[tokio::test]
+    async fn failed_records_are_dead_lettered_with_their_error() {
+        for concurrent in [false, true] {
+            let (sink, dead_letter) = (VecSink::new(), VecSink::new());
+            let pipeline = dead_letter_pipeline(&sink, &dead_letter, 5, 3);
+            let stats = if concurrent { pipeline.run_concurrent(4).await } else { pipeline.run().await }.unwrap();
+
+            assert_eq!(ids(&sink.records()), [0, 2, 4]);
+            let failed = dead_letter.records();
+            assert_eq!(ids(&failed), [1, 3]);
+            let transform_error = failed[0].get_metadata("error").unwrap();
+            assert!(transform_error.starts_with("FailOn: ") && transform_error.contains("bad record"), "{}", transform_error);
+            assert_eq!(failed[1].get_metadata("error"), Some("Sink error: rejected"));
+            assert_eq!(
+                (stats.records_read, stats.records_written, stats.transform_errors, stats.records_dead_lettered),
+                (5, 3, 1, 2)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn batched_run_dead_letters_whole_batches() {
+        let (sink, dead_letter) = (VecSink::new(), VecSink::new());
+        let stats = dead_letter_pipeline(&sink, &dead_letter, 9, 4).run_batched(3).await.unwrap();
+
+        // The transform fails the first batch and the sink rejects the second.
+        assert_eq!(ids(&sink.records()), [6, 7, 8]);
+        let failed = dead_letter.records();
+        assert_eq!(ids(&failed), [0, 1, 2, 3, 4, 5]);
+        assert!(failed[..3].iter().all(|r| r.get_metadata("error").unwrap().starts_with("FailOn: ")));
+        assert!(failed[3..].iter().all(|r| r.get_metadata("error") == Some("Sink error: rejected")));
+        assert_eq!((stats.records_read, stats.records_written, stats.records_dead_lettered), (9, 3, 6));
+    }
+
+    #[tokio::test]
+    async fn sink_errors_are_retried_up_to_max_attempts() {
+        let pipeline = |sink: &FlakySink| {
+            Pipeline::new(Box::new(VecSource::new(records(3))), vec![], Box::new(sink.clone())).with_retry(retry(3))
+        };
+
+        let recovers = FlakySink::new(2, unavailable);
+        let stats = pipeline(&recovers).run().await.unwrap();
+        assert_eq!(stats.records_written, 3);
+        assert_eq!(recovers.calls.lock().unwrap().len(), 5);
+
+        let gives_up = FlakySink::new(3, unavailable);
+        let error = pipeline(&gives_up).run().await.unwrap_err();
+        assert!(matches!(error, PipelineError::Sink(_)), "{}", error);
+        assert_eq!(gives_up.calls.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried() {
+        let sink = FlakySink::new(1, || PipelineError::Schema("missing key".to_string()));
+        let error = Pipeline::new(Box::new(VecSource::new(records(3))), vec![], Box::new(sink.clone()))
+            .with_retry(retry(3))
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, PipelineError::Schema(_)), "{}", error);
+        assert_eq!(sink.calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn batches_are_retried_whole() {
+        let sink = FlakySink::new(2, unavailable);
+        let stats = Pipeline::new(Box::new(VecSource::new(records(6))), vec![], Box::new(sink.clone()))
+            .with_retry(retry(3))
+            .run_batched(4)
+            .await
+            .unwrap();
+
+        assert_eq!(*sink.calls.lock().unwrap(), [4, 4, 4, 2]);
+        assert_eq!(stats.records_written, 6);
+    }
+
+    async fn progress_events(pipeline: Pipeline, capacity: usize) -> Vec<ProgressEvent> {
+        let (tx, mut rx) = mpsc::channel(capacity);
+        let run = tokio::spawn(pipeline.with_progress(tx).run());
+        // Let the run fill the channel before anything is taken from it.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        run.await.unwrap().unwrap();
+        events
+    }
+
+    fn read_counts(events: &[ProgressEvent]) -> Vec<(u64, bool)> {
+        events.iter().map(|event| (event.records_read, event.finished)).collect()
+    }
+
+    #[tokio::test]
+    async fn progress_is_sent_every_so_many_records() {
+        let pipeline = Pipeline::new(Box::new(VecSource::new(records(5))), vec![], Box::new(VecSink::new()))
+            .with_progress_interval(2, Duration::from_secs(3600));
+
+        let events = progress_events(pipeline, 16).await;
+        assert_eq!(read_counts(&events), [(2, false), (4, false), (5, true)]);
+        assert_eq!(events.last().unwrap().records_written, 5);
+    }
+
+    #[tokio::test]
+    async fn progress_is_sent_every_interval() {
+        let pipeline = Pipeline::new(
+            Box::new(VecSource::new(records(3))),
+            vec![Box::new(SlowTransform { delay: Duration::from_millis(10) })],
+            Box::new(VecSink::new()),
+        )
+        .with_progress_interval(1000, Duration::from_millis(5));
+
+        let events = progress_events(pipeline, 16).await;
+        assert_eq!(read_counts(&events), [(1, false), (2, false), (3, false), (3, true)]);
+    }
+
+    #[tokio::test]
+    async fn final_progress_event_waits_for_a_full_channel() {
+        let pipeline = Pipeline::new(Box::new(VecSource::new(records(5))), vec![], Box::new(VecSink::new()))
+            .with_progress_interval(1, Duration::from_secs(3600));
+
+        // Only the first event fits; the rest are dropped, but not the last.
+        let events = progress_events(pipeline, 1).await;
+        assert_eq!(read_counts(&events), [(1, false), (5, true)]);
+    }
+
+    #[tokio::test]
+    async fn limited_run_stops_pulling_from_the_source() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let source = PulledSource { inner: VecSource::new(records(100)), pulled: pulled.clone() };
+        let sink = VecSink::new();
+        let stats = Pipeline::new(Box::new(source), vec![], Box::new(sink.clone())).run_limited(3).await.unwrap();
+
+        assert_eq!(pulled.load(Ordering::SeqCst), 3);
+        assert_eq!(ids(&sink.records()), [0, 1, 2]);
+        assert_eq!((stats.records_read, stats.records_written), (3, 3));
+
+        // A limit transform writes as much but reads the whole source.
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let source = PulledSource { inner: VecSource::new(records(100)), pulled: pulled.clone() };
+        let sink = VecSink::new();
+        Pipeline::new(Box::new(source), vec![Box::new(LimitTransform::new(3))], Box::new(sink.clone()))
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(pulled.load(Ordering::SeqCst), 100);
+        assert_eq!(ids(&sink.records()), [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn tracing_wraps_every_stage_in_a_span() {
+        let trace = CapturedTrace::default();
+        let _guard = tracing::subscriber::set_default(trace.clone());
+        let pipeline = |tracing: bool| {
+            let pipeline =
+                Pipeline::new(Box::new(VecSource::new(records(2))), vec![Box::new(PassThrough)], Box::new(VecSink::new()));
+            if tracing { pipeline.with_tracing() } else { pipeline }
+        };
+
+        pipeline(false).run().await.unwrap();
+        assert!(trace.spans.lock().unwrap().is_empty());
+
+        pipeline(true).run().await.unwrap();
+        let spans: Vec<(String, Option<String>)> = trace
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|Fields(fields)| (fields["stage"].clone(), fields.get("record").cloned()))
+            .collect();
+        let span = |stage: &str, record: Option<&str>| (stage.to_string(), record.map(str::to_string));
+        assert_eq!(
+            spans,
+            [
+                span("PassThrough[0]", Some("0")),
+                span("sink", Some("0")),
+                span("PassThrough[0]", Some("1")),
+                span("sink", Some("1")),
+                // Flushing the transforms and closing the sink at the end.
+                span("PassThrough[0]", None),
+                span("sink", None),
+            ]
+        );
+
+        let events = trace.events.lock().unwrap();
+        let finished: Vec<&Fields> =
+            events.iter().filter(|Fields(f)| f.get("message").is_some_and(|m| m == "stage finished")).collect();
+        assert_eq!(finished.len(), 6);
+        assert!(finished.iter().all(|Fields(f)| f.contains_key("stage") && f["duration_us"].parse::<u64>().is_ok()));
+    }
+}
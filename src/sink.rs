@@ -1 +1,30 @@
-pub mod file;
\ No newline at end of file
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "avro")]
+pub mod avro;
+#[cfg(feature = "bigquery")]
+pub mod bigquery;
+pub mod bloom;
+pub(crate) mod buffered;
+pub mod cbor;
+pub mod encrypted;
+pub mod fanout;
+pub mod file;
+#[cfg(feature = "iceberg")]
+pub mod iceberg;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod manifest;
+pub mod memory;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(feature = "postgres")]
+pub mod sql;
+pub mod stdio;
+pub mod table;
+pub mod templated;
+pub mod transactional;
\ No newline at end of file
@@ -0,0 +1,154 @@
+use crate::core::arrow::{batch_from_records, schema_to_arrow};
+use crate::core::{DataType, PipelineError, Record, Result, Schema, Sink};
+use crate::sink::buffered::BufferedWrite;
+use crate::transform::cast::cast_value;
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, SchemaRef};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+type IpcWriter = FileWriter<BufWriter<File>>;
+
+fn sink_error(e: ArrowError) -> PipelineError {
+    PipelineError::Sink(format!("Arrow IPC error: {}", e))
+}
+
+/// Converts `value` into what the Arrow JSON decoder accepts for a column
+/// of `data_type`, leaving values that cannot be converted for it to reject.
+fn arrow_value(value: Value, data_type: &DataType) -> Value {
+    match data_type {
+        DataType::Json if !value.is_null() => Value::String(value.to_string()),
+        DataType::String | DataType::Bytes => match value {
+            Value::Array(_) | Value::Object(_) => Value::String(value.to_string()),
+            other => other,
+        },
+        _ => cast_value(&value, data_type).unwrap_or(value),
+    }
+}
+
+/// Writes records to an Arrow IPC (Feather v2) file with the given schema,
+/// in record batches of up to `batch_size` rows.
+///
+/// Each value is converted to its column's type, so strings are parsed into
+/// numbers, booleans and timestamps; fields missing from a record are null
+/// and fields not in the schema are dropped. The file is only readable once
+/// `close` has written its footer.
+pub struct ArrowSink {
+    path: PathBuf,
+    schema: Schema,
+    arrow_schema: SchemaRef,
+    batch_size: usize,
+    buffer: Vec<Record>,
+    writer: Option<IpcWriter>,
+    finished: bool,
+}
+
+impl ArrowSink {
+    pub fn new<P: AsRef<Path>>(path: P, schema: Schema) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            arrow_schema: Arc::new(schema_to_arrow(&schema)),
+            schema,
+            batch_size: 8192,
+            buffer: Vec::new(),
+            writer: None,
+            finished: false,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Runs `op` on the writer, creating the file first if needed, on a
+    /// blocking thread.
+    async fn with_writer<F>(&mut self, op: F) -> Result<()>
+    where
+        F: FnOnce(&mut IpcWriter) -> std::result::Result<(), ArrowError> + Send + 'static,
+    {
+        let writer = self.writer.take();
+        let path = self.path.clone();
+        let arrow_schema = self.arrow_schema.clone();
+        let (writer, result) = tokio::task::spawn_blocking(move || {
+            let mut writer = match writer {
+                Some(writer) => writer,
+                None => {
+                    let file = File::create(&path)?;
+                    FileWriter::try_new_buffered(file, &arrow_schema).map_err(sink_error)?
+                }
+            };
+            let result = op(&mut writer).map_err(sink_error);
+            Ok::<_, PipelineError>((writer, result))
+        })
+        .await
+        .map_err(|e| PipelineError::Sink(format!("Arrow IPC write task failed: {}", e)))??;
+        self.writer = Some(writer);
+        result
+    }
+
+    async fn write_buffer(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let batch = batch_from_records(&self.buffer, self.arrow_schema.clone())?;
+        self.with_writer(move |writer| writer.write(&batch)).await?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BufferedWrite for ArrowSink {
+    type Item = Record;
+
+    fn buffer(&mut self) -> &mut Vec<Record> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        self.write_buffer().await
+    }
+}
+
+#[async_trait]
+impl Sink for ArrowSink {
+    async fn write(&mut self, mut record: Record) -> Result<()> {
+        if self.finished {
+            return Err(PipelineError::Sink("Arrow IPC file is already closed".to_string()));
+        }
+        for field in &self.schema.fields {
+            if let Some(value) = record.data.get_mut(&field.name) {
+                *value = arrow_value(std::mem::take(value), &field.data_type);
+            }
+        }
+        self.buffer_write(record).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.write_buffer().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.write_buffer().await?;
+        self.with_writer(|writer| {
+            writer.finish()?;
+            writer.get_mut().flush().map_err(ArrowError::from)
+        })
+        .await?;
+        self.writer = None;
+        self.finished = true;
+        Ok(())
+    }
+}
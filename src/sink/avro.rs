@@ -0,0 +1,263 @@
+use crate::core::{timestamp_millis, DataType, PipelineError, Record, Result, Schema, Sink};
+use crate::sink::buffered::BufferedWrite;
+use crate::transform::cast::cast_value;
+use apache_avro::schema::Schema as AvroSchema;
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{read_marker, Writer};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::{json, Value};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+fn sink_error(e: apache_avro::Error) -> PipelineError {
+    PipelineError::Sink(format!("Avro error: {}", e))
+}
+
+fn avro_type(data_type: &DataType) -> Value {
+    match data_type {
+        DataType::String | DataType::Json => json!("string"),
+        DataType::Integer => json!("long"),
+        DataType::Float => json!("double"),
+        DataType::Boolean => json!("boolean"),
+        DataType::Bytes => json!("bytes"),
+        DataType::Date => json!({"type": "int", "logicalType": "date"}),
+        DataType::Timestamp => json!({"type": "long", "logicalType": "timestamp-micros"}),
+        DataType::DateTime => json!({"type": "long", "logicalType": "local-timestamp-micros"}),
+        DataType::Array(item_type) => json!({"type": "array", "items": ["null", avro_type(item_type)]}),
+    }
+}
+
+/// Translates `schema` into an Avro record schema named `name`. Nullable
+/// fields become a union with null, defaulting to null.
+fn schema_to_avro(schema: &Schema, name: &str) -> std::result::Result<AvroSchema, String> {
+    let fields: Vec<Value> = schema
+        .fields
+        .iter()
+        .map(|field| {
+            let mut avro_field = json!({"name": field.name});
+            if field.nullable {
+                avro_field["type"] = json!(["null", avro_type(&field.data_type)]);
+                avro_field["default"] = Value::Null;
+            } else {
+                avro_field["type"] = avro_type(&field.data_type);
+            }
+            if let Some(ref description) = field.description {
+                avro_field["doc"] = json!(description);
+            }
+            avro_field
+        })
+        .collect();
+    AvroSchema::parse(&json!({"type": "record", "name": name, "fields": fields}))
+        .map_err(|e| format!("Cannot translate schema to Avro: {}", e))
+}
+
+/// Microseconds since the epoch; strings without an offset are taken to be
+/// UTC.
+fn epoch_micros(value: &Value) -> Option<i64> {
+    if let Value::String(s) = value {
+        let s = s.trim();
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            return Some(dt.timestamp_micros());
+        }
+        for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+            if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+                return Some(dt.and_utc().timestamp_micros());
+            }
+        }
+    }
+    timestamp_millis(value)?.checked_mul(1000)
+}
+
+/// Converts a non-null `value` for a column of `data_type`, or `None` if it
+/// cannot be represented as one.
+fn avro_value(value: &Value, data_type: &DataType) -> Option<AvroValue> {
+    Some(match data_type {
+        DataType::String => match value {
+            Value::String(s) => AvroValue::String(s.clone()),
+            other => AvroValue::String(other.to_string()),
+        },
+        DataType::Json => AvroValue::String(value.to_string()),
+        DataType::Integer => AvroValue::Long(cast_value(value, data_type)?.as_i64()?),
+        DataType::Float => AvroValue::Double(cast_value(value, data_type)?.as_f64()?),
+        DataType::Boolean => AvroValue::Boolean(cast_value(value, data_type)?.as_bool()?),
+        DataType::Bytes => AvroValue::Bytes(BASE64.decode(value.as_str()?).ok()?),
+        DataType::Date => {
+            let date = cast_value(value, data_type)?;
+            let date = NaiveDate::parse_from_str(date.as_str()?, "%Y-%m-%d").ok()?;
+            let days = date.signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1)?).num_days();
+            AvroValue::Date(i32::try_from(days).ok()?)
+        }
+        DataType::Timestamp => AvroValue::TimestampMicros(epoch_micros(value)?),
+        DataType::DateTime => AvroValue::LocalTimestampMicros(epoch_micros(value)?),
+        DataType::Array(item_type) => AvroValue::Array(
+            value
+                .as_array()?
+                .iter()
+                .map(|item| match item {
+                    Value::Null => Some(AvroValue::Union(0, Box::new(AvroValue::Null))),
+                    item => Some(AvroValue::Union(1, Box::new(avro_value(item, item_type)?))),
+                })
+                .collect::<Option<_>>()?,
+        ),
+    })
+}
+
+/// Writes records to an Avro object container file with the given schema,
+/// translated to Avro: `Integer` is `long`, `Float` is `double`, `Json` is a
+/// string of JSON text, and a nullable field is a union with null.
+///
+/// Each value is converted to its field's type, so numeric strings become
+/// numbers and timestamp strings `timestamp-micros`; a value that cannot be,
+/// or a null in a non-nullable field, is an error, and fields not in the
+/// schema are dropped. Field names must be valid Avro names. Records are
+/// encoded in blocks of up to `batch_size`, and an empty file still gets its
+/// header on `close`.
+pub struct AvroSink {
+    file_path: String,
+    schema: Schema,
+    avro_schema: std::result::Result<AvroSchema, String>,
+    batch_size: usize,
+    buffer: Vec<AvroValue>,
+    file: Option<File>,
+    /// The sync marker from the file header, once it has been written.
+    marker: Option<[u8; 16]>,
+}
+
+impl AvroSink {
+    pub fn new<P: AsRef<Path>>(file_path: P, schema: Schema) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            avro_schema: schema_to_avro(&schema, "Record"),
+            schema,
+            batch_size: 1000,
+            buffer: Vec::new(),
+            file: None,
+            marker: None,
+        }
+    }
+
+    /// Names the Avro record type, `Record` by default.
+    pub fn with_record_name(mut self, name: &str) -> Self {
+        self.avro_schema = schema_to_avro(&self.schema, name);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn avro_schema(&self) -> Result<&AvroSchema> {
+        self.avro_schema.as_ref().map_err(|e| PipelineError::Config(e.clone()))
+    }
+
+    fn encode(&self, record: &Record) -> Result<AvroValue> {
+        let fields = self
+            .schema
+            .fields
+            .iter()
+            .map(|field| {
+                let value = match record.get_field(&field.name) {
+                    None | Some(Value::Null) if field.nullable => AvroValue::Union(0, Box::new(AvroValue::Null)),
+                    None | Some(Value::Null) => {
+                        return Err(PipelineError::Schema(format!("Required field '{}' is null", field.name)));
+                    }
+                    Some(value) => {
+                        let converted = avro_value(value, &field.data_type).ok_or_else(|| {
+                            PipelineError::Schema(format!(
+                                "Cannot write field '{}' value {} as {:?}",
+                                field.name, value, field.data_type
+                            ))
+                        })?;
+                        if field.nullable {
+                            AvroValue::Union(1, Box::new(converted))
+                        } else {
+                            converted
+                        }
+                    }
+                };
+                Ok((field.name.clone(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AvroValue::Record(fields))
+    }
+
+    /// Encodes the buffered records as a block and appends it to the file,
+    /// preceded by the header if this is the first write.
+    async fn write_buffer(&mut self) -> Result<()> {
+        if self.buffer.is_empty() && self.marker.is_some() {
+            return Ok(());
+        }
+        // The buffer is only cleared once the block is written, so a failed
+        // write can be retried.
+        let bytes = {
+            let avro_schema = self.avro_schema()?;
+            let mut writer = match self.marker {
+                Some(marker) => Writer::append_to(avro_schema, Vec::new(), marker),
+                None => Writer::new(avro_schema, Vec::new()),
+            }
+            .map_err(sink_error)?;
+            for value in &self.buffer {
+                writer.append_value(value.clone()).map_err(sink_error)?;
+            }
+            writer.into_inner().map_err(sink_error)?
+        };
+
+        if self.file.is_none() {
+            self.file = Some(File::create(&self.file_path).await?);
+        }
+        if let Some(ref mut file) = self.file {
+            file.write_all(&bytes).await?;
+        }
+        self.marker = Some(read_marker(&bytes));
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BufferedWrite for AvroSink {
+    type Item = AvroValue;
+
+    fn buffer(&mut self) -> &mut Vec<AvroValue> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        self.write_buffer().await
+    }
+}
+
+#[async_trait]
+impl Sink for AvroSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.avro_schema()?;
+        let value = self.encode(&record)?;
+        self.buffer_write(value).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if !self.buffer.is_empty() {
+            self.write_buffer().await?;
+        }
+        if let Some(ref mut file) = self.file {
+            file.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.write_buffer().await?;
+        self.flush().await?;
+        self.file = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use crate::sink::buffered::BufferedWrite;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_BIGQUERY_ENDPOINT: &str = "https://bigquery.googleapis.com";
+const DEFAULT_STORAGE_ENDPOINT: &str = "https://storage.googleapis.com";
+
+#[derive(Debug, Clone)]
+pub enum BigQueryWriteMode {
+    /// Stage each batch as newline-delimited JSON in a GCS bucket and load it
+    /// with a BigQuery load job.
+    LoadJob { bucket: String, prefix: String },
+    /// Send rows through the `tabledata.insertAll` streaming API.
+    Streaming,
+}
+
+/// Buffers records and ships them to a BigQuery table in batches, either
+/// through GCS-staged load jobs or the streaming insert API.
+pub struct BigQuerySink {
+    client: reqwest::Client,
+    project: String,
+    dataset: String,
+    table: String,
+    mode: BigQueryWriteMode,
+    access_token: Option<String>,
+    batch_size: usize,
+    buffer: Vec<Record>,
+    bigquery_endpoint: String,
+    storage_endpoint: String,
+    batches_sent: u64,
+}
+
+impl BigQuerySink {
+    pub fn new(project: &str, dataset: &str, table: &str, mode: BigQueryWriteMode) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            project: project.to_string(),
+            dataset: dataset.to_string(),
+            table: table.to_string(),
+            mode,
+            access_token: std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN").ok(),
+            batch_size: 10_000,
+            buffer: Vec::new(),
+            bigquery_endpoint: DEFAULT_BIGQUERY_ENDPOINT.to_string(),
+            storage_endpoint: DEFAULT_STORAGE_ENDPOINT.to_string(),
+            batches_sent: 0,
+        }
+    }
+
+    pub fn with_access_token(mut self, token: &str) -> Self {
+        self.access_token = Some(token.to_string());
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Overrides the API base URLs, e.g. to target an emulator.
+    pub fn with_endpoints(mut self, bigquery_endpoint: &str, storage_endpoint: &str) -> Self {
+        self.bigquery_endpoint = bigquery_endpoint.trim_end_matches('/').to_string();
+        self.storage_endpoint = storage_endpoint.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.access_token {
+            Some(ref token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request.send().await.map_err(sink_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(sink_error)?;
+        if !status.is_success() {
+            return Err(PipelineError::Sink(format!("BigQuery request failed with {}: {}", status, body)));
+        }
+        if body.is_empty() {
+            return Ok(Value::Null);
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    async fn insert_all(&self, records: &[Record]) -> Result<()> {
+        let url = format!(
+            "{}/bigquery/v2/projects/{}/datasets/{}/tables/{}/insertAll",
+            self.bigquery_endpoint, self.project, self.dataset, self.table
+        );
+        let rows: Vec<Value> = records.iter().map(|r| json!({ "json": r.data })).collect();
+        let request = self.request(reqwest::Method::POST, &url).json(&json!({ "rows": rows }));
+
+        let response = self.send(request).await?;
+        match response.get("insertErrors") {
+            Some(Value::Array(errors)) if !errors.is_empty() => Err(PipelineError::Sink(
+                format!("BigQuery rejected {} row(s): {}", errors.len(), Value::Array(errors.clone()))
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    async fn load_job(&self, records: &[Record], bucket: &str, prefix: &str) -> Result<()> {
+        let mut payload = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut payload, &record.data)?;
+            payload.push(b'\n');
+        }
+
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let object = format!("{}{}-{}-{}.jsonl", prefix, self.table, millis, self.batches_sent);
+
+        let upload_url = format!("{}/upload/storage/v1/b/{}/o", self.storage_endpoint, bucket);
+        let upload = self
+            .request(reqwest::Method::POST, &upload_url)
+            .query(&[("uploadType", "media"), ("name", object.as_str())])
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(payload);
+        self.send(upload).await?;
+
+        let jobs_url = format!("{}/bigquery/v2/projects/{}/jobs", self.bigquery_endpoint, self.project);
+        let job = json!({
+            "configuration": {
+                "load": {
+                    "sourceUris": [format!("gs://{}/{}", bucket, object)],
+                    "destinationTable": {
+                        "projectId": self.project,
+                        "datasetId": self.dataset,
+                        "tableId": self.table,
+                    },
+                    "sourceFormat": "NEWLINE_DELIMITED_JSON",
+                    "writeDisposition": "WRITE_APPEND",
+                    "autodetect": true,
+                }
+            }
+        });
+        let mut status = self.send(self.request(reqwest::Method::POST, &jobs_url).json(&job)).await?;
+
+        let job_id = status
+            .pointer("/jobReference/jobId")
+            .and_then(Value::as_str)
+            .ok_or_else(|| PipelineError::Sink("BigQuery load job response has no job id".to_string()))?
+            .to_string();
+        let location = status
+            .pointer("/jobReference/location")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        while status.pointer("/status/state").and_then(Value::as_str) != Some("DONE") {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let mut poll = self.request(reqwest::Method::GET, &format!("{}/{}", jobs_url, job_id));
+            if let Some(ref location) = location {
+                poll = poll.query(&[("location", location)]);
+            }
+            status = self.send(poll).await?;
+        }
+
+        match status.pointer("/status/errorResult") {
+            Some(error) if !error.is_null() => Err(PipelineError::Sink(
+                format!("BigQuery load job {} failed: {}", job_id, error)
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn sink_error(e: reqwest::Error) -> PipelineError {
+    PipelineError::Sink(format!("BigQuery request error: {}", e))
+}
+
+#[async_trait]
+impl BufferedWrite for BigQuerySink {
+    type Item = Record;
+
+    fn buffer(&mut self) -> &mut Vec<Record> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        Sink::flush(self).await
+    }
+}
+
+#[async_trait]
+impl Sink for BigQuerySink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.buffer_write(record).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        // The buffer is only cleared once the batch is accepted, so a failed
+        // flush can be retried.
+        match self.mode {
+            BigQueryWriteMode::Streaming => self.insert_all(&self.buffer).await?,
+            BigQueryWriteMode::LoadJob { ref bucket, ref prefix } => {
+                self.load_job(&self.buffer, bucket, prefix).await?
+            }
+        }
+        self.buffer.clear();
+        self.batches_sent += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve, MockRequest};
+
+    fn record(id: i64) -> Record {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), json!(id));
+        record
+    }
+
+    fn sink(base: &str, mode: BigQueryWriteMode) -> BigQuerySink {
+        BigQuerySink::new("proj", "ds", "events", mode)
+            .with_access_token("token")
+            .with_batch_size(2)
+            .with_endpoints(base, base)
+    }
+
+    fn load_job_mode() -> BigQueryWriteMode {
+        BigQueryWriteMode::LoadJob {
+            bucket: "staging".to_string(),
+            prefix: "loads/".to_string(),
+        }
+    }
+
+    /// Answers uploads and insertAll with success, and load jobs as done
+    /// with `error_result`.
+    fn handler(error_result: Value) -> impl Fn(&MockRequest) -> (u16, String) + Send + Sync + 'static {
+        move |request| {
+            let body = if request.path.ends_with("/jobs") {
+                json!({
+                    "jobReference": {"jobId": "job-1", "location": "EU"},
+                    "status": {"state": "DONE", "errorResult": error_result},
+                })
+            } else {
+                json!({})
+            };
+            (200, body.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn streaming_inserts_rows_in_batches() {
+        let (base, requests) = serve(handler(Value::Null)).await;
+        let mut sink = sink(&base, BigQueryWriteMode::Streaming);
+        for id in 0..3 {
+            sink.write(record(id)).await.unwrap();
+        }
+        sink.close().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/bigquery/v2/projects/proj/datasets/ds/tables/events/insertAll");
+        assert_eq!(requests[0].json(), json!({"rows": [{"json": {"id": 0}}, {"json": {"id": 1}}]}));
+        assert_eq!(requests[1].json(), json!({"rows": [{"json": {"id": 2}}]}));
+    }
+
+    #[tokio::test]
+    async fn rejected_rows_are_a_sink_error() {
+        let (base, _) = serve(|_: &MockRequest| (200, json!({"insertErrors": [{"index": 0}]}).to_string())).await;
+        let mut sink = sink(&base, BigQueryWriteMode::Streaming);
+        sink.write(record(0)).await.unwrap();
+        let error = sink.write(record(1)).await.unwrap_err();
+        assert!(matches!(error, PipelineError::Sink(ref message) if message.contains("rejected 1 row")));
+    }
+
+    #[tokio::test]
+    async fn load_job_stages_ndjson_in_gcs() {
+        let (base, requests) = serve(handler(Value::Null)).await;
+        let mut sink = sink(&base, load_job_mode());
+        sink.write(record(0)).await.unwrap();
+        sink.write(record(1)).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let (upload, job) = (&requests[0], &requests[1]);
+        assert!(upload.path.starts_with("/upload/storage/v1/b/staging/o?uploadType=media&name=loads%2Fevents-"));
+        assert_eq!(upload.body, b"{\"id\":0}\n{\"id\":1}\n");
+
+        assert_eq!(job.path, "/bigquery/v2/projects/proj/jobs");
+        let load = &job.json()["configuration"]["load"];
+        let source_uri = load["sourceUris"][0].as_str().unwrap();
+        assert!(source_uri.starts_with("gs://staging/loads/events-") && source_uri.ends_with(".jsonl"));
+        assert_eq!(load["destinationTable"], json!({"projectId": "proj", "datasetId": "ds", "tableId": "events"}));
+        assert_eq!(load["sourceFormat"], json!("NEWLINE_DELIMITED_JSON"));
+    }
+
+    #[tokio::test]
+    async fn failed_load_job_is_a_sink_error() {
+        let (base, _) = serve(handler(json!({"reason": "invalid", "message": "bad row"}))).await;
+        let mut sink = sink(&base, load_job_mode());
+        sink.write(record(0)).await.unwrap();
+        match sink.write(record(1)).await {
+            Err(PipelineError::Sink(message)) => {
+                assert!(message.contains("load job job-1 failed") && message.contains("bad row"), "{}", message);
+            }
+            other => panic!("expected a sink error, got {:?}", other),
+        }
+        // The batch is kept for a retry.
+        assert_eq!(sink.buffer.len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use async_trait::async_trait;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"DPBF";
+
+struct BloomFilter {
+    bits: Vec<u8>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    fn with_capacity(expected_items: u64, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = ((-n * p.ln()) / (ln2 * ln2)).ceil().max(8.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round().max(1.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(8) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    // SHA-256 keeps bit positions stable across runs and builds, which a
+    // randomly seeded std hasher would not.
+    fn positions(&self, key: &[u8]) -> impl Iterator<Item = u64> + '_ {
+        let digest = Sha256::digest(key);
+        let h1 = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().unwrap()) | 1;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+
+    /// Inserts `key`, returning `false` if it was (probably) already present.
+    fn insert(&mut self, key: &[u8]) -> bool {
+        let positions: Vec<u64> = self.positions(key).collect();
+        let mut inserted = false;
+        for position in positions {
+            let (byte, mask) = ((position / 8) as usize, 1u8 << (position % 8));
+            if self.bits[byte] & mask == 0 {
+                self.bits[byte] |= mask;
+                inserted = true;
+            }
+        }
+        inserted
+    }
+
+    /// Whether `key` is (probably) present.
+    fn contains(&self, key: &[u8]) -> bool {
+        self.positions(key).all(|position| self.bits[(position / 8) as usize] & (1u8 << (position % 8)) != 0)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + self.bits.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.num_bits.to_le_bytes());
+        bytes.extend_from_slice(&self.num_hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.bits);
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = || PipelineError::Sink("Invalid Bloom filter file".to_string());
+        if bytes.len() < 16 || &bytes[0..4] != MAGIC {
+            return Err(invalid());
+        }
+        let num_bits = u64::from_le_bytes(bytes[4..12].try_into().unwrap());
+        let num_hashes = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        let bits = bytes[16..].to_vec();
+        if num_bits == 0 || num_hashes == 0 || bits.len() as u64 != num_bits.div_ceil(8) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            bits,
+            num_bits,
+            num_hashes,
+        })
+    }
+}
+
+/// Skips records whose key was already delivered, in this run or any earlier
+/// run, using a Bloom filter persisted at `filter_path`.
+///
+/// A Bloom filter never forgets a key but may report a key it has never seen
+/// as present, so a small fraction of genuinely new records (about
+/// `false_positive_rate` once `expected_items` keys are stored) is dropped.
+/// In return memory and disk use stay fixed at roughly
+/// `-expected_items * ln(rate) / ln(2)^2` bits regardless of how many runs
+/// accumulate. A key is only added once the inner sink has taken its
+/// record, so a write that fails can be retried, and the filter is only
+/// saved after the inner sink closes successfully.
+pub struct BloomDedupSink {
+    inner: Box<dyn Sink>,
+    filter_path: PathBuf,
+    key_fields: Vec<String>,
+    expected_items: u64,
+    false_positive_rate: f64,
+    filter: Option<BloomFilter>,
+}
+
+impl BloomDedupSink {
+    pub fn new<P: AsRef<Path>>(inner: Box<dyn Sink>, filter_path: P, key_fields: Vec<String>) -> Self {
+        Self {
+            inner,
+            filter_path: filter_path.as_ref().to_path_buf(),
+            key_fields,
+            expected_items: 1_000_000,
+            false_positive_rate: 0.001,
+            filter: None,
+        }
+    }
+
+    /// Sizes a newly created filter; an existing filter file keeps its size.
+    pub fn with_capacity(mut self, expected_items: u64, false_positive_rate: f64) -> Self {
+        self.expected_items = expected_items;
+        self.false_positive_rate = false_positive_rate;
+        self
+    }
+
+    async fn filter(&mut self) -> Result<&mut BloomFilter> {
+        if self.filter.is_none() {
+            let filter = match tokio::fs::read(&self.filter_path).await {
+                Ok(bytes) => BloomFilter::from_bytes(&bytes)?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    BloomFilter::with_capacity(self.expected_items, self.false_positive_rate)
+                }
+                Err(e) => return Err(e.into()),
+            };
+            self.filter = Some(filter);
+        }
+        Ok(self.filter.as_mut().unwrap())
+    }
+
+    fn key(&self, record: &Record) -> Vec<u8> {
+        let mut key = Vec::new();
+        for field in &self.key_fields {
+            let value = record.get_field(field).unwrap_or(&Value::Null);
+            key.extend_from_slice(value.to_string().as_bytes());
+            key.push(0x1f);
+        }
+        key
+    }
+}
+
+#[async_trait]
+impl Sink for BloomDedupSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let key = self.key(&record);
+        if self.filter().await?.contains(&key) {
+            return Ok(());
+        }
+        self.inner.write(record).await?;
+        self.filter().await?.insert(&key);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await?;
+        if let Some(ref filter) = self.filter {
+            tokio::fs::write(&self.filter_path, filter.to_bytes()).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::memory::VecSink;
+    use serde_json::json;
+
+    fn record(id: i64) -> Record {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), json!(id));
+        record
+    }
+
+    /// Runs one load of `ids` through a fresh sink, returning the ids the
+    /// inner sink received.
+    async fn run(filter_path: &Path, ids: &[i64]) -> Vec<i64> {
+        let inner = VecSink::new();
+        let mut sink = BloomDedupSink::new(Box::new(inner.clone()), filter_path, vec!["id".to_string()])
+            .with_capacity(1000, 0.001);
+        for id in ids {
+            sink.write(record(*id)).await.unwrap();
+        }
+        sink.close().await.unwrap();
+        inner.records().iter().map(|r| r.get_field("id").unwrap().as_i64().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn keys_from_a_prior_run_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let filter_path = dir.path().join("seen.bloom");
+
+        assert_eq!(run(&filter_path, &[1, 2, 2, 3]).await, [1, 2, 3]);
+        assert!(filter_path.exists());
+        assert_eq!(run(&filter_path, &[3, 4, 1, 5]).await, [4, 5]);
+        assert_eq!(run(&filter_path, &[1, 2, 3, 4, 5]).await, Vec::<i64>::new());
+    }
+
+    #[tokio::test]
+    async fn corrupt_filter_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let filter_path = dir.path().join("seen.bloom");
+        std::fs::write(&filter_path, b"not a filter").unwrap();
+
+        let mut sink = BloomDedupSink::new(Box::new(VecSink::new()), &filter_path, vec!["id".to_string()]);
+        assert!(matches!(sink.write(record(1)).await, Err(PipelineError::Sink(_))));
+    }
+
+    /// Fails its first write, passing the rest to `inner`.
+    struct FailFirst {
+        inner: VecSink,
+        failed: bool,
+    }
+
+    #[async_trait]
+    impl Sink for FailFirst {
+        async fn write(&mut self, record: Record) -> Result<()> {
+            if !self.failed {
+                self.failed = true;
+                return Err(PipelineError::Sink("unavailable".to_string()));
+            }
+            self.inner.write(record).await
+        }
+    }
+
+    #[tokio::test]
+    async fn a_failed_write_does_not_mark_the_key_seen() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = VecSink::new();
+        let failing = FailFirst { inner: inner.clone(), failed: false };
+        let mut sink = BloomDedupSink::new(Box::new(failing), dir.path().join("seen.bloom"), vec!["id".to_string()]);
+
+        assert!(sink.write(record(1)).await.is_err());
+        sink.write(record(1)).await.unwrap();
+        sink.write(record(1)).await.unwrap();
+        assert_eq!(inner.records().len(), 1);
+    }
+
+    #[test]
+    fn filter_round_trips_through_bytes() {
+        let mut filter = BloomFilter::with_capacity(100, 0.01);
+        assert!(filter.insert(b"a"));
+        assert!(!filter.insert(b"a"));
+
+        let mut restored = BloomFilter::from_bytes(&filter.to_bytes()).unwrap();
+        assert_eq!((restored.num_bits, restored.num_hashes), (filter.num_bits, filter.num_hashes));
+        assert!(!restored.insert(b"a"));
+        assert!(restored.insert(b"b"));
+    }
+}
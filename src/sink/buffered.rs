@@ -0,0 +1,32 @@
+use crate::core::Result;
+use async_trait::async_trait;
+
+/// A sink that collects what it is given into a buffer and sends the buffer
+/// once it holds `batch_size` items.
+///
+/// [`buffer_write`](Self::buffer_write) keeps the buffer when sending it
+/// fails and hands back the item that triggered the send, so a write retried
+/// by [`Pipeline::with_retry`](crate::pipeline::Pipeline::with_retry) sends
+/// every buffered item and adds its own once.
+#[async_trait]
+pub(crate) trait BufferedWrite: Send {
+    type Item: Send;
+
+    fn buffer(&mut self) -> &mut Vec<Self::Item>;
+
+    fn batch_size(&self) -> usize;
+
+    /// Sends the buffer, clearing it only once it has been sent.
+    async fn send_buffer(&mut self) -> Result<()>;
+
+    async fn buffer_write(&mut self, item: Self::Item) -> Result<()> {
+        self.buffer().push(item);
+        if self.buffer().len() >= self.batch_size()
+            && let Err(e) = self.send_buffer().await
+        {
+            self.buffer().pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use async_trait::async_trait;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+/// Writes each record as a CBOR map preceded by its length as a 4-byte
+/// big-endian integer, the framing read by
+/// [`CborSource`](crate::source::cbor::CborSource).
+pub struct CborSink {
+    file_path: String,
+    writer: Option<BufWriter<File>>,
+}
+
+impl CborSink {
+    pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            writer: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for CborSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        if self.writer.is_none() {
+            self.writer = Some(BufWriter::new(File::create(&self.file_path).await?));
+        }
+
+        let mut item = Vec::new();
+        ciborium::into_writer(&record.data, &mut item)
+            .map_err(|e| PipelineError::Sink(format!("CBOR encoding failed: {}", e)))?;
+        let length = u32::try_from(item.len())
+            .map_err(|_| PipelineError::Sink(format!("CBOR item of {} bytes is too large", item.len())))?;
+
+        if let Some(ref mut writer) = self.writer {
+            writer.write_all(&length.to_be_bytes()).await?;
+            writer.write_all(&item).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if let Some(ref mut writer) = self.writer {
+            writer.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.flush().await?;
+        self.writer = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::core::{Record, Result, Sink};
+use async_trait::async_trait;
+
+/// Writes every record to each of several sinks, in order.
+///
+/// Each call is made on every sink even when an earlier one fails, so one
+/// failing destination neither starves the others of records nor leaves
+/// them unflushed or unclosed; the first error is then returned. Retrying a
+/// failed write therefore repeats it on the sinks that succeeded. To keep
+/// destinations independent, with their own transforms and retries, use
+/// [`Pipeline::with_branches`](crate::pipeline::Pipeline::with_branches)
+/// instead.
+pub struct FanoutSink {
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl FanoutSink {
+    pub fn new(sinks: Vec<Box<dyn Sink>>) -> Self {
+        Self { sinks }
+    }
+}
+
+/// Keeps the first of `results`' errors, logging the rest.
+fn first_error(results: Vec<Result<()>>) -> Result<()> {
+    let mut first = Ok(());
+    for result in results {
+        match (result, &first) {
+            (Err(e), Ok(())) => first = Err(e),
+            (Err(e), Err(_)) => tracing::warn!("Fan-out sink also failed: {}", e),
+            (Ok(()), _) => {}
+        }
+    }
+    first
+}
+
+#[async_trait]
+impl Sink for FanoutSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let mut results = Vec::with_capacity(self.sinks.len());
+        for sink in self.sinks.iter_mut() {
+            results.push(sink.write(record.clone()).await);
+        }
+        first_error(results)
+    }
+
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        let mut results = Vec::with_capacity(self.sinks.len());
+        for sink in self.sinks.iter_mut() {
+            results.push(sink.write_batch(records.clone()).await);
+        }
+        first_error(results)
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let mut results = Vec::with_capacity(self.sinks.len());
+        for sink in self.sinks.iter_mut() {
+            results.push(sink.flush().await);
+        }
+        first_error(results)
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let mut results = Vec::with_capacity(self.sinks.len());
+        for sink in self.sinks.iter_mut() {
+            results.push(sink.close().await);
+        }
+        first_error(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PipelineError;
+    use crate::sink::memory::VecSink;
+    use serde_json::json;
+
+    fn record(id: i64) -> Record {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), json!(id));
+        record
+    }
+
+    struct FailingSink;
+
+    #[async_trait]
+    impl Sink for FailingSink {
+        async fn write(&mut self, _record: Record) -> Result<()> {
+            Err(PipelineError::Sink("unavailable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn every_sink_receives_each_record() {
+        let (first, second) = (VecSink::new(), VecSink::new());
+        let mut sink = FanoutSink::new(vec![Box::new(first.clone()), Box::new(second.clone())]);
+        sink.write(record(1)).await.unwrap();
+        sink.write_batch(vec![record(2), record(3)]).await.unwrap();
+        sink.close().await.unwrap();
+
+        for inner in [first, second] {
+            let ids: Vec<_> = inner
+                .records()
+                .iter()
+                .map(|r| r.get_field("id").cloned().unwrap())
+                .collect();
+            assert_eq!(ids, vec![json!(1), json!(2), json!(3)]);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_failing_sink_does_not_starve_the_others() {
+        let healthy = VecSink::new();
+        let mut sink = FanoutSink::new(vec![Box::new(FailingSink), Box::new(healthy.clone())]);
+        assert!(matches!(
+            sink.write(record(1)).await,
+            Err(PipelineError::Sink(_))
+        ));
+        assert_eq!(healthy.records().len(), 1);
+    }
+}
@@ -1,16 +1,34 @@
-use crate::core::{Record, Result, Sink};
+use crate::core::{PipelineError, Record, Result, Sink, SinkMode};
 use async_trait::async_trait;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::path::Path;
 use tokio::fs::OpenOptions;
 use tokio::io::{AsyncWriteExt, BufWriter};
 
+/// When `CsvSink` wraps a value in double quotes. Embedded quotes are always
+/// doubled inside a quoted value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum QuoteStyle {
+    /// Quote only values containing the delimiter, a quote or a line break.
+    #[default]
+    Necessary,
+    /// Quote every value and header.
+    Always,
+    /// Quote everything except numeric values.
+    NonNumeric,
+    /// Never quote, for consumers that do not understand quoting. A value
+    /// containing the delimiter or a line break then corrupts the row.
+    Never,
+}
+
 pub struct CsvSink {
     file_path: String,
     delimiter: u8,
+    quoting: QuoteStyle,
     headers: Option<Vec<String>>,
     writer: Option<BufWriter<tokio::fs::File>>,
     headers_written: bool,
+    mode: SinkMode,
 }
 
 impl CsvSink {
@@ -18,30 +36,48 @@ impl CsvSink {
         Self {
             file_path: file_path.as_ref().to_string_lossy().into_owned(),
             delimiter: b',',
+            quoting: QuoteStyle::Necessary,
             headers: None,
             writer: None,
             headers_written: false,
+            mode: SinkMode::Overwrite,
         }
     }
 
+    /// A sink writing tab-separated values.
+    pub fn tsv<P: AsRef<Path>>(file_path: P) -> Self {
+        Self::new(file_path).with_delimiter(b'\t')
+    }
+
     pub fn with_delimiter(mut self, delimiter: u8) -> Self {
         self.delimiter = delimiter;
         self
     }
 
+    pub fn with_quoting(mut self, quoting: QuoteStyle) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
     pub fn with_headers(mut self, headers: Vec<String>) -> Self {
         self.headers = Some(headers);
         self
     }
 
+    /// With `SinkMode::Append`, adds rows to the end of an existing file
+    /// instead of replacing it, writing the header only if the file is new
+    /// or empty. The columns should match those already in the file, so
+    /// set them with `with_headers` when records may vary.
+    pub fn with_mode(mut self, mode: SinkMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     async fn ensure_writer(&mut self) -> Result<()> {
         if self.writer.is_none() {
-            let file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(&self.file_path)
-                .await?;
+            let (file, has_data) = open_output(&self.file_path, self.mode).await?;
+            // An existing file already starts with its header.
+            self.headers_written |= has_data;
             self.writer = Some(BufWriter::new(file));
         }
         Ok(())
@@ -56,7 +92,11 @@ impl CsvSink {
             };
 
             if let Some(ref mut writer) = self.writer {
-                let header_line = headers.join(&(self.delimiter as char).to_string());
+                let header_line = headers
+                    .iter()
+                    .map(|h| quote_field(h, false, self.delimiter, self.quoting))
+                    .collect::<Vec<_>>()
+                    .join(&(self.delimiter as char).to_string());
                 writer.write_all(header_line.as_bytes()).await?;
                 writer.write_all(b"\n").await?;
             }
@@ -67,6 +107,56 @@ impl CsvSink {
     }
 }
 
+/// Opens `path` for writing under `mode`, returning the file and whether it
+/// already held data to append to.
+async fn open_output(path: &str, mode: SinkMode) -> Result<(tokio::fs::File, bool)> {
+    let mut options = OpenOptions::new();
+    options.create(true);
+    match mode {
+        SinkMode::Overwrite => options.write(true).truncate(true),
+        SinkMode::Append => options.append(true),
+        SinkMode::Update => {
+            return Err(PipelineError::Config("File sinks cannot update records in place".to_string()));
+        }
+    };
+    let file = options.open(path).await?;
+    let has_data = mode == SinkMode::Append && file.metadata().await?.len() > 0;
+    Ok((file, has_data))
+}
+
+pub(crate) fn quote_field(value: &str, numeric: bool, delimiter: u8, quoting: QuoteStyle) -> String {
+    let quote = match quoting {
+        QuoteStyle::Never => false,
+        QuoteStyle::Always => true,
+        QuoteStyle::NonNumeric if !numeric => true,
+        _ => value.contains([delimiter as char, '"', '\n', '\r']),
+    };
+    if quote {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn format_delimited_line(record: &Record, headers: &[String], delimiter: u8, quoting: QuoteStyle) -> String {
+    let values: Vec<String> = headers
+        .iter()
+        .map(|key| {
+            let value = record.data.get(key).unwrap_or(&Value::Null);
+            let text = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::Null => String::new(),
+                _ => serde_json::to_string(value).unwrap_or_else(|_| String::new()),
+            };
+            quote_field(&text, value.is_number(), delimiter, quoting)
+        })
+        .collect();
+
+    values.join(&(delimiter as char).to_string())
+}
+
 #[async_trait]
 impl Sink for CsvSink {
     async fn write(&mut self, record: Record) -> Result<()> {
@@ -79,25 +169,8 @@ impl Sink for CsvSink {
             record.data.keys().cloned().collect()
         };
 
-        let values: Vec<String> = headers
-            .iter()
-            .map(|key| {
-                record
-                    .data
-                    .get(key)
-                    .map(|v| match v {
-                        Value::String(s) => s.clone(),
-                        Value::Number(n) => n.to_string(),
-                        Value::Bool(b) => b.to_string(),
-                        Value::Null => String::new(),
-                        _ => serde_json::to_string(v).unwrap_or_else(|_| String::new()),
-                    })
-                    .unwrap_or_default()
-            })
-            .collect();
-
         if let Some(ref mut writer) = self.writer {
-            let line = values.join(&(self.delimiter as char).to_string());
+            let line = format_delimited_line(&record, &headers, self.delimiter, self.quoting);
             writer.write_all(line.as_bytes()).await?;
             writer.write_all(b"\n").await?;
         }
@@ -122,6 +195,7 @@ impl Sink for CsvSink {
 pub struct JsonLinesSink {
     file_path: String,
     writer: Option<BufWriter<tokio::fs::File>>,
+    mode: SinkMode,
 }
 
 impl JsonLinesSink {
@@ -129,17 +203,20 @@ impl JsonLinesSink {
         Self {
             file_path: file_path.as_ref().to_string_lossy().into_owned(),
             writer: None,
+            mode: SinkMode::Overwrite,
         }
     }
 
+    /// With `SinkMode::Append`, adds lines to the end of an existing file
+    /// instead of replacing it.
+    pub fn with_mode(mut self, mode: SinkMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     async fn ensure_writer(&mut self) -> Result<()> {
         if self.writer.is_none() {
-            let file = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(&self.file_path)
-                .await?;
+            let (file, _) = open_output(&self.file_path, self.mode).await?;
             self.writer = Some(BufWriter::new(file));
         }
         Ok(())
@@ -174,3 +251,276 @@ impl Sink for JsonLinesSink {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicateKeyPolicy {
+    Error,
+    KeepFirst,
+    KeepLast,
+}
+
+/// Collects every record and, on `close`, writes them as a single JSON
+/// object keyed by the value of `key_field`. Intended for small reference
+/// datasets, since all records are held in memory until then. A record
+/// without a key, or with a duplicate one under
+/// [`DuplicateKeyPolicy::Error`], is a `PipelineError::Schema`, so it is not
+/// retried.
+pub struct JsonObjectSink {
+    file_path: String,
+    key_field: String,
+    on_duplicate: DuplicateKeyPolicy,
+    pretty: bool,
+    entries: Map<String, Value>,
+}
+
+impl JsonObjectSink {
+    pub fn new<P: AsRef<Path>>(file_path: P, key_field: &str) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            key_field: key_field.to_string(),
+            on_duplicate: DuplicateKeyPolicy::Error,
+            pretty: false,
+            entries: Map::new(),
+        }
+    }
+
+    pub fn with_duplicate_policy(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.on_duplicate = policy;
+        self
+    }
+
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+}
+
+#[async_trait]
+impl Sink for JsonObjectSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let key = match record.get_field(&self.key_field) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => {
+                return Err(PipelineError::Schema(
+                    format!("Record has no value for key field '{}'", self.key_field)
+                ));
+            }
+            Some(other) => other.to_string(),
+        };
+
+        if self.entries.contains_key(&key) {
+            match self.on_duplicate {
+                DuplicateKeyPolicy::Error => {
+                    return Err(PipelineError::Schema(format!("Duplicate key '{}'", key)));
+                }
+                DuplicateKeyPolicy::KeepFirst => return Ok(()),
+                DuplicateKeyPolicy::KeepLast => {}
+            }
+        }
+
+        let value: Map<String, Value> = record.data.into_iter().collect();
+        self.entries.insert(key, Value::Object(value));
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let object = Value::Object(std::mem::take(&mut self.entries));
+        let mut json = if self.pretty {
+            serde_json::to_vec_pretty(&object)?
+        } else {
+            serde_json::to_vec(&object)?
+        };
+        json.push(b'\n');
+
+        tokio::fs::write(&self.file_path, json).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Source;
+    use crate::source::file::CsvSource;
+    use crate::test_support::record;
+    use futures::TryStreamExt;
+    use serde_json::json;
+
+    fn users() -> Vec<Record> {
+        vec![
+            record(json!({"id": "u1", "name": "alice"})),
+            record(json!({"id": "u2", "name": "bob"})),
+            record(json!({"id": "u1", "name": "alicia"})),
+        ]
+    }
+
+    async fn write_csv(mut sink: CsvSink, records: Vec<Record>) -> String {
+        let path = sink.file_path.clone();
+        for record in records {
+            sink.write(record).await.unwrap();
+        }
+        sink.close().await.unwrap();
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    async fn write_keyed(sink: &mut JsonObjectSink, records: Vec<Record>) -> Result<()> {
+        for record in records {
+            sink.write(record).await?;
+        }
+        sink.close().await
+    }
+
+    #[tokio::test]
+    async fn json_object_sink_keys_records_by_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json");
+        let mut sink = JsonObjectSink::new(&path, "id").with_pretty(true);
+        write_keyed(&mut sink, users()[..2].to_vec()).await.unwrap();
+
+        let written: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(
+            written,
+            json!({
+                "u1": {"id": "u1", "name": "alice"},
+                "u2": {"id": "u2", "name": "bob"},
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn json_object_sink_applies_the_duplicate_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json");
+
+        let mut sink = JsonObjectSink::new(&path, "id");
+        assert!(matches!(write_keyed(&mut sink, users()).await, Err(PipelineError::Schema(_))));
+
+        let u1_name = |path: &Path| {
+            let written: Value = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+            written["u1"]["name"].clone()
+        };
+        let mut sink = JsonObjectSink::new(&path, "id").with_duplicate_policy(DuplicateKeyPolicy::KeepFirst);
+        write_keyed(&mut sink, users()).await.unwrap();
+        assert_eq!(u1_name(&path), json!("alice"));
+
+        let mut sink = JsonObjectSink::new(&path, "id").with_duplicate_policy(DuplicateKeyPolicy::KeepLast);
+        write_keyed(&mut sink, users()).await.unwrap();
+        assert_eq!(u1_name(&path), json!("alicia"));
+    }
+
+    #[tokio::test]
+    async fn json_object_sink_requires_the_key_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = JsonObjectSink::new(dir.path().join("users.json"), "id");
+        let result = sink.write(record(json!({"name": "anonymous"}))).await;
+        assert!(matches!(result, Err(PipelineError::Schema(_))));
+    }
+
+    #[tokio::test]
+    async fn csv_sink_quotes_values_per_style() {
+        let dir = tempfile::tempdir().unwrap();
+        let mixed = || {
+            vec![record(json!({
+                "id": 1,
+                "name": "ada",
+                "note": "says \"hi\", twice",
+                "score": 9.5,
+                "active": true,
+                "missing": null,
+            }))]
+        };
+
+        for (quoting, expected) in [
+            (
+                QuoteStyle::Necessary,
+                r#"id,name,note,score,active,missing
+1,ada,"says ""hi"", twice",9.5,true,
+"#,
+            ),
+            (
+                QuoteStyle::Always,
+                r#""id","name","note","score","active","missing"
+"1","ada","says ""hi"", twice","9.5","true",""
+"#,
+            ),
+            (
+                QuoteStyle::NonNumeric,
+                r#""id","name","note","score","active","missing"
+1,"ada","says ""hi"", twice",9.5,"true",""
+"#,
+            ),
+            (
+                QuoteStyle::Never,
+                r#"id,name,note,score,active,missing
+1,ada,says "hi", twice,9.5,true,
+"#,
+            ),
+        ] {
+            let sink = CsvSink::new(dir.path().join("mixed.csv")).with_quoting(quoting);
+            assert_eq!(write_csv(sink, mixed()).await, expected, "{:?}", quoting);
+        }
+    }
+
+    #[tokio::test]
+    async fn csv_quoting_round_trips_through_the_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quoted.csv");
+        let written = vec![
+            record(json!({"id": "1", "text": "a,b"})),
+            record(json!({"id": "2", "text": "he said \"hi\""})),
+        ];
+        write_csv(CsvSink::new(&path), written.clone()).await;
+
+        let read: Vec<Record> = CsvSource::new(&path).read().await.unwrap().try_collect().await.unwrap();
+        assert_eq!(read.len(), written.len());
+        for (read, written) in read.iter().zip(&written) {
+            assert_eq!(read.data, written.data);
+        }
+    }
+
+    #[tokio::test]
+    async fn csv_sink_keeps_insertion_order_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let rows = || {
+            let mut row = Record::new();
+            for name in ["zeta", "alpha", "mid", "beta", "omega", "gamma", "delta", "kappa"] {
+                row.set_field(name.to_string(), json!(name.len()));
+            }
+            vec![row]
+        };
+
+        let first = write_csv(CsvSink::new(dir.path().join("first.csv")), rows()).await;
+        let second = write_csv(CsvSink::new(dir.path().join("second.csv")), rows()).await;
+        assert_eq!(first, second);
+        assert_eq!(first.lines().next(), Some("zeta,alpha,mid,beta,omega,gamma,delta,kappa"));
+    }
+
+    #[tokio::test]
+    async fn append_mode_adds_to_the_previous_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("users.csv");
+        for run in users().chunks(2) {
+            let sink = CsvSink::new(&csv_path).with_mode(SinkMode::Append);
+            write_csv(sink, run.to_vec()).await;
+        }
+        assert_eq!(
+            std::fs::read_to_string(&csv_path).unwrap(),
+            "id,name\nu1,alice\nu2,bob\nu1,alicia\n"
+        );
+
+        let jsonl_path = dir.path().join("users.jsonl");
+        for run in users().chunks(2) {
+            let mut sink = JsonLinesSink::new(&jsonl_path).with_mode(SinkMode::Append);
+            for record in run.iter().cloned() {
+                sink.write(record).await.unwrap();
+            }
+            sink.close().await.unwrap();
+        }
+        let names: Vec<Value> = std::fs::read_to_string(&jsonl_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["name"].clone())
+            .collect();
+        assert_eq!(names, [json!("alice"), json!("bob"), json!("alicia")]);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::core::arrow::batch_from_records;
+use crate::core::{PipelineError, Record, Result, Sink};
+use crate::sink::buffered::BufferedWrite;
+use async_trait::async_trait;
+use iceberg::arrow::schema_to_arrow_schema;
+use iceberg::spec::{DataFile, DataFileFormat, PrimitiveType, Type};
+use iceberg::table::Table;
+use iceberg::transaction::{AddColumn, ApplyTransactionAction, Transaction};
+use iceberg::writer::base_writer::data_file_writer::DataFileWriterBuilder;
+use iceberg::writer::file_writer::location_generator::{DefaultFileNameGenerator, DefaultLocationGenerator};
+use iceberg::writer::file_writer::rolling_writer::RollingFileWriterBuilder;
+use iceberg::writer::file_writer::ParquetWriterBuilder;
+use iceberg::writer::{IcebergWriter, IcebergWriterBuilder};
+use iceberg::{Catalog, TableIdent};
+use parquet::file::properties::WriterProperties;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+fn sink_error(e: iceberg::Error) -> PipelineError {
+    PipelineError::Sink(format!("Iceberg error: {}", e))
+}
+
+fn iceberg_type(value: &Value) -> Type {
+    Type::Primitive(match value {
+        Value::Bool(_) => PrimitiveType::Boolean,
+        Value::Number(n) if n.is_i64() || n.is_u64() => PrimitiveType::Long,
+        Value::Number(_) => PrimitiveType::Double,
+        _ => PrimitiveType::String,
+    })
+}
+
+/// Appends records to an unpartitioned Iceberg table as Parquet data files,
+/// committing them as one new snapshot when the sink is closed. Nothing is
+/// visible to readers before then, and a run that fails never commits.
+///
+/// Top-level fields the table does not have yet are added as optional
+/// columns, typed from their first non-null value (`long`, `double`,
+/// `boolean`, or `string` for anything else). Schema changes are committed
+/// as soon as they are seen, since the data files written afterwards depend
+/// on the new column ids.
+pub struct IcebergSink {
+    catalog: Arc<dyn Catalog>,
+    table_ident: TableIdent,
+    batch_size: usize,
+    table: Option<Table>,
+    // Only reached through `&mut self`; the mutex just makes the sink `Sync`,
+    // which the Parquet writer is not.
+    writer: Option<Mutex<Box<dyn IcebergWriter>>>,
+    buffer: Vec<Record>,
+    data_files: Vec<DataFile>,
+}
+
+impl IcebergSink {
+    pub fn new(catalog: Arc<dyn Catalog>, table_ident: TableIdent) -> Self {
+        Self {
+            catalog,
+            table_ident,
+            batch_size: 10_000,
+            table: None,
+            writer: None,
+            buffer: Vec::new(),
+            data_files: Vec::new(),
+        }
+    }
+
+    /// Sets how many records are buffered before being written as one batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn table(&mut self) -> Result<&Table> {
+        if self.table.is_none() {
+            let table = self.catalog.load_table(&self.table_ident).await.map_err(sink_error)?;
+            if !table.metadata().default_partition_spec().is_unpartitioned() {
+                return Err(PipelineError::Sink(format!(
+                    "Iceberg table '{}' is partitioned, which IcebergSink does not support",
+                    self.table_ident
+                )));
+            }
+            self.table = Some(table);
+        }
+        Ok(self.table.as_ref().unwrap())
+    }
+
+    /// Adds optional columns for buffered fields the table does not have.
+    async fn evolve_schema(&mut self) -> Result<()> {
+        let existing: HashSet<String> = self
+            .table()
+            .await?
+            .metadata()
+            .current_schema()
+            .as_struct()
+            .fields()
+            .iter()
+            .map(|field| field.name.clone())
+            .collect();
+
+        let mut added = HashSet::new();
+        let mut columns = Vec::new();
+        for record in &self.buffer {
+            for (name, value) in &record.data {
+                if !value.is_null() && !existing.contains(name) && added.insert(name.clone()) {
+                    columns.push(AddColumn::optional(name, iceberg_type(value)));
+                }
+            }
+        }
+        if columns.is_empty() {
+            return Ok(());
+        }
+
+        // Files written so far use the old schema and stay valid; close them
+        // out so later files are written with the new one.
+        self.close_writer().await?;
+        let transaction = Transaction::new(self.table().await?);
+        let update = columns
+            .into_iter()
+            .fold(transaction.update_schema(), |update, column| update.add_column(column));
+        let transaction = update.apply(transaction).map_err(sink_error)?;
+        self.table = Some(transaction.commit(self.catalog.as_ref()).await.map_err(sink_error)?);
+        Ok(())
+    }
+
+    async fn writer(&mut self) -> Result<&mut Box<dyn IcebergWriter>> {
+        if self.writer.is_none() {
+            let table = self.table().await?;
+            let schema = table.metadata().current_schema().clone();
+            let location = DefaultLocationGenerator::new(table.metadata()).map_err(sink_error)?;
+            let file_names = DefaultFileNameGenerator::new(
+                "dpipeline".to_string(),
+                Some(uuid_suffix()),
+                DataFileFormat::Parquet,
+            );
+            let rolling = RollingFileWriterBuilder::new_with_default_file_size(
+                ParquetWriterBuilder::new(WriterProperties::builder().build(), schema),
+                table.file_io().clone(),
+                location,
+                file_names,
+            );
+            let writer = DataFileWriterBuilder::new(rolling).build(None).await.map_err(sink_error)?;
+            self.writer = Some(Mutex::new(Box::new(writer)));
+        }
+        Ok(self.writer.as_mut().unwrap().get_mut().unwrap())
+    }
+
+    async fn close_writer(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            let mut writer = writer.into_inner().unwrap();
+            self.data_files.extend(writer.close().await.map_err(sink_error)?);
+        }
+        Ok(())
+    }
+
+    async fn write_buffer(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.evolve_schema().await?;
+
+        let schema = self.table().await?.metadata().current_schema().clone();
+        let arrow_schema = schema_to_arrow_schema(&schema).map_err(sink_error)?;
+        let batch = batch_from_records(&self.buffer, Arc::new(arrow_schema))?;
+        self.writer().await?.write(batch).await.map_err(sink_error)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+// Distinguishes file names written by concurrent or successive runs.
+fn uuid_suffix() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("{:x}-{:x}", nanos, std::process::id())
+}
+
+#[async_trait]
+impl BufferedWrite for IcebergSink {
+    type Item = Record;
+
+    fn buffer(&mut self) -> &mut Vec<Record> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        self.write_buffer().await
+    }
+}
+
+#[async_trait]
+impl Sink for IcebergSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.buffer_write(record).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.write_buffer().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.write_buffer().await?;
+        self.close_writer().await?;
+        if self.data_files.is_empty() {
+            return Ok(());
+        }
+
+        let data_files = std::mem::take(&mut self.data_files);
+        let transaction = Transaction::new(self.table().await?);
+        let append = transaction.fast_append().add_data_files(data_files);
+        let transaction = append.apply(transaction).map_err(sink_error)?;
+        self.table = Some(transaction.commit(self.catalog.as_ref()).await.map_err(sink_error)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::arrow::records_from_batch;
+    use crate::test_support::record;
+    use futures::TryStreamExt;
+    use iceberg::memory::{MemoryCatalogBuilder, MEMORY_CATALOG_WAREHOUSE};
+    use iceberg::spec::{NestedField, Schema as IcebergSchema};
+    use iceberg::{CatalogBuilder, NamespaceIdent, TableCreation};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    async fn catalog_with_table() -> (Arc<dyn Catalog>, TableIdent) {
+        let catalog = MemoryCatalogBuilder::default()
+            .load(
+                "memory",
+                HashMap::from([(MEMORY_CATALOG_WAREHOUSE.to_string(), "memory://warehouse".to_string())]),
+            )
+            .await
+            .unwrap();
+        let namespace = NamespaceIdent::new("db".to_string());
+        catalog.create_namespace(&namespace, HashMap::new()).await.unwrap();
+
+        let schema = IcebergSchema::builder()
+            .with_fields(vec![
+                NestedField::required(1, "id", Type::Primitive(PrimitiveType::Long)).into(),
+                NestedField::optional(2, "name", Type::Primitive(PrimitiveType::String)).into(),
+            ])
+            .build()
+            .unwrap();
+        let creation = TableCreation::builder().name("events".to_string()).schema(schema).build();
+        catalog.create_table(&namespace, creation).await.unwrap();
+        (Arc::new(catalog), TableIdent::new(namespace, "events".to_string()))
+    }
+
+    async fn read_back(catalog: &dyn Catalog, table_ident: &TableIdent) -> Vec<Value> {
+        let table = catalog.load_table(table_ident).await.unwrap();
+        let batches: Vec<_> = table
+            .scan()
+            .select_all()
+            .build()
+            .unwrap()
+            .to_arrow()
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let mut rows: Vec<Value> = batches
+            .iter()
+            .flat_map(|batch| records_from_batch(batch).unwrap())
+            .map(|record| Value::Object(record.data.into_iter().collect()))
+            .collect();
+        rows.sort_by_key(|row| row["id"].as_i64());
+        rows
+    }
+
+    #[tokio::test]
+    async fn written_records_are_read_back_after_close() {
+        let (catalog, table_ident) = catalog_with_table().await;
+        let mut sink = IcebergSink::new(catalog.clone(), table_ident.clone()).with_batch_size(1);
+
+        sink.write(record(json!({"id": 1, "name": "ada"}))).await.unwrap();
+        sink.write(record(json!({"id": 2, "name": "grace", "score": 9.5}))).await.unwrap();
+        let table = catalog.load_table(&table_ident).await.unwrap();
+        assert!(table.metadata().current_snapshot().is_none());
+
+        sink.close().await.unwrap();
+
+        let table = catalog.load_table(&table_ident).await.unwrap();
+        let score = table.metadata().current_schema().field_by_name("score").unwrap().clone();
+        assert!(!score.required);
+        assert_eq!(*score.field_type, Type::Primitive(PrimitiveType::Double));
+        assert_eq!(
+            read_back(catalog.as_ref(), &table_ident).await,
+            vec![
+                json!({"id": 1, "name": "ada", "score": null}),
+                json!({"id": 2, "name": "grace", "score": 9.5}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn an_empty_run_commits_no_snapshot() {
+        let (catalog, table_ident) = catalog_with_table().await;
+        IcebergSink::new(catalog.clone(), table_ident.clone()).close().await.unwrap();
+
+        let table = catalog.load_table(&table_ident).await.unwrap();
+        assert!(table.metadata().current_snapshot().is_none());
+    }
+}
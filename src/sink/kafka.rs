@@ -0,0 +1,126 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use async_trait::async_trait;
+use futures::future::join_all;
+use rdkafka::ClientConfig;
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use rdkafka::util::Timeout;
+use serde_json::Value;
+use std::time::Duration;
+
+fn sink_error(e: KafkaError) -> PipelineError {
+    PipelineError::Sink(format!("Kafka error: {}", e))
+}
+
+/// Produces each record to a Kafka topic as a JSON object of its fields.
+///
+/// With a key field, that field's value becomes the message key, so records
+/// sharing it land on the same partition; a record without the field is
+/// sent unkeyed. `write` waits for the broker to acknowledge the message and
+/// `write_batch` sends the whole batch before waiting. `flush`, and so
+/// `close`, waits up to the flush timeout for outstanding deliveries.
+pub struct KafkaSink {
+    config: ClientConfig,
+    topic: String,
+    key_field: Option<String>,
+    flush_timeout: Duration,
+    producer: Option<FutureProducer>,
+}
+
+impl KafkaSink {
+    pub fn new(brokers: &str, topic: &str) -> Self {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", brokers);
+        Self {
+            config,
+            topic: topic.to_string(),
+            key_field: None,
+            flush_timeout: Duration::from_secs(30),
+            producer: None,
+        }
+    }
+
+    /// Sets a librdkafka producer property, such as `acks` or
+    /// `compression.type`.
+    pub fn with_config(mut self, key: &str, value: &str) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn with_key_field(mut self, name: &str) -> Self {
+        self.key_field = Some(name.to_string());
+        self
+    }
+
+    pub fn with_flush_timeout(mut self, flush_timeout: Duration) -> Self {
+        self.flush_timeout = flush_timeout;
+        self
+    }
+
+    fn producer(&mut self) -> Result<FutureProducer> {
+        if self.producer.is_none() {
+            self.producer = Some(self.config.create().map_err(sink_error)?);
+        }
+        Ok(self.producer.clone().unwrap())
+    }
+
+    /// Returns the message key and JSON payload for `record`.
+    fn encode(&self, record: &Record) -> Result<(Option<String>, Vec<u8>)> {
+        let key = self
+            .key_field
+            .as_ref()
+            .and_then(|name| record.get_field(name))
+            .and_then(|value| match value {
+                Value::Null => None,
+                Value::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            });
+        Ok((key, serde_json::to_vec(&record.data)?))
+    }
+
+    async fn produce(&self, producer: &FutureProducer, key: Option<&str>, payload: &[u8]) -> Result<()> {
+        let mut message: FutureRecord<'_, str, [u8]> = FutureRecord::to(&self.topic).payload(payload);
+        if let Some(key) = key {
+            message = message.key(key);
+        }
+        producer
+            .send(message, Timeout::Never)
+            .await
+            .map(|_| ())
+            .map_err(|(e, _)| sink_error(e))
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let producer = self.producer()?;
+        let (key, payload) = self.encode(&record)?;
+        self.produce(&producer, key.as_deref(), &payload).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        let producer = self.producer()?;
+        let messages = records
+            .iter()
+            .map(|record| self.encode(record))
+            .collect::<Result<Vec<_>>>()?;
+
+        let deliveries = messages
+            .iter()
+            .map(|(key, payload)| self.produce(&producer, key.as_deref(), payload));
+        join_all(deliveries).await.into_iter().collect()
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let Some(producer) = self.producer.clone() else {
+            return Ok(());
+        };
+        let timeout = self.flush_timeout;
+        // librdkafka's flush blocks until delivery, so it runs off the runtime.
+        tokio::task::spawn_blocking(move || producer.flush(timeout))
+            .await
+            .map_err(|e| PipelineError::Sink(format!("Kafka flush task failed: {}", e)))?
+            .map_err(sink_error)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::core::{Record, Result, Sink};
+use async_trait::async_trait;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub bytes: u64,
+    pub records: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Manifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+/// Wraps a file sink and, once the inner sink is closed, writes a
+/// `manifest.json` describing the output file's size, record count and
+/// SHA-256 so consumers can verify the delivery.
+pub struct ManifestSink {
+    inner: Box<dyn Sink>,
+    output_path: PathBuf,
+    manifest_path: PathBuf,
+    records: u64,
+}
+
+impl ManifestSink {
+    pub fn new<P: AsRef<Path>>(inner: Box<dyn Sink>, output_path: P) -> Self {
+        let output_path = output_path.as_ref().to_path_buf();
+        let manifest_path = output_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join("manifest.json");
+        Self {
+            inner,
+            output_path,
+            manifest_path,
+            records: 0,
+        }
+    }
+
+    pub fn with_manifest_path<P: AsRef<Path>>(mut self, manifest_path: P) -> Self {
+        self.manifest_path = manifest_path.as_ref().to_path_buf();
+        self
+    }
+
+    async fn describe_output(&self) -> Result<ManifestEntry> {
+        let mut file = File::open(&self.output_path).await?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut bytes = 0u64;
+
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            bytes += read as u64;
+        }
+
+        Ok(ManifestEntry {
+            path: self.output_path.to_string_lossy().into_owned(),
+            bytes,
+            records: self.records,
+            sha256: format!("{:x}", hasher.finalize()),
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for ManifestSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.inner.write(record).await?;
+        self.records += 1;
+        Ok(())
+    }
+
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        let count = records.len() as u64;
+        self.inner.write_batch(records).await?;
+        self.records += count;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.inner.close().await?;
+
+        let manifest = Manifest {
+            files: vec![self.describe_output().await?],
+        };
+        tokio::fs::write(&self.manifest_path, serde_json::to_vec_pretty(&manifest)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::file::JsonLinesSink;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn manifest_matches_the_written_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.jsonl");
+        let mut sink = ManifestSink::new(Box::new(JsonLinesSink::new(&output)), &output);
+        for id in 0..3 {
+            let mut record = Record::new();
+            record.set_field("id".to_string(), json!(id));
+            sink.write(record).await.unwrap();
+        }
+        sink.write_batch(vec![Record::new(), Record::new()]).await.unwrap();
+        sink.close().await.unwrap();
+
+        let written = std::fs::read(&output).unwrap();
+        let manifest: Value =
+            serde_json::from_slice(&std::fs::read(dir.path().join("manifest.json")).unwrap()).unwrap();
+        let entry = &manifest["files"][0];
+        assert_eq!(entry["path"], json!(output.to_string_lossy()));
+        assert_eq!(entry["records"], json!(5));
+        assert_eq!(entry["bytes"], json!(written.len()));
+        assert_eq!(entry["sha256"], json!(format!("{:x}", Sha256::digest(&written))));
+    }
+}
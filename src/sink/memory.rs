@@ -0,0 +1,36 @@
+use crate::core::{Record, Result, Sink};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Collects written records in memory, for tests.
+///
+/// Clones share the same records, so a test can keep a clone to inspect
+/// after handing the sink to a pipeline.
+#[derive(Clone, Default)]
+pub struct VecSink {
+    records: Arc<Mutex<Vec<Record>>>,
+}
+
+impl VecSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The records written so far, in order.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Sink for VecSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.records.lock().unwrap().push(record);
+        Ok(())
+    }
+
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        self.records.lock().unwrap().extend(records);
+        Ok(())
+    }
+}
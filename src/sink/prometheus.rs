@@ -0,0 +1,279 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use crate::sink::buffered::BufferedWrite;
+use async_trait::async_trait;
+use serde_json::Value;
+
+// The subset of the remote-write protobuf schema (prometheus/prompb) that
+// carries samples.
+#[derive(Clone, PartialEq, prost::Message)]
+struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    timeseries: Vec<TimeSeries>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct TimeSeries {
+    #[prost(message, repeated, tag = "1")]
+    labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    samples: Vec<Sample>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Label {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Sample {
+    #[prost(double, tag = "1")]
+    value: f64,
+    #[prost(int64, tag = "2")]
+    timestamp: i64,
+}
+
+/// Ships records as Prometheus remote-write samples. Each record needs a
+/// metric name, a numeric value and a timestamp in milliseconds since the
+/// Unix epoch, read from the `name`, `value` and `timestamp` fields by
+/// default; an optional `labels` object supplies extra string labels.
+/// Records are sent in snappy-compressed protobuf batches.
+pub struct PromRemoteWriteSink {
+    client: reqwest::Client,
+    endpoint: String,
+    name_field: String,
+    value_field: String,
+    timestamp_field: String,
+    labels_field: String,
+    bearer_token: Option<String>,
+    batch_size: usize,
+    buffer: Vec<TimeSeries>,
+}
+
+impl PromRemoteWriteSink {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.to_string(),
+            name_field: "name".to_string(),
+            value_field: "value".to_string(),
+            timestamp_field: "timestamp".to_string(),
+            labels_field: "labels".to_string(),
+            bearer_token: None,
+            batch_size: 500,
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn with_fields(mut self, name: &str, value: &str, timestamp: &str, labels: &str) -> Self {
+        self.name_field = name.to_string();
+        self.value_field = value.to_string();
+        self.timestamp_field = timestamp.to_string();
+        self.labels_field = labels.to_string();
+        self
+    }
+
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn to_series(&self, record: &Record) -> Result<TimeSeries> {
+        let missing = |field: &str| PipelineError::Sink(format!("Record has no valid metric field '{}'", field));
+
+        let name = record
+            .get_field(&self.name_field)
+            .and_then(Value::as_str)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| missing(&self.name_field))?;
+        let value = match record.get_field(&self.value_field) {
+            Some(Value::Number(n)) => n.as_f64(),
+            Some(Value::String(s)) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| missing(&self.value_field))?;
+        let timestamp = match record.get_field(&self.timestamp_field) {
+            Some(Value::Number(n)) => n.as_i64(),
+            Some(Value::String(s)) => s.trim().parse::<i64>().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| missing(&self.timestamp_field))?;
+
+        let mut labels = vec![Label {
+            name: "__name__".to_string(),
+            value: name.to_string(),
+        }];
+        match record.get_field(&self.labels_field) {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(map)) => {
+                for (name, value) in map {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    labels.push(Label { name: name.clone(), value });
+                }
+            }
+            Some(_) => return Err(missing(&self.labels_field)),
+        }
+        // Remote write requires labels sorted by name.
+        labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(TimeSeries {
+            labels,
+            samples: vec![Sample { value, timestamp }],
+        })
+    }
+}
+
+#[async_trait]
+impl BufferedWrite for PromRemoteWriteSink {
+    type Item = TimeSeries;
+
+    fn buffer(&mut self) -> &mut Vec<TimeSeries> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        Sink::flush(self).await
+    }
+}
+
+#[async_trait]
+impl Sink for PromRemoteWriteSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let series = self.to_series(&record)?;
+        self.buffer_write(series).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        // The buffer is only cleared once the write is accepted, so a failed
+        // flush can be retried.
+        let request = WriteRequest {
+            timeseries: self.buffer.clone(),
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&prost::Message::encode_to_vec(&request))
+            .map_err(|e| PipelineError::Sink(format!("Snappy compression failed: {}", e)))?;
+
+        let mut post = self
+            .client
+            .post(&self.endpoint)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+            .header(reqwest::header::CONTENT_ENCODING, "snappy")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+            .body(body);
+        if let Some(ref token) = self.bearer_token {
+            post = post.bearer_auth(token);
+        }
+
+        let response = post
+            .send()
+            .await
+            .map_err(|e| PipelineError::Sink(format!("Remote write request error: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(PipelineError::Sink(format!("Remote write failed with {}: {}", status, body)));
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use crate::test_support::serve;
+    use serde_json::json;
+
+    fn decode(body: &[u8]) -> WriteRequest {
+        let raw = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+        <WriteRequest as prost::Message>::decode(raw.as_slice()).unwrap()
+    }
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_samples_that_decode_to_the_records() {
+        let (base, requests) = serve(|_| (200, String::new())).await;
+        let mut sink = PromRemoteWriteSink::new(&format!("{}/api/v1/write", base)).with_batch_size(2);
+
+        sink.write(record(json!({
+            "name": "http_requests_total",
+            "value": 42,
+            "timestamp": 1_700_000_000_000i64,
+            "labels": {"method": "GET", "code": 200}
+        })))
+        .await
+        .unwrap();
+        sink.write(record(json!({"name": "up", "value": "1", "timestamp": "1700000000001"})))
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/api/v1/write");
+        assert_eq!(
+            decode(&requests[0].body).timeseries,
+            vec![
+                TimeSeries {
+                    labels: vec![
+                        label("__name__", "http_requests_total"),
+                        label("code", "200"),
+                        label("method", "GET"),
+                    ],
+                    samples: vec![Sample {
+                        value: 42.0,
+                        timestamp: 1_700_000_000_000,
+                    }],
+                },
+                TimeSeries {
+                    labels: vec![label("__name__", "up")],
+                    samples: vec![Sample {
+                        value: 1.0,
+                        timestamp: 1_700_000_000_001,
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn records_missing_metric_fields_error() {
+        let (base, requests) = serve(|_| (200, String::new())).await;
+        let mut sink = PromRemoteWriteSink::new(&base);
+
+        for input in [
+            json!({"value": 1, "timestamp": 1}),
+            json!({"name": "up", "value": "high", "timestamp": 1}),
+            json!({"name": "up", "value": 1}),
+        ] {
+            assert!(sink.write(record(input)).await.is_err());
+        }
+        sink.flush().await.unwrap();
+        assert!(requests.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use crate::sink::buffered::BufferedWrite;
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+pub enum RedisWriteMode {
+    /// Write each record as a hash at `{key_prefix}{value of key_field}`.
+    Hash { key_field: String, key_prefix: String },
+    /// Append each record as an entry of `stream`.
+    Stream { stream: String },
+}
+
+/// Writes records to Redis as hashes or stream entries, sending each batch
+/// as one pipeline. String fields are stored as-is, other values as JSON,
+/// and null fields are left out.
+pub struct RedisSink {
+    client: redis::Client,
+    mode: RedisWriteMode,
+    batch_size: usize,
+    buffer: Vec<Record>,
+    connection: Option<MultiplexedConnection>,
+}
+
+impl RedisSink {
+    pub fn new(url: &str, mode: RedisWriteMode) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| PipelineError::Config(format!("Invalid Redis URL '{}': {}", url, e)))?;
+        Ok(Self {
+            client,
+            mode,
+            batch_size: 500,
+            buffer: Vec::new(),
+            connection: None,
+        })
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn build_pipeline(&self, records: &[Record]) -> Result<redis::Pipeline> {
+        let mut pipeline = redis::pipe();
+        for record in records {
+            let mut fields: Vec<(&str, String)> = record
+                .data
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (name.as_str(), value)
+                })
+                .collect();
+            fields.sort_unstable();
+
+            match self.mode {
+                RedisWriteMode::Hash { ref key_field, ref key_prefix } => {
+                    let key = match record.get_field(key_field) {
+                        Some(Value::String(s)) => s.clone(),
+                        Some(value) if !value.is_null() => value.to_string(),
+                        _ => {
+                            return Err(PipelineError::Sink(format!("Record has no hash key field '{}'", key_field)));
+                        }
+                    };
+                    pipeline.hset_multiple(format!("{}{}", key_prefix, key), &fields).ignore();
+                }
+                RedisWriteMode::Stream { ref stream } => {
+                    pipeline.xadd(stream, "*", &fields).ignore();
+                }
+            }
+        }
+        Ok(pipeline)
+    }
+}
+
+#[async_trait]
+impl BufferedWrite for RedisSink {
+    type Item = Record;
+
+    fn buffer(&mut self) -> &mut Vec<Record> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        Sink::flush(self).await
+    }
+}
+
+#[async_trait]
+impl Sink for RedisSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.buffer_write(record).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        // The buffer is only cleared once the commands succeed, so a failed
+        // flush can be retried.
+        let pipeline = self.build_pipeline(&self.buffer)?;
+        if self.connection.is_none() {
+            let connection = self
+                .client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(sink_error)?;
+            self.connection = Some(connection);
+        }
+
+        let connection = self.connection.as_mut().unwrap();
+        let _: () = pipeline.query_async(connection).await.map_err(sink_error)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+fn sink_error(e: redis::RedisError) -> PipelineError {
+    PipelineError::Sink(format!("Redis error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Source, SourceMode};
+    use crate::source::redis::RedisStreamSource;
+    use crate::test_support::record;
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn hash_sink() -> RedisSink {
+        RedisSink::new(
+            "redis://127.0.0.1/",
+            RedisWriteMode::Hash {
+                key_field: "id".to_string(),
+                key_prefix: "user:".to_string(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn hash_mode_sets_the_non_null_fields_at_the_prefixed_key() {
+        let pipeline = hash_sink()
+            .build_pipeline(&[record(json!({"id": 7, "name": "ada", "age": 36, "email": null}))])
+            .unwrap();
+        let expected = redis::pipe()
+            .hset_multiple("user:7", &[("age", "36"), ("id", "7"), ("name", "ada")])
+            .ignore()
+            .get_packed_pipeline();
+        assert_eq!(pipeline.get_packed_pipeline(), expected);
+    }
+
+    #[test]
+    fn hash_mode_requires_the_key_field() {
+        assert!(hash_sink().build_pipeline(&[record(json!({"name": "ada"}))]).is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Redis server at REDIS_URL"]
+    async fn stream_entries_round_trip_through_the_stream_source() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+        let stream = format!(
+            "dpipeline-test-{}",
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()
+        );
+
+        let mut sink = RedisSink::new(&url, RedisWriteMode::Stream { stream: stream.clone() })
+            .unwrap()
+            .with_batch_size(2);
+        for (id, name) in [(1, "ada"), (2, "grace"), (3, "edsger")] {
+            sink.write(record(json!({"id": id, "name": name}))).await.unwrap();
+        }
+        sink.flush().await.unwrap();
+
+        let source = RedisStreamSource::new(&url, &stream, "test", "consumer-1")
+            .unwrap()
+            .with_mode(SourceMode::Batch)
+            .with_block(Duration::from_millis(100));
+        let records: Vec<Record> = source.read().await.unwrap().map(|r| r.unwrap()).collect().await;
+        source.close().await.unwrap();
+
+        let rows: Vec<(&Value, &Value)> = records
+            .iter()
+            .map(|r| (r.get_field("id").unwrap(), r.get_field("name").unwrap()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (&json!("1"), &json!("ada")),
+                (&json!("2"), &json!("grace")),
+                (&json!("3"), &json!("edsger")),
+            ]
+        );
+
+        let client = redis::Client::open(url.as_str()).unwrap();
+        let mut connection = client.get_multiplexed_async_connection().await.unwrap();
+        let _: () = redis::cmd("DEL").arg(&stream).query_async(&mut connection).await.unwrap();
+    }
+}
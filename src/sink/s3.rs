@@ -0,0 +1,892 @@
+use crate::core::{timestamp_millis, PipelineError, Record, Result, Sink};
+use crate::sink::file::{format_delimited_line, quote_field, QuoteStyle};
+use crate::source::s3::Format;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::multipart::{MultipartStore, PartId};
+use object_store::{MultipartId, ObjectStore, PutOptions, PutPayload};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::{JoinError, JoinSet};
+
+const MILLIS_PER_HOUR: i64 = 3_600_000;
+
+/// Parts of a multipart upload allowed in flight at once, bounding the
+/// memory a fast producer can tie up at about this many 5 MiB parts.
+const MAX_CONCURRENT_PARTS: usize = 8;
+
+/// The smallest part S3 accepts, other than an upload's last.
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+fn sink_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Sink(format!("S3 error: {}", e))
+}
+
+fn run_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("{:x}", nanos)
+}
+
+struct HourBucket {
+    encoder: GzEncoder<Vec<u8>>,
+    records: usize,
+    /// Whether the gzip stream has been finished for an upload, which then
+    /// failed; nothing more can be added and the upload is tried again.
+    sealed: bool,
+}
+
+/// Archives records as gzipped NDJSON objects in S3, partitioned by the hour
+/// of an event-time field: `prefix/yyyy/mm/dd/hh/part-<run>-<n>.jsonl.gz`.
+///
+/// Each hour is buffered in memory and uploaded as one part once event time
+/// has moved past it by more than the allowed lateness, when a record arrives
+/// for it after it has reached `max_records_per_part`, on `flush` and on
+/// `close`. A record arriving for an hour that was already uploaded starts a
+/// new part for that hour. Event times are read with [`timestamp_millis`]; a
+/// missing or unreadable time is an error.
+///
+/// An hour stays buffered until its upload succeeds, and a write uploads
+/// whatever is due before buffering its record, so a write that fails has
+/// not taken its record and can be retried.
+pub struct S3TimePartitionedSink {
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+    time_field: String,
+    allowed_lateness: Duration,
+    max_records_per_part: usize,
+    run_id: String,
+    buckets: BTreeMap<i64, HourBucket>,
+    parts_written: usize,
+    latest_hour: Option<i64>,
+}
+
+impl S3TimePartitionedSink {
+    /// Writes to `bucket` with credentials and region taken from the standard
+    /// `AWS_*` environment variables.
+    pub fn new(bucket: &str, prefix: &str, time_field: &str) -> Result<Self> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(sink_error)?;
+        Ok(Self::with_store(Arc::new(store), prefix, time_field))
+    }
+
+    /// Writes to an already configured store, e.g. an S3-compatible service
+    /// built with a custom endpoint.
+    pub fn with_store(store: Arc<dyn ObjectStore>, prefix: &str, time_field: &str) -> Self {
+        Self {
+            store,
+            prefix: prefix.trim_matches('/').to_string(),
+            time_field: time_field.to_string(),
+            allowed_lateness: Duration::from_secs(3600),
+            max_records_per_part: 100_000,
+            run_id: run_id(),
+            buckets: BTreeMap::new(),
+            parts_written: 0,
+            latest_hour: None,
+        }
+    }
+
+    /// How far behind the latest event time an hour stays open for late
+    /// records before it is uploaded.
+    pub fn with_allowed_lateness(mut self, allowed_lateness: Duration) -> Self {
+        self.allowed_lateness = allowed_lateness;
+        self
+    }
+
+    pub fn with_max_records_per_part(mut self, max_records_per_part: usize) -> Self {
+        self.max_records_per_part = max_records_per_part.max(1);
+        self
+    }
+
+    fn object_path(&self, hour: i64) -> ObjectPath {
+        let start = DateTime::<Utc>::from_timestamp_millis(hour * MILLIS_PER_HOUR).unwrap_or_default();
+        let name = format!(
+            "{}/part-{}-{:05}.jsonl.gz",
+            start.format("%Y/%m/%d/%H"),
+            self.run_id,
+            self.parts_written
+        );
+        if self.prefix.is_empty() {
+            ObjectPath::from(name)
+        } else {
+            ObjectPath::from(format!("{}/{}", self.prefix, name))
+        }
+    }
+
+    async fn upload(&mut self, hour: i64) -> Result<()> {
+        let path = self.object_path(hour);
+        let Some(bucket) = self.buckets.get_mut(&hour) else {
+            return Ok(());
+        };
+        if !bucket.sealed {
+            bucket.encoder.try_finish()?;
+            bucket.sealed = true;
+        }
+        let body = bucket.encoder.get_ref().clone();
+        self.store
+            .put_opts(&path, PutPayload::from(body), PutOptions::default())
+            .await
+            .map_err(sink_error)?;
+        self.buckets.remove(&hour);
+        self.parts_written += 1;
+        Ok(())
+    }
+
+    /// Uploads every hour that has fallen out of the lateness window behind
+    /// `latest`.
+    async fn rotate(&mut self, latest: i64) -> Result<()> {
+        let lateness_hours = (self.allowed_lateness.as_millis() as u64).div_ceil(MILLIS_PER_HOUR as u64) as i64;
+        let closed: Vec<i64> = self
+            .buckets
+            .keys()
+            .copied()
+            .take_while(|hour| *hour < latest - lateness_hours)
+            .collect();
+        for hour in closed {
+            self.upload(hour).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sink for S3TimePartitionedSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let millis = record
+            .get_field(&self.time_field)
+            .and_then(timestamp_millis)
+            .ok_or_else(|| {
+                PipelineError::Sink(format!("Field '{}' is missing or not a timestamp", self.time_field))
+            })?;
+        let hour = millis.div_euclid(MILLIS_PER_HOUR);
+        let mut line = serde_json::to_vec(&record.data)?;
+        line.push(b'\n');
+
+        let due = self
+            .buckets
+            .get(&hour)
+            .is_some_and(|bucket| bucket.sealed || bucket.records >= self.max_records_per_part);
+        if due {
+            self.upload(hour).await?;
+        }
+        if self.latest_hour.is_none_or(|latest| hour > latest) {
+            self.rotate(hour).await?;
+            self.latest_hour = Some(hour);
+        }
+
+        let bucket = self.buckets.entry(hour).or_insert_with(|| HourBucket {
+            encoder: GzEncoder::new(Vec::new(), Compression::default()),
+            records: 0,
+            sealed: false,
+        });
+        bucket.encoder.write_all(&line)?;
+        bucket.records += 1;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let hours: Vec<i64> = self.buckets.keys().copied().collect();
+        for hour in hours {
+            self.upload(hour).await?;
+        }
+        Ok(())
+    }
+}
+
+/// A part upload's index and payload, kept with its outcome so a failed part
+/// can be sent again.
+type PartUpload = (usize, PutPayload, object_store::Result<PartId>);
+
+/// An object being uploaded, in parts of 5 MiB as its bytes arrive.
+struct OpenObject {
+    path: ObjectPath,
+    id: MultipartId,
+    encoder: Option<GzEncoder<Vec<u8>>>,
+    /// Bytes not yet sent in a part.
+    unsent: Vec<u8>,
+    /// The parts uploaded so far, by index.
+    parts: BTreeMap<usize, PartId>,
+    /// Parts whose upload failed, to be sent again under the same index.
+    failed: Vec<(usize, PutPayload)>,
+    in_flight: JoinSet<PartUpload>,
+    next_part: usize,
+    records: usize,
+}
+
+impl OpenObject {
+    /// Adds `bytes` to the object, sending a part once `part_size` bytes are
+    /// buffered.
+    fn write(&mut self, store: &Arc<dyn MultipartStore>, bytes: &[u8], part_size: usize) -> Result<()> {
+        match self.encoder {
+            Some(ref mut encoder) => {
+                encoder.write_all(bytes)?;
+                self.unsent.append(encoder.get_mut());
+            }
+            None => self.unsent.extend_from_slice(bytes),
+        }
+        if self.unsent.len() >= part_size {
+            self.send_unsent(store);
+        }
+        Ok(())
+    }
+
+    fn send_unsent(&mut self, store: &Arc<dyn MultipartStore>) {
+        let payload = PutPayload::from(std::mem::take(&mut self.unsent));
+        let index = self.next_part;
+        self.next_part += 1;
+        self.send(store, index, payload);
+    }
+
+    fn send(&mut self, store: &Arc<dyn MultipartStore>, index: usize, payload: PutPayload) {
+        let (store, path, id) = (store.clone(), self.path.clone(), self.id.clone());
+        self.in_flight.spawn(async move {
+            let result = store.put_part(&path, &id, index, payload.clone()).await;
+            (index, payload, result)
+        });
+    }
+
+    /// Sends the parts that failed again.
+    fn resend_failed(&mut self, store: &Arc<dyn MultipartStore>) {
+        for (index, payload) in std::mem::take(&mut self.failed) {
+            self.send(store, index, payload);
+        }
+    }
+
+    /// Records a finished part upload, keeping the part if it failed.
+    fn settle(&mut self, joined: std::result::Result<PartUpload, JoinError>) -> Result<()> {
+        let (index, payload, result) = joined.map_err(sink_error)?;
+        match result {
+            Ok(part) => {
+                self.parts.insert(index, part);
+                Ok(())
+            }
+            Err(e) => {
+                self.failed.push((index, payload));
+                Err(sink_error(e))
+            }
+        }
+    }
+
+    /// Collects every finished part upload, then waits until fewer than
+    /// `max` are in flight. Returns the first part failure seen.
+    async fn wait_for_capacity(&mut self, max: usize) -> Result<()> {
+        let mut result = Ok(());
+        while let Some(joined) = self.in_flight.try_join_next() {
+            result = result.and(self.settle(joined));
+        }
+        while result.is_ok() && self.in_flight.len() >= max {
+            let Some(joined) = self.in_flight.join_next().await else {
+                break;
+            };
+            result = self.settle(joined);
+        }
+        result
+    }
+}
+
+/// Writes records to S3 as CSV or JSON Lines, serialized as [`CsvSink`] and
+/// [`JsonLinesSink`] write files.
+///
+/// By default every record goes to the one object at `key`. With
+/// [`S3Sink::with_rolling`] the key is instead a prefix and a new object,
+/// `prefix/part-<run>-<n>.<ext>`, is started every `max_records` records, so
+/// long streams split into objects of a manageable size. Bytes are uploaded
+/// with multipart uploads as they are written, so memory stays bounded
+/// however large an object grows, but an object only appears in the bucket
+/// once it is complete: when the next record rolls past it or on `close`. A
+/// run that writes no records creates no object.
+///
+/// When a part fails to upload it is kept, and the next write or `close`
+/// sends it again under the same part number before going on, so a write
+/// retried by [`Pipeline::with_retry`] loses nothing. A write that returns an
+/// error has not taken its record. Only when `close` fails is the upload
+/// aborted, so no orphaned parts are left accruing storage charges.
+///
+/// Uploads go through `object_store` rather than `aws-sdk-s3`, the same
+/// client as [`S3TimePartitionedSink`] and [`S3Source`], which also lets the
+/// sink write to any S3-compatible store or an in-memory one in tests.
+///
+/// [`Pipeline::with_retry`]: crate::pipeline::Pipeline::with_retry
+/// [`S3Source`]: crate::source::s3::S3Source
+/// [`CsvSink`]: crate::sink::file::CsvSink
+/// [`JsonLinesSink`]: crate::sink::file::JsonLinesSink
+pub struct S3Sink {
+    store: Arc<dyn MultipartStore>,
+    key: String,
+    format: Format,
+    delimiter: u8,
+    quoting: QuoteStyle,
+    headers: Option<Vec<String>>,
+    gzip: bool,
+    max_records_per_object: Option<usize>,
+    part_size: usize,
+    run_id: String,
+    objects_written: usize,
+    current: Option<OpenObject>,
+}
+
+impl S3Sink {
+    /// Writes to `bucket` with credentials and region taken from the standard
+    /// `AWS_*` environment variables.
+    pub fn new(bucket: &str, key: &str, format: Format) -> Result<Self> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(sink_error)?;
+        Ok(Self::with_store(Arc::new(store), key, format))
+    }
+
+    /// Writes to an already configured store, e.g. an S3-compatible service
+    /// built with a custom endpoint.
+    pub fn with_store(store: Arc<dyn MultipartStore>, key: &str, format: Format) -> Self {
+        Self {
+            store,
+            key: key.trim_matches('/').to_string(),
+            format,
+            delimiter: b',',
+            quoting: QuoteStyle::Necessary,
+            headers: None,
+            gzip: key.ends_with(".gz"),
+            max_records_per_object: None,
+            part_size: MIN_PART_SIZE,
+            run_id: run_id(),
+            objects_written: 0,
+            current: None,
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quoting(mut self, quoting: QuoteStyle) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    /// The CSV columns, taken from the first record by default. Every
+    /// object gets the same header row.
+    pub fn with_headers(mut self, headers: Vec<String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    /// Gzips each object, which is the default when the key ends in `.gz`.
+    pub fn with_gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// Treats the key as a prefix and starts a new object every
+    /// `max_records` records.
+    pub fn with_rolling(mut self, max_records: usize) -> Self {
+        self.max_records_per_object = Some(max_records.max(1));
+        self
+    }
+
+    fn object_path(&self) -> ObjectPath {
+        if self.max_records_per_object.is_none() {
+            return ObjectPath::from(self.key.as_str());
+        }
+        let name = format!(
+            "part-{}-{:05}.{}{}",
+            self.run_id,
+            self.objects_written,
+            self.format.extension(),
+            if self.gzip { ".gz" } else { "" }
+        );
+        if self.key.is_empty() {
+            ObjectPath::from(name)
+        } else {
+            ObjectPath::from(format!("{}/{}", self.key, name))
+        }
+    }
+
+    fn serialize(&mut self, record: &Record) -> Result<Vec<u8>> {
+        let mut bytes = match self.format {
+            Format::Jsonl => serde_json::to_vec(&record.data)?,
+            Format::Csv => {
+                let headers = self.headers.get_or_insert_with(|| record.data.keys().cloned().collect());
+                format_delimited_line(record, headers, self.delimiter, self.quoting).into_bytes()
+            }
+        };
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    async fn start_object(&mut self) -> Result<()> {
+        let path = self.object_path();
+        let id = self.store.create_multipart(&path).await.map_err(sink_error)?;
+        let mut object = OpenObject {
+            path,
+            id,
+            encoder: self.gzip.then(|| GzEncoder::new(Vec::new(), Compression::default())),
+            unsent: Vec::new(),
+            parts: BTreeMap::new(),
+            failed: Vec::new(),
+            in_flight: JoinSet::new(),
+            next_part: 0,
+            records: 0,
+        };
+        if let (Format::Csv, Some(headers)) = (self.format, &self.headers) {
+            let header_line = headers
+                .iter()
+                .map(|h| quote_field(h, false, self.delimiter, self.quoting))
+                .collect::<Vec<_>>()
+                .join(&(self.delimiter as char).to_string());
+            object.write(&self.store, format!("{}\n", header_line).as_bytes(), self.part_size)?;
+        }
+        self.current = Some(object);
+        Ok(())
+    }
+
+    /// Abandons the current upload, deleting the parts already uploaded.
+    /// The original error is the one reported, so a failure to abort is
+    /// ignored.
+    async fn abort_object(&mut self) {
+        if let Some(mut object) = self.current.take() {
+            object.in_flight.shutdown().await;
+            let _ = self.store.abort_multipart(&object.path, &object.id).await;
+        }
+    }
+
+    /// Sends the rest of the current object and completes the upload, making
+    /// the object visible. On failure the object stays open, with any failed
+    /// part kept, so finishing can be tried again.
+    async fn finish_object(&mut self) -> Result<()> {
+        let Some(ref mut object) = self.current else {
+            return Ok(());
+        };
+        if let Some(ref mut encoder) = object.encoder {
+            encoder.try_finish()?;
+            object.unsent.append(encoder.get_mut());
+            object.encoder = None;
+        }
+        if !object.unsent.is_empty() || object.next_part == 0 {
+            object.send_unsent(&self.store);
+        }
+        object.resend_failed(&self.store);
+        object.wait_for_capacity(1).await?;
+
+        if object.parts.len() != object.next_part {
+            return Err(PipelineError::Sink(format!(
+                "S3 error: {} of {} parts of '{}' were uploaded",
+                object.parts.len(),
+                object.next_part,
+                object.path
+            )));
+        }
+        let parts = object.parts.values().cloned().collect();
+        self.store
+            .complete_multipart(&object.path, &object.id, parts)
+            .await
+            .map_err(sink_error)?;
+        self.current = None;
+        self.objects_written += 1;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sink for S3Sink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let line = self.serialize(&record)?;
+        let full = self
+            .current
+            .as_ref()
+            .zip(self.max_records_per_object)
+            .is_some_and(|(object, max)| object.records >= max);
+        if full {
+            self.finish_object().await?;
+        }
+        if self.current.is_none() {
+            self.start_object().await?;
+        }
+        if let Some(ref mut object) = self.current {
+            object.resend_failed(&self.store);
+            object.wait_for_capacity(MAX_CONCURRENT_PARTS).await?;
+            object.write(&self.store, &line, self.part_size)?;
+            object.records += 1;
+        }
+        Ok(())
+    }
+
+    /// Objects cannot be appended to once complete, so the open object stays
+    /// open until it rolls over or the sink is closed.
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let result = self.finish_object().await;
+        if result.is_err() {
+            self.abort_object().await;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{RetryPolicy, Source};
+    use crate::pipeline::Pipeline;
+    use crate::source::memory::VecSource;
+    use crate::source::s3::S3Source;
+    use futures::stream::{BoxStream, TryStreamExt};
+    use object_store::memory::InMemory;
+    use object_store::{
+        CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStoreExt,
+        PutMultipartOptions, PutResult,
+    };
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn record(id: i64, name: &str) -> Record {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), json!(id));
+        record.set_field("name".to_string(), json!(name));
+        record
+    }
+
+    /// One in-memory store, as the source reads it and as the sink writes to
+    /// it.
+    fn in_memory() -> (Arc<dyn ObjectStore>, Arc<dyn MultipartStore>) {
+        let store = Arc::new(InMemory::new());
+        (store.clone(), store)
+    }
+
+    async fn write_all(sink: &mut S3Sink, records: &[Record]) {
+        for record in records {
+            sink.write(record.clone()).await.unwrap();
+        }
+        sink.close().await.unwrap();
+    }
+
+    async fn read_all(store: &Arc<dyn ObjectStore>, key: &str, format: Format) -> Vec<Record> {
+        let source = S3Source::with_store(store.clone(), key, format);
+        source.read().await.unwrap().try_collect().await.unwrap()
+    }
+
+    async fn keys(store: &Arc<dyn ObjectStore>) -> Vec<String> {
+        let mut keys: Vec<String> = store
+            .list(None)
+            .map_ok(|meta| meta.location.to_string())
+            .try_collect()
+            .await
+            .unwrap();
+        keys.sort();
+        keys
+    }
+
+    #[tokio::test]
+    async fn jsonl_round_trips_through_the_source() {
+        let (store, uploads) = in_memory();
+        let records = vec![record(1, "alice"), record(2, "bob")];
+        let mut sink = S3Sink::with_store(uploads, "out/records.jsonl", Format::Jsonl);
+        write_all(&mut sink, &records).await;
+
+        let read = read_all(&store, "out/records.jsonl", Format::Jsonl).await;
+        assert_eq!(read.len(), 2);
+        for (read, written) in read.iter().zip(&records) {
+            assert_eq!(read.data, written.data);
+        }
+    }
+
+    #[tokio::test]
+    async fn gzipped_csv_round_trips_through_the_source() {
+        let (store, uploads) = in_memory();
+        let mut sink = S3Sink::with_store(uploads, "records.csv.gz", Format::Csv);
+        write_all(&mut sink, &[record(1, "a, b"), record(2, "c")]).await;
+
+        let read = read_all(&store, "records.csv.gz", Format::Csv).await;
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].get_field("id"), Some(&json!("1")));
+        assert_eq!(read[0].get_field("name"), Some(&json!("a, b")));
+    }
+
+    #[tokio::test]
+    async fn rolling_splits_records_into_objects_with_headers() {
+        let (store, uploads) = in_memory();
+        let records: Vec<Record> = (0..5).map(|i| record(i, "x")).collect();
+        let mut sink = S3Sink::with_store(uploads, "prefix/", Format::Csv).with_rolling(2);
+        write_all(&mut sink, &records).await;
+
+        let keys = keys(&store).await;
+        assert_eq!(keys.len(), 3);
+        let mut ids = Vec::new();
+        for key in &keys {
+            assert!(key.starts_with("prefix/part-") && key.ends_with(".csv"), "{}", key);
+            for record in read_all(&store, key, Format::Csv).await {
+                ids.push(record.get_field("id").unwrap().as_str().unwrap().to_string());
+            }
+        }
+        assert_eq!(ids, ["0", "1", "2", "3", "4"]);
+    }
+
+    #[tokio::test]
+    async fn no_records_creates_no_object() {
+        let (store, uploads) = in_memory();
+        let mut sink = S3Sink::with_store(uploads, "records.jsonl", Format::Jsonl);
+        sink.close().await.unwrap();
+        assert!(keys(&store).await.is_empty());
+    }
+
+    /// An in-memory store whose upload of one part, and whose single-request
+    /// puts, fail the first `failures` times, counting aborted uploads.
+    #[derive(Debug)]
+    struct FlakyStore {
+        inner: Arc<InMemory>,
+        failing_part: usize,
+        failures: AtomicUsize,
+        aborted: AtomicUsize,
+    }
+
+    impl std::fmt::Display for FlakyStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FlakyStore")
+        }
+    }
+
+    impl FlakyStore {
+        fn new(inner: Arc<InMemory>, failing_part: usize, failures: usize) -> Self {
+            Self {
+                inner,
+                failing_part,
+                failures: AtomicUsize::new(failures),
+                aborted: AtomicUsize::new(0),
+            }
+        }
+
+        fn fail(&self) -> object_store::Result<()> {
+            match self.failures.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)) {
+                Ok(_) => Err(object_store::Error::Generic {
+                    store: "FlakyStore",
+                    source: "upload failed".into(),
+                }),
+                Err(_) => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn put_opts(
+            &self,
+            path: &ObjectPath,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> object_store::Result<PutResult> {
+            self.fail()?;
+            self.inner.put_opts(path, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            path: &ObjectPath,
+            opts: PutMultipartOptions,
+        ) -> object_store::Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(path, opts).await
+        }
+
+        async fn get_opts(&self, path: &ObjectPath, opts: GetOptions) -> object_store::Result<GetResult> {
+            self.inner.get_opts(path, opts).await
+        }
+
+        fn delete_stream(
+            &self,
+            paths: BoxStream<'static, object_store::Result<ObjectPath>>,
+        ) -> BoxStream<'static, object_store::Result<ObjectPath>> {
+            self.inner.delete_stream(paths)
+        }
+
+        fn list(&self, prefix: Option<&ObjectPath>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&ObjectPath>) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy_opts(&self, from: &ObjectPath, to: &ObjectPath, opts: CopyOptions) -> object_store::Result<()> {
+            self.inner.copy_opts(from, to, opts).await
+        }
+    }
+
+    #[async_trait]
+    impl MultipartStore for FlakyStore {
+        async fn create_multipart(&self, path: &ObjectPath) -> object_store::Result<MultipartId> {
+            self.inner.create_multipart(path).await
+        }
+
+        async fn put_part(
+            &self,
+            path: &ObjectPath,
+            id: &MultipartId,
+            part_idx: usize,
+            data: PutPayload,
+        ) -> object_store::Result<PartId> {
+            if part_idx == self.failing_part {
+                self.fail()?;
+            }
+            self.inner.put_part(path, id, part_idx, data).await
+        }
+
+        async fn complete_multipart(
+            &self,
+            path: &ObjectPath,
+            id: &MultipartId,
+            parts: Vec<PartId>,
+        ) -> object_store::Result<PutResult> {
+            self.inner.complete_multipart(path, id, parts).await
+        }
+
+        async fn abort_multipart(&self, path: &ObjectPath, id: &MultipartId) -> object_store::Result<()> {
+            self.aborted.fetch_add(1, Ordering::SeqCst);
+            self.inner.abort_multipart(path, id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_parts_are_resent_when_the_write_is_retried() {
+        let inner = Arc::new(InMemory::new());
+        let store = Arc::new(FlakyStore::new(inner.clone(), 1, 2));
+        let mut sink = S3Sink::with_store(store.clone(), "records.jsonl", Format::Jsonl);
+        // A part per record fills the window of parts in flight, so the
+        // failures surface on writes rather than on `close`.
+        sink.part_size = 1;
+        let retry = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            jitter: false,
+        };
+        let records: Vec<Record> = (0..40).map(|i| record(i, "x")).collect();
+        Pipeline::new(Box::new(VecSource::new(records)), vec![], Box::new(sink))
+            .with_retry(retry)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(store.failures.load(Ordering::SeqCst), 0);
+        let store: Arc<dyn ObjectStore> = inner;
+        let ids: Vec<i64> = read_all(&store, "records.jsonl", Format::Jsonl)
+            .await
+            .iter()
+            .map(|r| r.get_field("id").unwrap().as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, (0..40).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn failed_close_aborts_the_upload() {
+        let store = Arc::new(FlakyStore::new(Arc::new(InMemory::new()), 0, usize::MAX));
+        let mut sink = S3Sink::with_store(store.clone(), "records.jsonl", Format::Jsonl);
+        sink.write(record(0, "a")).await.unwrap();
+
+        let error = sink.close().await.unwrap_err().to_string();
+        assert!(error.contains("upload failed"), "{}", error);
+        assert_eq!(store.aborted.load(Ordering::SeqCst), 1);
+    }
+
+    fn event(id: i64, time: &str) -> Record {
+        let mut record = record(id, "event");
+        record.set_field("time".to_string(), json!(time));
+        record
+    }
+
+    async fn gunzip_ids(store: &Arc<dyn ObjectStore>, key: &str) -> Vec<i64> {
+        let bytes = store.get(&ObjectPath::from(key)).await.unwrap().bytes().await.unwrap();
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(&bytes[..]), &mut text).unwrap();
+        text.lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["id"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn time_partitioned_sink_writes_hourly_parts() {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut sink = S3TimePartitionedSink::with_store(store.clone(), "/archive/", "time")
+            .with_allowed_lateness(Duration::ZERO);
+        let run = sink.run_id.clone();
+
+        for (id, time) in [
+            (1, "2024-03-05T10:15:00Z"),
+            (2, "2024-03-05T10:50:00Z"),
+            (3, "2024-03-05T11:05:00Z"),
+            (4, "2024-03-05T12:10:00Z"),
+            // Hour 10 was uploaded when hour 11 began, so this starts a new part.
+            (5, "2024-03-05T10:20:00Z"),
+        ] {
+            sink.write(event(id, time)).await.unwrap();
+        }
+        sink.close().await.unwrap();
+
+        let part = |hour: &str, n: usize| format!("archive/2024/03/05/{}/part-{}-{:05}.jsonl.gz", hour, run, n);
+        assert_eq!(keys(&store).await, vec![part("10", 0), part("10", 2), part("11", 1), part("12", 3)]);
+        assert_eq!(gunzip_ids(&store, &part("10", 0)).await, vec![1, 2]);
+        assert_eq!(gunzip_ids(&store, &part("10", 2)).await, vec![5]);
+        assert_eq!(gunzip_ids(&store, &part("11", 1)).await, vec![3]);
+        assert_eq!(gunzip_ids(&store, &part("12", 3)).await, vec![4]);
+    }
+
+    #[tokio::test]
+    async fn time_partitioned_sink_splits_full_parts_and_requires_the_time() {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut sink = S3TimePartitionedSink::with_store(store.clone(), "", "time").with_max_records_per_part(2);
+        for id in 1..=3 {
+            sink.write(event(id, "2024-03-05T10:00:00Z")).await.unwrap();
+        }
+        assert!(matches!(sink.write(record(4, "untimed")).await, Err(PipelineError::Sink(_))));
+        sink.close().await.unwrap();
+
+        let keys = keys(&store).await;
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().all(|key| key.starts_with("2024/03/05/10/part-")));
+        assert_eq!(gunzip_ids(&store, &keys[0]).await, vec![1, 2]);
+        assert_eq!(gunzip_ids(&store, &keys[1]).await, vec![3]);
+    }
+
+    #[tokio::test]
+    async fn time_partitioned_sink_keeps_an_hour_whose_upload_fails() {
+        let inner = Arc::new(InMemory::new());
+        let store = Arc::new(FlakyStore::new(inner.clone(), 0, 0));
+        let mut sink = S3TimePartitionedSink::with_store(store.clone(), "", "time")
+            .with_allowed_lateness(Duration::ZERO)
+            .with_max_records_per_part(2);
+        let run = sink.run_id.clone();
+        sink.write(event(1, "2024-03-05T10:00:00Z")).await.unwrap();
+        sink.write(event(2, "2024-03-05T10:10:00Z")).await.unwrap();
+
+        // The full hour is uploaded before the next record for it is
+        // buffered, so a failed upload leaves that record untaken.
+        store.failures.store(1, Ordering::SeqCst);
+        assert!(sink.write(event(3, "2024-03-05T10:20:00Z")).await.is_err());
+        sink.write(event(3, "2024-03-05T10:20:00Z")).await.unwrap();
+
+        // Likewise for an hour closed by a later event time.
+        store.failures.store(1, Ordering::SeqCst);
+        assert!(sink.write(event(4, "2024-03-05T11:00:00Z")).await.is_err());
+        sink.write(event(4, "2024-03-05T11:00:00Z")).await.unwrap();
+        sink.close().await.unwrap();
+
+        let store: Arc<dyn ObjectStore> = inner;
+        let part = |hour: &str, n: usize| format!("2024/03/05/{}/part-{}-{:05}.jsonl.gz", hour, run, n);
+        assert_eq!(keys(&store).await, vec![part("10", 0), part("10", 1), part("11", 2)]);
+        assert_eq!(gunzip_ids(&store, &part("10", 0)).await, vec![1, 2]);
+        assert_eq!(gunzip_ids(&store, &part("10", 1)).await, vec![3]);
+        assert_eq!(gunzip_ids(&store, &part("11", 2)).await, vec![4]);
+    }
+}
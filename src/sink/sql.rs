@@ -0,0 +1,402 @@
+use crate::core::{PipelineError, Record, Result, Sink, Transactional, timestamp_millis};
+use crate::sink::buffered::BufferedWrite;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value;
+use sqlx::postgres::{PgArguments, PgPool, PgPoolOptions};
+use sqlx::query::Query;
+use sqlx::{Column, Executor, Postgres, Transaction, TypeInfo};
+
+// PostgreSQL accepts at most this many bind parameters per statement.
+const MAX_PARAMETERS: usize = 65535;
+
+type PgQuery<'q> = Query<'q, Postgres, PgArguments>;
+
+/// What an insert does with a row that violates a unique constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnConflict {
+    /// Keep the existing row and skip the new one.
+    DoNothing,
+    /// Overwrite the other inserted columns of the existing row that
+    /// conflicts on these key columns.
+    DoUpdate(Vec<String>),
+}
+
+struct TableColumn {
+    name: String,
+    type_name: String,
+}
+
+fn sink_error(e: sqlx::Error) -> PipelineError {
+    PipelineError::Sink(format!("PostgreSQL error: {}", e))
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Applies `convert` to a non-null value; `None` means the value has the
+/// wrong type for the column.
+fn nullable<T>(value: &Value, convert: impl FnOnce(&Value) -> Option<T>) -> Option<Option<T>> {
+    if value.is_null() { Some(None) } else { convert(value).map(Some) }
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn as_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    if let Some(s) = value.as_str()
+        && let Ok(dt) = DateTime::parse_from_rfc3339(s.trim())
+    {
+        return Some(dt.with_timezone(&Utc));
+    }
+    timestamp_millis(value).and_then(DateTime::from_timestamp_millis)
+}
+
+/// Binds `value` with the Rust type matching the column, so the server
+/// receives a typed parameter rather than text. Types without a mapping are
+/// bound as text and cast in the statement; see [`placeholder`].
+fn bind_value<'q>(query: PgQuery<'q>, column: &TableColumn, value: &Value) -> Result<PgQuery<'q>> {
+    let mismatch = || {
+        PipelineError::Sink(format!(
+            "Column '{}' of type {} cannot hold {}",
+            column.name, column.type_name, value
+        ))
+    };
+    let query = match column.type_name.as_str() {
+        "INT2" => query.bind(nullable(value, |v| as_i64(v).and_then(|i| i16::try_from(i).ok())).ok_or_else(mismatch)?),
+        "INT4" => query.bind(nullable(value, |v| as_i64(v).and_then(|i| i32::try_from(i).ok())).ok_or_else(mismatch)?),
+        "INT8" => query.bind(nullable(value, as_i64).ok_or_else(mismatch)?),
+        "FLOAT4" => query.bind(nullable(value, |v| as_f64(v).map(|f| f as f32)).ok_or_else(mismatch)?),
+        "FLOAT8" => query.bind(nullable(value, as_f64).ok_or_else(mismatch)?),
+        "BOOL" => query.bind(
+            nullable(value, |v| v.as_bool().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok())))
+                .ok_or_else(mismatch)?,
+        ),
+        "TIMESTAMPTZ" => query.bind(nullable(value, as_timestamp).ok_or_else(mismatch)?),
+        "TIMESTAMP" => query.bind(
+            nullable(value, |v| as_timestamp(v).map(|dt| dt.naive_utc())).ok_or_else(mismatch)?,
+        ),
+        "DATE" => query.bind(
+            nullable(value, |v| {
+                v.as_str()
+                    .and_then(|s| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok())
+                    .or_else(|| as_timestamp(v).map(|dt| dt.date_naive()))
+            })
+            .ok_or_else(mismatch)?,
+        ),
+        "JSON" | "JSONB" => query.bind(nullable(value, |v| Some(sqlx::types::Json(v.clone()))).ok_or_else(mismatch)?),
+        "BYTEA" => query.bind(
+            nullable(value, |v| v.as_str().and_then(|s| BASE64.decode(s).ok())).ok_or_else(mismatch)?,
+        ),
+        _ => query.bind(nullable(value, |v| Some(as_text(v))).ok_or_else(mismatch)?),
+    };
+    Ok(query)
+}
+
+fn placeholder(index: usize, column: &TableColumn) -> String {
+    match column.type_name.as_str() {
+        "INT2" | "INT4" | "INT8" | "FLOAT4" | "FLOAT8" | "BOOL" | "TIMESTAMPTZ" | "TIMESTAMP" | "DATE" | "JSON"
+        | "JSONB" | "BYTEA" => format!("${}", index),
+        other => format!("${}::{}", index, other),
+    }
+}
+
+/// Writes records into a PostgreSQL table using multi-row `INSERT`
+/// statements of up to `batch_size` rows.
+///
+/// Each value is bound as a parameter of its column's type, read from the
+/// table when the first batch is sent; strings are parsed into numbers,
+/// booleans and timestamps where the column needs them. A statement inserts
+/// the table columns present in any record of the batch, with `NULL` for
+/// records that lack one, and a record field the table has no column for is
+/// an error.
+pub struct PostgresSink {
+    connection_string: String,
+    table: String,
+    batch_size: usize,
+    on_conflict: Option<OnConflict>,
+    buffer: Vec<Record>,
+    pool: Option<PgPool>,
+    columns: Option<Vec<TableColumn>>,
+    transaction: Option<Transaction<'static, Postgres>>,
+}
+
+impl PostgresSink {
+    /// `table` may be schema-qualified, as in `analytics.events`.
+    pub fn new(connection_string: &str, table: &str) -> Self {
+        Self {
+            connection_string: connection_string.to_string(),
+            table: table.to_string(),
+            batch_size: 1000,
+            on_conflict: None,
+            buffer: Vec::new(),
+            pool: None,
+            columns: None,
+            transaction: None,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+        self.on_conflict = Some(on_conflict);
+        self
+    }
+
+    fn quoted_table(&self) -> String {
+        self.table.split('.').map(quote_identifier).collect::<Vec<_>>().join(".")
+    }
+
+    async fn pool(&mut self) -> Result<&PgPool> {
+        if self.pool.is_none() {
+            let pool = PgPoolOptions::new()
+                .connect(&self.connection_string)
+                .await
+                .map_err(sink_error)?;
+            self.pool = Some(pool);
+        }
+        Ok(self.pool.as_ref().unwrap())
+    }
+
+    async fn load_columns(&mut self) -> Result<()> {
+        if self.columns.is_some() {
+            return Ok(());
+        }
+        let sql = format!("SELECT * FROM {}", self.quoted_table());
+        let describe = self.pool().await?.describe(&sql).await.map_err(sink_error)?;
+        let columns = describe
+            .columns()
+            .iter()
+            .map(|column| TableColumn {
+                name: column.name().to_string(),
+                type_name: column.type_info().name().to_string(),
+            })
+            .collect();
+        self.columns = Some(columns);
+        Ok(())
+    }
+
+    fn insert_sql(&self, columns: &[&TableColumn], rows: usize) -> String {
+        let names: Vec<String> = columns.iter().map(|c| quote_identifier(&c.name)).collect();
+        let mut sql = format!("INSERT INTO {} ({}) VALUES ", self.quoted_table(), names.join(", "));
+        for row in 0..rows {
+            if row > 0 {
+                sql.push_str(", ");
+            }
+            let placeholders: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| placeholder(row * columns.len() + i + 1, column))
+                .collect();
+            sql.push('(');
+            sql.push_str(&placeholders.join(", "));
+            sql.push(')');
+        }
+
+        match self.on_conflict {
+            None => {}
+            Some(OnConflict::DoNothing) => sql.push_str(" ON CONFLICT DO NOTHING"),
+            Some(OnConflict::DoUpdate(ref keys)) => {
+                let keys: Vec<String> = keys.iter().map(|k| quote_identifier(k)).collect();
+                let updates: Vec<String> = names
+                    .iter()
+                    .filter(|name| !keys.contains(name))
+                    .map(|name| format!("{} = EXCLUDED.{}", name, name))
+                    .collect();
+                if updates.is_empty() {
+                    sql.push_str(&format!(" ON CONFLICT ({}) DO NOTHING", keys.join(", ")));
+                } else {
+                    sql.push_str(&format!(
+                        " ON CONFLICT ({}) DO UPDATE SET {}",
+                        keys.join(", "),
+                        updates.join(", ")
+                    ));
+                }
+            }
+        }
+        sql
+    }
+
+    async fn insert(&mut self, records: &[Record]) -> Result<()> {
+        self.load_columns().await?;
+        let table_columns = self.columns.as_ref().unwrap();
+
+        for record in records {
+            if let Some(name) = record.data.keys().find(|name| !table_columns.iter().any(|c| &c.name == *name)) {
+                return Err(PipelineError::Sink(format!("Table '{}' has no column '{}'", self.table, name)));
+            }
+        }
+        let columns: Vec<&TableColumn> = table_columns
+            .iter()
+            .filter(|column| records.iter().any(|record| record.data.contains_key(&column.name)))
+            .collect();
+        if columns.is_empty() {
+            return Ok(());
+        }
+
+        let rows_per_statement = self.batch_size.min(MAX_PARAMETERS / columns.len()).max(1);
+        for chunk in records.chunks(rows_per_statement) {
+            let sql = self.insert_sql(&columns, chunk.len());
+            let mut query = sqlx::query(&sql);
+            for record in chunk {
+                for column in &columns {
+                    query = bind_value(query, column, record.get_field(&column.name).unwrap_or(&Value::Null))?;
+                }
+            }
+
+            match self.transaction {
+                Some(ref mut transaction) => query.execute(&mut **transaction).await,
+                None => query.execute(self.pool.as_ref().unwrap()).await,
+            }
+            .map_err(sink_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BufferedWrite for PostgresSink {
+    type Item = Record;
+
+    fn buffer(&mut self) -> &mut Vec<Record> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        Sink::flush(self).await
+    }
+}
+
+#[async_trait]
+impl Sink for PostgresSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.buffer_write(record).await
+    }
+
+    /// A failed insert keeps the records not yet inserted, except those of
+    /// this call, which are handed back so the call can be retried. Chunks
+    /// of this call inserted before the failure are then sent again.
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        let mut unsent = records.len();
+        self.buffer.extend(records);
+        while self.buffer.len() >= self.batch_size {
+            let buffer = std::mem::take(&mut self.buffer);
+            let result = self.insert(&buffer[..self.batch_size]).await;
+            self.buffer = buffer;
+            if let Err(e) = result {
+                self.buffer.truncate(self.buffer.len() - unsent);
+                return Err(e);
+            }
+            self.buffer.drain(..self.batch_size);
+            unsent = unsent.min(self.buffer.len());
+        }
+        Ok(())
+    }
+
+    /// The buffer is only cleared once the insert succeeds.
+    async fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let records = std::mem::take(&mut self.buffer);
+        let result = self.insert(&records).await;
+        if result.is_err() {
+            self.buffer = records;
+        }
+        result
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.flush().await?;
+        if let Some(pool) = self.pool.take() {
+            pool.close().await;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transactional for PostgresSink {
+    async fn begin(&mut self) -> Result<()> {
+        let transaction = self.pool().await?.begin().await.map_err(sink_error)?;
+        self.transaction = Some(transaction);
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.flush().await?;
+        match self.transaction.take() {
+            Some(transaction) => transaction.commit().await.map_err(sink_error),
+            None => Ok(()),
+        }
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        self.buffer.clear();
+        match self.transaction.take() {
+            Some(transaction) => transaction.rollback().await.map_err(sink_error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::transactional::TransactionalSink;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn order(id: i64, qty: i64) -> Record {
+        record(json!({"id": id, "qty": qty}))
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a PostgreSQL server at DATABASE_URL"]
+    async fn batch_with_one_bad_row_leaves_the_table_unchanged() {
+        let url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "postgres://postgres@localhost/postgres".to_string());
+        let table = format!("dpipeline_tx_test_{}", std::process::id());
+        let pool = PgPoolOptions::new().connect(&url).await.unwrap();
+        pool.execute(format!("CREATE TABLE {} (id INT8 PRIMARY KEY, qty INT8 NOT NULL CHECK (qty > 0))", table).as_str())
+            .await
+            .unwrap();
+
+        // One row per statement, so the good rows before the bad one are
+        // already inserted when it fails.
+        let mut sink = TransactionalSink::new(Box::new(PostgresSink::new(&url, &table).with_batch_size(1)));
+        sink.write_batch(vec![order(1, 5)]).await.unwrap();
+        let result = sink.write_batch(vec![order(2, 3), order(3, -1), order(4, 2)]).await;
+        sink.close().await.unwrap();
+
+        let rows: Vec<(i64, i64)> = sqlx::query_as(&format!("SELECT id, qty FROM {} ORDER BY id", table))
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        pool.execute(format!("DROP TABLE {}", table).as_str()).await.unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(rows, vec![(1, 5)]);
+    }
+}
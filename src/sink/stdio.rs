@@ -0,0 +1,37 @@
+use crate::core::{Record, Result, Sink};
+use async_trait::async_trait;
+use tokio::io::{AsyncWriteExt, BufWriter, Stdout};
+
+/// Writes each record to standard output as one line of JSON.
+pub struct StdoutSink {
+    writer: BufWriter<Stdout>,
+}
+
+impl StdoutSink {
+    pub fn new() -> Self {
+        Self {
+            writer: BufWriter::new(tokio::io::stdout()),
+        }
+    }
+}
+
+impl Default for StdoutSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Sink for StdoutSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let json_line = serde_json::to_string(&record.data)?;
+        self.writer.write_all(json_line.as_bytes()).await?;
+        self.writer.write_all(b"\n").await?;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
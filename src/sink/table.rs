@@ -0,0 +1,219 @@
+use crate::core::{Record, Result, Schema, Sink};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+/// Prints records as an aligned Unicode table on `close`, for inspecting
+/// pipeline output in a terminal. Columns come from the schema if given,
+/// otherwise from the sorted union of record keys.
+///
+/// At most `max_rows` records are buffered; the rest are only counted.
+/// Values wider than `max_width` characters are cut short with `…`.
+pub struct TableSink {
+    writer: Box<dyn Write + Send + Sync>,
+    schema: Option<Schema>,
+    max_rows: usize,
+    max_width: usize,
+    rows: Vec<Record>,
+    omitted: usize,
+}
+
+impl TableSink {
+    pub fn new() -> Self {
+        Self {
+            writer: Box::new(std::io::stdout()),
+            schema: None,
+            max_rows: 1000,
+            max_width: 40,
+            rows: Vec::new(),
+            omitted: 0,
+        }
+    }
+
+    /// Writes the table to `writer` instead of stdout.
+    pub fn with_writer<W: Write + Send + Sync + 'static>(mut self, writer: W) -> Self {
+        self.writer = Box::new(writer);
+        self
+    }
+
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width.max(1);
+        self
+    }
+
+    fn cell(&self, value: Option<&Value>) -> String {
+        let text = match value {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        };
+        let text = text.replace(['\n', '\r', '\t'], " ");
+        if text.chars().count() <= self.max_width {
+            return text;
+        }
+        let mut truncated: String = text.chars().take(self.max_width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+
+    fn render(&self) -> String {
+        let columns: Vec<String> = match self.schema {
+            Some(ref schema) => schema.fields.iter().map(|f| f.name.clone()).collect(),
+            None => self
+                .rows
+                .iter()
+                .flat_map(|r| r.data.keys().cloned())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+
+        let header: Vec<String> = columns.iter().map(|c| self.cell(Some(&Value::String(c.clone())))).collect();
+        let body: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|r| columns.iter().map(|c| self.cell(r.get_field(c))).collect())
+            .collect();
+
+        let widths: Vec<usize> = (0..columns.len())
+            .map(|i| {
+                body.iter()
+                    .map(|row| row[i].chars().count())
+                    .chain(std::iter::once(header[i].chars().count()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let rule = |left: &str, mid: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            format!("{}{}{}\n", left, segments.join(mid), right)
+        };
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, w)| format!(" {}{} ", cell, " ".repeat(w - cell.chars().count())))
+                .collect();
+            format!("│{}│\n", padded.join("│"))
+        };
+
+        let mut out = rule("┌", "┬", "┐");
+        out.push_str(&line(&header));
+        out.push_str(&rule("├", "┼", "┤"));
+        for row in &body {
+            out.push_str(&line(row));
+        }
+        out.push_str(&rule("└", "┴", "┘"));
+
+        let shown = self.rows.len();
+        if self.omitted > 0 {
+            out.push_str(&format!("{} row(s) shown, {} more omitted\n", shown, self.omitted));
+        } else {
+            out.push_str(&format!("{} row(s)\n", shown));
+        }
+        out
+    }
+}
+
+impl Default for TableSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Sink for TableSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        if self.rows.len() < self.max_rows {
+            self.rows.push(record);
+        } else {
+            self.omitted += 1;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let table = self.render();
+        self.writer.write_all(table.as_bytes())?;
+        self.writer.flush()?;
+        self.rows.clear();
+        self.omitted = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn prints_headers_and_aligned_rows() {
+        let output = SharedBuffer::default();
+        let mut sink = TableSink::new().with_writer(output.clone()).with_max_width(8);
+        sink.write(record(json!({"name": "ada", "age": 36}))).await.unwrap();
+        sink.write(record(json!({"name": "grace hopper", "email": null}))).await.unwrap();
+        sink.close().await.unwrap();
+
+        assert_eq!(
+            output.text(),
+            "\
+┌─────┬───────┬──────────┐
+│ age │ email │ name     │
+├─────┼───────┼──────────┤
+│ 36  │       │ ada      │
+│     │       │ grace h… │
+└─────┴───────┴──────────┘
+2 row(s)
+"
+        );
+    }
+
+    #[tokio::test]
+    async fn rows_past_the_cap_are_counted() {
+        let output = SharedBuffer::default();
+        let mut sink = TableSink::new().with_writer(output.clone()).with_max_rows(1);
+        for id in 1..=3 {
+            sink.write(record(json!({"id": id}))).await.unwrap();
+        }
+        sink.close().await.unwrap();
+
+        let text = output.text();
+        assert!(text.contains("│ 1  │"));
+        assert!(!text.contains("│ 2  │"));
+        assert!(text.ends_with("1 row(s) shown, 2 more omitted\n"));
+    }
+}
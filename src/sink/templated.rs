@@ -0,0 +1,166 @@
+use crate::core::{PipelineError, Record, Result, Sink};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+type SinkFactory = Box<dyn Fn(&Path) -> Box<dyn Sink> + Send + Sync>;
+
+/// Routes each record to a file whose path is rendered from a template such
+/// as `reports/{year}/{region}.csv`, where `{field}` is replaced with that
+/// field's value. A sink is opened through `factory` the first time a path is
+/// seen, after creating its parent directories, and every sink is closed on
+/// `close`.
+///
+/// Path separators in field values are replaced with `_` so a value cannot
+/// add directory levels. Records with an empty or missing template field are an error
+/// unless a placeholder is configured. A record goes straight to its path's
+/// sink, so a failed write can be retried if that sink's can.
+pub struct TemplatedPathSink {
+    template: String,
+    factory: SinkFactory,
+    placeholder: Option<String>,
+    sinks: HashMap<PathBuf, Box<dyn Sink>>,
+}
+
+impl TemplatedPathSink {
+    pub fn new<F>(template: &str, factory: F) -> Self
+    where
+        F: Fn(&Path) -> Box<dyn Sink> + Send + Sync + 'static,
+    {
+        Self {
+            template: template.to_string(),
+            factory: Box::new(factory),
+            placeholder: None,
+            sinks: HashMap::new(),
+        }
+    }
+
+    /// Substitutes `placeholder` for missing, null or empty template fields.
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    fn render(&self, record: &Record) -> Result<PathBuf> {
+        let mut path = String::new();
+        let mut rest = self.template.as_str();
+
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').map(|end| start + end).ok_or_else(|| {
+                PipelineError::Config(format!("Unclosed '{{' in path template '{}'", self.template))
+            })?;
+            path.push_str(&rest[..start]);
+
+            let field = &rest[start + 1..end];
+            let value = match record.get_field(field) {
+                Some(Value::String(s)) if !s.is_empty() => s.clone(),
+                Some(Value::String(_)) | Some(Value::Null) | None => self.placeholder.clone().ok_or_else(|| {
+                    PipelineError::Sink(format!("Record has no value for path template field '{}'", field))
+                })?,
+                Some(other) => other.to_string(),
+            };
+            path.push_str(&value.replace(['/', '\\'], "_"));
+            rest = &rest[end + 1..];
+        }
+        path.push_str(rest);
+
+        Ok(PathBuf::from(path))
+    }
+}
+
+#[async_trait]
+impl Sink for TemplatedPathSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        let path = self.render(&record)?;
+        if !self.sinks.contains_key(&path) {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let sink = (self.factory)(&path);
+            self.sinks.insert(path.clone(), sink);
+        }
+        self.sinks.get_mut(&path).unwrap().write(record).await
+    }
+
+    /// Flushes every sink even when an earlier one fails, returning the
+    /// first error, so one failing file does not leave the others
+    /// unflushed.
+    async fn flush(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for sink in self.sinks.values_mut() {
+            if let Err(e) = sink.flush().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for (_, mut sink) in self.sinks.drain() {
+            if let Err(e) = sink.close().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::file::JsonLinesSink;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn sink(dir: &Path) -> TemplatedPathSink {
+        let template = format!("{}/reports/{{year}}/{{region}}.jsonl", dir.display());
+        TemplatedPathSink::new(&template, |path| Box::new(JsonLinesSink::new(path)))
+    }
+
+    fn lines(path: PathBuf) -> Vec<Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn records_land_in_their_templated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sink = sink(dir.path());
+        let records = [
+            json!({"year": 2024, "region": "eu", "id": 1}),
+            json!({"year": 2024, "region": "us", "id": 2}),
+            json!({"year": 2025, "region": "eu", "id": 3}),
+            json!({"year": 2024, "region": "eu", "id": 4}),
+            json!({"year": 2025, "region": "a/b", "id": 5}),
+        ];
+        for value in records.clone() {
+            sink.write(record(value)).await.unwrap();
+        }
+        sink.close().await.unwrap();
+
+        let reports = dir.path().join("reports");
+        assert_eq!(lines(reports.join("2024/eu.jsonl")), [records[0].clone(), records[3].clone()]);
+        assert_eq!(lines(reports.join("2024/us.jsonl")), [records[1].clone()]);
+        assert_eq!(lines(reports.join("2025/eu.jsonl")), [records[2].clone()]);
+        assert_eq!(lines(reports.join("2025/a_b.jsonl")), [records[4].clone()]);
+    }
+
+    #[tokio::test]
+    async fn missing_fields_error_or_use_the_placeholder() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = record(json!({"year": 2024, "region": ""}));
+
+        let mut strict = sink(dir.path());
+        assert!(matches!(strict.write(missing.clone()).await, Err(PipelineError::Sink(_))));
+
+        let mut lenient = sink(dir.path()).with_placeholder("unknown");
+        lenient.write(missing).await.unwrap();
+        lenient.close().await.unwrap();
+        assert_eq!(lines(dir.path().join("reports/2024/unknown.jsonl")).len(), 1);
+    }
+}
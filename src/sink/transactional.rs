@@ -0,0 +1,193 @@
+use crate::core::{Record, Result, Sink, Transactional};
+use crate::sink::buffered::BufferedWrite;
+use async_trait::async_trait;
+
+/// Commits records to a [`Transactional`] sink in all-or-nothing batches.
+/// Every `write_batch` call, and every `batch_size` records passed to
+/// `write`, runs in its own transaction. If any record in a batch fails the
+/// transaction is rolled back, so none of the batch is kept, and the error
+/// is returned.
+pub struct TransactionalSink {
+    inner: Box<dyn Transactional>,
+    batch_size: usize,
+    buffer: Vec<Record>,
+}
+
+impl TransactionalSink {
+    pub fn new(inner: Box<dyn Transactional>) -> Self {
+        Self {
+            inner,
+            batch_size: 1000,
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn write_transaction(&mut self, records: Vec<Record>) -> Result<()> {
+        self.inner.begin().await?;
+
+        let written = async {
+            self.inner.write_batch(records).await?;
+            self.inner.flush().await
+        }
+        .await;
+
+        match written {
+            Ok(()) => self.inner.commit().await,
+            Err(e) => {
+                if let Err(rollback_error) = self.inner.rollback().await {
+                    tracing::warn!("Rolling back failed batch failed: {}", rollback_error);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl BufferedWrite for TransactionalSink {
+    type Item = Record;
+
+    fn buffer(&mut self) -> &mut Vec<Record> {
+        &mut self.buffer
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    async fn send_buffer(&mut self) -> Result<()> {
+        Sink::flush(self).await
+    }
+}
+
+#[async_trait]
+impl Sink for TransactionalSink {
+    async fn write(&mut self, record: Record) -> Result<()> {
+        self.buffer_write(record).await
+    }
+
+    async fn write_batch(&mut self, records: Vec<Record>) -> Result<()> {
+        self.flush().await?;
+        self.write_transaction(records).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        // A rolled-back batch stays buffered, so a failed flush can be
+        // retried.
+        self.write_transaction(self.buffer.clone()).await?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.flush().await?;
+        self.inner.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{PipelineError, RetryPolicy};
+    use crate::pipeline::Pipeline;
+    use crate::source::memory::VecSource;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Stages writes until `commit`, failing the first `failures` commits.
+    /// Records whose `id` is not a number are rejected.
+    struct FlakyStore {
+        staged: Vec<Record>,
+        committed: Arc<Mutex<Vec<Record>>>,
+        failures: usize,
+    }
+
+    #[async_trait]
+    impl Sink for FlakyStore {
+        async fn write(&mut self, record: Record) -> Result<()> {
+            if !record.get_field("id").is_some_and(|id| id.is_number()) {
+                return Err(PipelineError::Sink("invalid id".to_string()));
+            }
+            self.staged.push(record);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Transactional for FlakyStore {
+        async fn begin(&mut self) -> Result<()> {
+            self.staged.clear();
+            Ok(())
+        }
+
+        async fn commit(&mut self) -> Result<()> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(PipelineError::Sink("connection reset".to_string()));
+            }
+            self.committed.lock().unwrap().append(&mut self.staged);
+            Ok(())
+        }
+
+        async fn rollback(&mut self) -> Result<()> {
+            self.staged.clear();
+            Ok(())
+        }
+    }
+
+    fn with_id(id: Value) -> Record {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), id);
+        record
+    }
+
+    fn ids(committed: &Mutex<Vec<Record>>) -> Vec<Value> {
+        committed.lock().unwrap().iter().map(|r| r.get_field("id").cloned().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn batch_with_one_bad_row_is_rolled_back() {
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let store = FlakyStore { staged: Vec::new(), committed: committed.clone(), failures: 0 };
+        let mut sink = TransactionalSink::new(Box::new(store));
+
+        sink.write_batch(vec![with_id(json!(1))]).await.unwrap();
+        let batch = vec![with_id(json!(2)), with_id(json!("three")), with_id(json!(4))];
+        assert!(sink.write_batch(batch).await.is_err());
+        sink.close().await.unwrap();
+
+        assert_eq!(ids(&committed), vec![json!(1)]);
+    }
+
+    #[tokio::test]
+    async fn retried_write_keeps_the_buffered_batch() {
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let store = FlakyStore { staged: Vec::new(), committed: committed.clone(), failures: 1 };
+        let sink = TransactionalSink::new(Box::new(store)).with_batch_size(2);
+        let records = (1..=5).map(|i| with_id(json!(i))).collect();
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            jitter: false,
+        };
+
+        let stats = Pipeline::new(Box::new(VecSource::new(records)), vec![], Box::new(sink))
+            .with_retry(retry)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(ids(&committed), (1..=5).map(|i| json!(i)).collect::<Vec<_>>());
+        assert_eq!(stats.records_written, 5);
+    }
+}
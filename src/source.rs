@@ -1 +1,30 @@
-pub mod file;
\ No newline at end of file
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod binary;
+pub mod cbor;
+pub mod encrypted;
+pub mod file;
+pub mod fixedwidth;
+#[cfg(feature = "flight")]
+pub mod flight;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod log;
+pub mod memory;
+pub mod merge;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(feature = "postgres")]
+pub mod sql;
+pub mod stdio;
+pub mod xml;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
\ No newline at end of file
@@ -0,0 +1,266 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use crate::transform::aggregate::number_to_value;
+use apache_avro::Reader;
+use apache_avro::schema::Schema as AvroSchema;
+use apache_avro::types::Value as AvroValue;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat};
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tokio_stream::wrappers::ReceiverStream;
+
+const RECORD_BUFFER: usize = 1024;
+
+fn source_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Avro error: {}", e))
+}
+
+fn open_reader(path: &str) -> Result<Reader<'static, BufReader<File>>> {
+    Reader::new(BufReader::new(File::open(path)?)).map_err(source_error)
+}
+
+/// Maps an Avro type to the closest `DataType` and whether it admits null.
+/// A union of null and one other type is that type, nullable; other unions,
+/// maps, records and durations are `Json`. Decimals and times of day are
+/// read as strings, so they are `String`.
+fn avro_data_type(schema: &AvroSchema) -> (DataType, bool) {
+    let data_type = match schema {
+        AvroSchema::Boolean => DataType::Boolean,
+        AvroSchema::Int | AvroSchema::Long => DataType::Integer,
+        AvroSchema::Float | AvroSchema::Double => DataType::Float,
+        AvroSchema::Bytes | AvroSchema::Fixed(_) => DataType::Bytes,
+        AvroSchema::String | AvroSchema::Enum(_) | AvroSchema::Uuid(_) => DataType::String,
+        AvroSchema::Decimal(_) | AvroSchema::BigDecimal => DataType::String,
+        AvroSchema::TimeMillis | AvroSchema::TimeMicros => DataType::String,
+        AvroSchema::Date => DataType::Date,
+        AvroSchema::TimestampMillis | AvroSchema::TimestampMicros | AvroSchema::TimestampNanos => DataType::Timestamp,
+        AvroSchema::LocalTimestampMillis | AvroSchema::LocalTimestampMicros | AvroSchema::LocalTimestampNanos => {
+            DataType::DateTime
+        }
+        AvroSchema::Array(array) => DataType::Array(Box::new(avro_data_type(&array.items).0)),
+        AvroSchema::Null => return (DataType::Json, true),
+        AvroSchema::Union(union) => {
+            let mut variants = union.variants().iter().filter(|variant| **variant != AvroSchema::Null);
+            return match (variants.next(), variants.next()) {
+                (Some(only), None) => (avro_data_type(only).0, union.is_nullable()),
+                _ => (DataType::Json, union.is_nullable()),
+            };
+        }
+        _ => DataType::Json,
+    };
+    (data_type, false)
+}
+
+fn schema_from_avro(schema: &AvroSchema) -> Result<Schema> {
+    let AvroSchema::Record(record) = schema else {
+        return Err(PipelineError::Schema("Avro file does not contain records".to_string()));
+    };
+    let fields = record
+        .fields
+        .iter()
+        .map(|field| {
+            let (data_type, nullable) = avro_data_type(&field.schema);
+            Field {
+                name: field.name.clone(),
+                data_type,
+                nullable,
+                description: field.doc.clone(),
+            }
+        })
+        .collect();
+    Ok(Schema::new(fields))
+}
+
+/// Formats an unscaled two's-complement big-endian decimal with `scale`
+/// fractional digits.
+fn decimal_string(bytes: &[u8], scale: usize) -> Result<String> {
+    if bytes.len() > 16 {
+        return Err(source_error("decimal is too large"));
+    }
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let unscaled = bytes
+        .iter()
+        .fold(if negative { -1i128 } else { 0 }, |acc, &b| (acc << 8) | i128::from(b));
+
+    let digits = unscaled.unsigned_abs().to_string();
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - scale);
+    let sign = if negative { "-" } else { "" };
+    Ok(if scale == 0 {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    })
+}
+
+fn timestamp_string(micros: i64) -> Value {
+    DateTime::from_timestamp_micros(micros)
+        .map_or(Value::Null, |dt| Value::String(dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)))
+}
+
+fn local_timestamp_string(micros: i64) -> Value {
+    DateTime::from_timestamp_micros(micros).map_or(Value::Null, |dt| {
+        Value::String(dt.naive_utc().format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+    })
+}
+
+fn time_string(micros: i64) -> Value {
+    let seconds = micros.div_euclid(1_000_000) as u32;
+    let nanos = micros.rem_euclid(1_000_000) as u32 * 1000;
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos)
+        .map_or(Value::Null, |time| Value::String(time.format("%H:%M:%S%.f").to_string()))
+}
+
+/// Converts a datum to JSON. `schema` is the datum's schema where known and
+/// is only needed for the scale of decimals; dates and timestamps become
+/// strings and bytes base64.
+fn avro_to_json(value: AvroValue, schema: Option<&AvroSchema>) -> Result<Value> {
+    Ok(match value {
+        AvroValue::Null => Value::Null,
+        AvroValue::Boolean(b) => Value::Bool(b),
+        AvroValue::Int(i) => Value::from(i),
+        AvroValue::Long(i) => Value::from(i),
+        AvroValue::Float(f) => number_to_value(f64::from(f)),
+        AvroValue::Double(f) => number_to_value(f),
+        AvroValue::Bytes(bytes) | AvroValue::Fixed(_, bytes) => Value::String(BASE64.encode(bytes)),
+        AvroValue::String(s) | AvroValue::Enum(_, s) => Value::String(s),
+        AvroValue::Uuid(uuid) => Value::String(uuid.to_string()),
+        AvroValue::Union(index, inner) => {
+            let variant = match schema {
+                Some(AvroSchema::Union(union)) => union.variants().get(index as usize),
+                _ => None,
+            };
+            avro_to_json(*inner, variant)?
+        }
+        AvroValue::Array(items) => {
+            let item_schema = match schema {
+                Some(AvroSchema::Array(array)) => Some(array.items.as_ref()),
+                _ => None,
+            };
+            Value::Array(items.into_iter().map(|item| avro_to_json(item, item_schema)).collect::<Result<_>>()?)
+        }
+        AvroValue::Map(entries) => {
+            let value_schema = match schema {
+                Some(AvroSchema::Map(map)) => Some(map.types.as_ref()),
+                _ => None,
+            };
+            let mut keys: Vec<String> = entries.keys().cloned().collect();
+            keys.sort();
+            let mut entries = entries;
+            let mut object = Map::new();
+            for key in keys {
+                let value = entries.remove(&key).unwrap_or(AvroValue::Null);
+                object.insert(key, avro_to_json(value, value_schema)?);
+            }
+            Value::Object(object)
+        }
+        AvroValue::Record(fields) => {
+            let field_schemas = match schema {
+                Some(AvroSchema::Record(record)) => Some(&record.fields),
+                _ => None,
+            };
+            let mut object = Map::new();
+            for (i, (name, value)) in fields.into_iter().enumerate() {
+                let field_schema = field_schemas.and_then(|fields| fields.get(i)).map(|field| &field.schema);
+                object.insert(name, avro_to_json(value, field_schema)?);
+            }
+            Value::Object(object)
+        }
+        AvroValue::Date(days) => NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(i64::from(days))))
+            .map_or(Value::Null, |date| Value::String(date.to_string())),
+        AvroValue::Decimal(decimal) => {
+            let bytes = <Vec<u8>>::try_from(&decimal).map_err(source_error)?;
+            let scale = match schema {
+                Some(AvroSchema::Decimal(decimal)) => decimal.scale,
+                _ => 0,
+            };
+            Value::String(decimal_string(&bytes, scale)?)
+        }
+        AvroValue::BigDecimal(decimal) => Value::String(decimal.to_string()),
+        AvroValue::TimeMillis(millis) => time_string(i64::from(millis) * 1000),
+        AvroValue::TimeMicros(micros) => time_string(micros),
+        AvroValue::TimestampMillis(millis) => timestamp_string(millis.saturating_mul(1000)),
+        AvroValue::TimestampMicros(micros) => timestamp_string(micros),
+        AvroValue::TimestampNanos(nanos) => Value::String(
+            DateTime::from_timestamp_nanos(nanos).to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        ),
+        AvroValue::LocalTimestampMillis(millis) => local_timestamp_string(millis.saturating_mul(1000)),
+        AvroValue::LocalTimestampMicros(micros) => local_timestamp_string(micros),
+        AvroValue::LocalTimestampNanos(nanos) => Value::String(
+            DateTime::from_timestamp_nanos(nanos).naive_utc().format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+        ),
+        AvroValue::Duration(duration) => serde_json::json!({
+            "months": u32::from(duration.months()),
+            "days": u32::from(duration.days()),
+            "millis": u32::from(duration.millis()),
+        }),
+    })
+}
+
+fn record_from_avro(value: AvroValue, schema: &AvroSchema) -> Result<Record> {
+    match avro_to_json(value, Some(schema))? {
+        Value::Object(fields) => Ok(Record::with_data(fields.into_iter().collect())),
+        _ => Err(PipelineError::Schema("Avro datum is not a record".to_string())),
+    }
+}
+
+/// Reads an Avro object container file, yielding one record per datum.
+///
+/// The schema comes from the file's writer schema: `long` and `int` are
+/// `Integer`, `double` and `float` are `Float`, nested records and maps are
+/// `Json`, and a union with null is a nullable field of the other type.
+/// Bytes are base64 strings and dates, times and timestamps are strings in
+/// the formats the rest of the crate parses; decimals are exact decimal
+/// strings.
+pub struct AvroSource {
+    file_path: String,
+}
+
+impl AvroSource {
+    pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for AvroSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let file_path = self.file_path.clone();
+        tokio::task::spawn_blocking(move || schema_from_avro(open_reader(&file_path)?.writer_schema()))
+            .await
+            .map_err(source_error)?
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let reader = {
+            let file_path = self.file_path.clone();
+            tokio::task::spawn_blocking(move || open_reader(&file_path))
+                .await
+                .map_err(source_error)??
+        };
+        schema_from_avro(reader.writer_schema())?;
+        let (tx, rx) = tokio::sync::mpsc::channel(RECORD_BUFFER);
+
+        // Decoding is blocking I/O, so it runs on its own thread; dropping the
+        // record stream closes the channel and ends it.
+        tokio::task::spawn_blocking(move || {
+            let schema = reader.writer_schema().clone();
+            for datum in reader {
+                let record = datum.map_err(source_error).and_then(|value| record_from_avro(value, &schema));
+                let failed = record.is_err();
+                if tx.blocking_send(record).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+}
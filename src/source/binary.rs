@@ -0,0 +1,250 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, BufReader};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryType {
+    U8,
+    U16,
+    U32,
+    I32,
+    F32,
+    F64,
+}
+
+impl BinaryType {
+    fn size(&self) -> usize {
+        match self {
+            BinaryType::U8 => 1,
+            BinaryType::U16 => 2,
+            BinaryType::U32 | BinaryType::I32 | BinaryType::F32 => 4,
+            BinaryType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+#[derive(Debug, Clone)]
+pub struct BinaryField {
+    pub name: String,
+    pub data_type: BinaryType,
+    pub endianness: Endianness,
+}
+
+fn decode(bytes: &[u8], field: &BinaryField) -> Value {
+    macro_rules! read {
+        ($ty:ty) => {{
+            let array = bytes.try_into().unwrap();
+            match field.endianness {
+                Endianness::Little => <$ty>::from_le_bytes(array),
+                Endianness::Big => <$ty>::from_be_bytes(array),
+            }
+        }};
+    }
+
+    match field.data_type {
+        BinaryType::U8 => Value::from(bytes[0]),
+        BinaryType::U16 => Value::from(read!(u16)),
+        BinaryType::U32 => Value::from(read!(u32)),
+        BinaryType::I32 => Value::from(read!(i32)),
+        BinaryType::F32 => Number::from_f64(read!(f32) as f64).map(Value::Number).unwrap_or(Value::Null),
+        BinaryType::F64 => Number::from_f64(read!(f64)).map(Value::Number).unwrap_or(Value::Null),
+    }
+}
+
+/// Reads packed fixed-length binary records. Fields are decoded back to back
+/// from the start of each record in layout order; any bytes after the last
+/// field up to `record_length` are padding. A trailing partial record is an
+/// error.
+pub struct BinaryStructSource {
+    file_path: String,
+    layout: Vec<BinaryField>,
+    record_length: usize,
+}
+
+impl BinaryStructSource {
+    pub fn new<P: AsRef<Path>>(file_path: P, layout: Vec<BinaryField>) -> Self {
+        let record_length = layout.iter().map(|f| f.data_type.size()).sum();
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            layout,
+            record_length,
+        }
+    }
+
+    pub fn with_record_length(mut self, record_length: usize) -> Self {
+        self.record_length = record_length;
+        self
+    }
+
+    fn fields_length(&self) -> usize {
+        self.layout.iter().map(|f| f.data_type.size()).sum()
+    }
+}
+
+async fn read_chunk(reader: &mut BufReader<File>, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let read = reader.read(&mut buffer[filled..]).await?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    Ok(filled)
+}
+
+#[async_trait]
+impl Source for BinaryStructSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let fields = self
+            .layout
+            .iter()
+            .map(|f| Field {
+                name: f.name.clone(),
+                data_type: match f.data_type {
+                    BinaryType::F32 | BinaryType::F64 => DataType::Float,
+                    _ => DataType::Integer,
+                },
+                nullable: false,
+                description: None,
+            })
+            .collect();
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        if self.record_length < self.fields_length() || self.record_length == 0 {
+            return Err(PipelineError::Config(format!(
+                "Record length {} is shorter than the layout's {} bytes",
+                self.record_length,
+                self.fields_length()
+            )));
+        }
+
+        let reader = BufReader::new(File::open(&self.file_path).await?);
+        let layout = self.layout.clone();
+        let record_length = self.record_length;
+
+        let stream = futures::stream::unfold(Some((reader, 0u64)), move |state| {
+            let layout = layout.clone();
+            async move {
+                let (mut reader, index) = state?;
+                let mut buffer = vec![0u8; record_length];
+                match read_chunk(&mut reader, &mut buffer).await {
+                    Ok(0) => None,
+                    Ok(read) if read < record_length => Some((
+                        Err(PipelineError::Source(anyhow::anyhow!(
+                            "Trailing partial record {}: {} of {} bytes",
+                            index,
+                            read,
+                            record_length
+                        ))),
+                        None,
+                    )),
+                    Ok(_) => {
+                        let mut data = IndexMap::new();
+                        let mut offset = 0;
+                        for field in &layout {
+                            let size = field.data_type.size();
+                            data.insert(field.name.clone(), decode(&buffer[offset..offset + size], field));
+                            offset += size;
+                        }
+                        Some((Ok(Record::with_data(data)), Some((reader, index + 1))))
+                    }
+                    Err(e) => Some((Err(PipelineError::Io(e)), None)),
+                }
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use serde_json::json;
+
+    fn field(name: &str, data_type: BinaryType, endianness: Endianness) -> BinaryField {
+        BinaryField {
+            name: name.to_string(),
+            data_type,
+            endianness,
+        }
+    }
+
+    fn layout() -> Vec<BinaryField> {
+        vec![
+            field("kind", BinaryType::U8, Endianness::Little),
+            field("sensor", BinaryType::U16, Endianness::Big),
+            field("offset", BinaryType::I32, Endianness::Little),
+            field("reading", BinaryType::F64, Endianness::Little),
+        ]
+    }
+
+    /// Packs one record in [`layout`] followed by `padding` zero bytes.
+    fn pack(kind: u8, sensor: u16, offset: i32, reading: f64, padding: usize) -> Vec<u8> {
+        let mut bytes = vec![kind];
+        bytes.extend_from_slice(&sensor.to_be_bytes());
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes.extend_from_slice(&reading.to_le_bytes());
+        bytes.extend(std::iter::repeat_n(0, padding));
+        bytes
+    }
+
+    async fn read_all(source: &BinaryStructSource) -> Vec<Result<Record>> {
+        source.read().await.unwrap().collect().await
+    }
+
+    #[tokio::test]
+    async fn decodes_packed_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.bin");
+        let mut bytes = pack(1, 0x0102, -5, 21.5, 3);
+        bytes.extend(pack(2, 513, 70_000, -0.25, 3));
+        std::fs::write(&path, bytes).unwrap();
+
+        let source = BinaryStructSource::new(&path, layout()).with_record_length(18);
+        let records: Vec<Record> = read_all(&source).await.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        let first: Vec<(&str, &Value)> = records[0].data.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        assert_eq!(
+            first,
+            [("kind", &json!(1)), ("sensor", &json!(258)), ("offset", &json!(-5)), ("reading", &json!(21.5))]
+        );
+        assert_eq!(records[1].get_field("offset"), Some(&json!(70_000)));
+        assert_eq!(records[1].get_field("reading"), Some(&json!(-0.25)));
+    }
+
+    #[tokio::test]
+    async fn trailing_partial_record_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.bin");
+        let mut bytes = pack(1, 1, 1, 1.0, 0);
+        bytes.extend_from_slice(&[9, 9]);
+        std::fs::write(&path, bytes).unwrap();
+
+        let results = read_all(&BinaryStructSource::new(&path, layout())).await;
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        let error = results[1].as_ref().unwrap_err().to_string();
+        assert!(error.contains("Trailing partial record 1: 2 of 15 bytes"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn record_length_shorter_than_the_layout_is_rejected() {
+        let source = BinaryStructSource::new("unused.bin", layout()).with_record_length(10);
+        assert!(matches!(source.read().await, Err(PipelineError::Config(_))));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ciborium::Value as Cbor;
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, BufReader};
+
+fn cbor_to_value(cbor: Cbor) -> Value {
+    match cbor {
+        Cbor::Null => Value::Null,
+        Cbor::Bool(b) => Value::Bool(b),
+        Cbor::Integer(i) => {
+            let i = i128::from(i);
+            i64::try_from(i)
+                .map(Value::from)
+                .or_else(|_| u64::try_from(i).map(Value::from))
+                .unwrap_or_else(|_| Value::String(i.to_string()))
+        }
+        Cbor::Float(f) => Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        Cbor::Text(s) => Value::String(s),
+        Cbor::Bytes(bytes) => Value::String(BASE64.encode(bytes)),
+        // Tags (dates, bignums, ...) are dropped in favour of the tagged item.
+        Cbor::Tag(_, inner) => cbor_to_value(*inner),
+        Cbor::Array(items) => Value::Array(items.into_iter().map(cbor_to_value).collect()),
+        Cbor::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (map_key(key), cbor_to_value(value)))
+                .collect(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+fn map_key(key: Cbor) -> String {
+    match key {
+        Cbor::Text(s) => s,
+        other => cbor_to_value(other).to_string(),
+    }
+}
+
+fn cbor_data_type(cbor: &Cbor) -> DataType {
+    match cbor {
+        Cbor::Bool(_) => DataType::Boolean,
+        Cbor::Integer(_) => DataType::Integer,
+        Cbor::Float(_) => DataType::Float,
+        Cbor::Text(_) => DataType::String,
+        Cbor::Bytes(_) => DataType::Bytes,
+        Cbor::Tag(_, inner) => cbor_data_type(inner),
+        _ => DataType::Json,
+    }
+}
+
+/// Reads a stream of CBOR items, each preceded by its length as a 4-byte
+/// big-endian integer, as written by [`CborSink`](crate::sink::cbor::CborSink).
+/// Every item must be a map, which becomes one record. Byte strings are
+/// base64-encoded and tags are unwrapped.
+pub struct CborSource {
+    file_path: String,
+}
+
+impl CborSource {
+    pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// Reads the next frame, returning `None` at a clean end of file.
+async fn read_frame(reader: &mut BufReader<File>, index: u64) -> Result<Option<Cbor>> {
+    let truncated = || PipelineError::Source(anyhow::anyhow!("CBOR item {} is truncated", index));
+
+    // Only an end of file before the first byte of the length prefix is a
+    // clean end; a partial prefix means the last item was cut short.
+    let mut length = [0u8; 4];
+    let mut filled = 0;
+    while filled < length.len() {
+        match reader.read(&mut length[filled..]).await? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(truncated()),
+            n => filled += n,
+        }
+    }
+
+    let mut item = vec![0u8; u32::from_be_bytes(length) as usize];
+    reader.read_exact(&mut item).await.map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => truncated(),
+        _ => e.into(),
+    })?;
+    let cbor = ciborium::from_reader(item.as_slice())
+        .map_err(|e| PipelineError::Source(anyhow::anyhow!("Invalid CBOR item {}: {}", index, e)))?;
+    Ok(Some(cbor))
+}
+
+fn record_from_cbor(cbor: Cbor, index: u64) -> Result<Record> {
+    match cbor_to_value(cbor) {
+        Value::Object(map) => Ok(Record::with_data(map.into_iter().collect::<IndexMap<_, _>>())),
+        _ => Err(PipelineError::Source(anyhow::anyhow!("CBOR item {} is not a map", index))),
+    }
+}
+
+#[async_trait]
+impl Source for CborSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let mut reader = BufReader::new(File::open(&self.file_path).await?);
+        let fields = match read_frame(&mut reader, 0).await? {
+            Some(Cbor::Map(entries)) => entries
+                .iter()
+                .map(|(key, value)| Field {
+                    name: map_key(key.clone()),
+                    data_type: cbor_data_type(value),
+                    nullable: true,
+                    description: None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let reader = BufReader::new(File::open(&self.file_path).await?);
+
+        let stream = futures::stream::unfold(Some((reader, 0u64)), |state| async move {
+            let (mut reader, index) = state?;
+            match read_frame(&mut reader, index).await {
+                Ok(Some(cbor)) => Some((record_from_cbor(cbor, index), Some((reader, index + 1)))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Sink;
+    use crate::sink::cbor::CborSink;
+    use crate::test_support::record;
+    use futures::StreamExt;
+    use serde_json::json;
+
+    async fn write_records(path: &Path, records: Vec<Record>) {
+        let mut sink = CborSink::new(path);
+        for record in records {
+            sink.write(record).await.unwrap();
+        }
+        sink.close().await.unwrap();
+    }
+
+    async fn read_all(path: &Path) -> Vec<Result<Record>> {
+        CborSource::new(path).read().await.unwrap().collect().await
+    }
+
+    #[tokio::test]
+    async fn records_round_trip_through_the_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("records.cbor");
+        let written = vec![
+            record(json!({"id": 1, "name": "alice", "score": 1.5, "active": true, "tags": ["a", "b"]})),
+            record(json!({"id": -2, "name": null, "nested": {"x": 1}})),
+        ];
+        write_records(&path, written.clone()).await;
+
+        let read: Vec<Record> = read_all(&path).await.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(read.len(), written.len());
+        for (read, written) in read.iter().zip(&written) {
+            assert_eq!(read.data, written.data);
+        }
+
+        let schema = CborSource::new(&path).get_schema().await.unwrap();
+        let names: Vec<&str> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "score", "active", "tags"]);
+        assert!(matches!(schema.fields[0].data_type, DataType::Integer));
+        assert!(matches!(schema.fields[2].data_type, DataType::Float));
+    }
+
+    #[tokio::test]
+    async fn empty_file_has_no_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.cbor");
+        std::fs::write(&path, b"").unwrap();
+        assert!(read_all(&path).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn partial_length_prefix_is_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("truncated.cbor");
+        write_records(&path, vec![record(json!({"id": 1}))]).await;
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.extend_from_slice(&[0, 0]);
+        std::fs::write(&path, bytes).unwrap();
+
+        let results = read_all(&path).await;
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        let error = results[1].as_ref().unwrap_err().to_string();
+        assert!(error.contains("CBOR item 1 is truncated"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn partial_item_is_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("truncated.cbor");
+        write_records(&path, vec![record(json!({"id": 1, "name": "alice"}))]).await;
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 3);
+        std::fs::write(&path, bytes).unwrap();
+
+        let results = read_all(&path).await;
+        assert_eq!(results.len(), 1);
+        let error = results[0].as_ref().unwrap_err().to_string();
+        assert!(error.contains("CBOR item 0 is truncated"), "{}", error);
+    }
+}
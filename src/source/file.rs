@@ -1,17 +1,23 @@
-use crate::core::{Source, Record, Schema, Field, DataType, Result, PipelineError, RecordStream};
+use crate::core::{open_decompressed, Compression, Source, Record, Schema, Field, DataType, Result, PipelineError, RecordStream};
 use async_trait::async_trait;
-use futures::stream::{StreamExt};
+use csv_async::{AsyncReader, AsyncReaderBuilder, StringRecord};
+use futures::future;
+use futures::stream::{Stream, StreamExt};
+use indexmap::IndexMap;
 use serde_json::Value;
-use std::collections::HashMap;
 use std::path::Path;
 use tokio::fs::File;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncSeekExt, BufReader, SeekFrom};
 use tokio_stream::wrappers::LinesStream;
 
 pub struct CsvSource {
     file_path: String,
     has_header: bool,
     delimiter: u8,
+    byte_range: Option<(u64, u64)>,
+    compression: Compression,
+    infer_types: bool,
+    sample_size: usize,
 }
 
 impl CsvSource {
@@ -20,6 +26,10 @@ impl CsvSource {
             file_path: file_path.as_ref().to_string_lossy().into_owned(),
             has_header: true,
             delimiter: b',',
+            byte_range: None,
+            compression: Compression::from_path(&file_path),
+            infer_types: false,
+            sample_size: 100,
         }
     }
     
@@ -32,26 +42,274 @@ impl CsvSource {
         self.has_header = has_header;
         self
     }
+
+    /// Restricts `read` to the records whose first byte lies in `[start, end)`.
+    /// Reading starts at the first record boundary at or after `start`, so
+    /// adjacent ranges tile the file without duplicating or losing records.
+    /// The schema is still taken from the header at the start of the file.
+    /// Boundaries are found by newline, so a range must not start inside a
+    /// quoted field that spans lines.
+    pub fn with_byte_range(mut self, start: u64, end: u64) -> Self {
+        self.byte_range = Some((start, end));
+        self
+    }
+
+    /// Overrides the compression inferred from the file extension.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Types each column as `Integer`, `Float`, `Boolean`, `Date`
+    /// (`YYYY-MM-DD`), `Timestamp` (RFC 3339) or `String` from the first
+    /// `sample_size` rows, and parses numbers and booleans into matching
+    /// values. A column is only given a non-string type if every non-empty
+    /// sampled cell parses as it. Empty cells become `Value::Null` and make the
+    /// column nullable; a later cell that does not parse is kept as a string.
+    pub fn with_type_inference(mut self, infer_types: bool) -> Self {
+        self.infer_types = infer_types;
+        self
+    }
+
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size.max(1);
+        self
+    }
+
+    fn csv_reader<R: AsyncRead + Unpin + Send>(&self, reader: R) -> AsyncReader<R> {
+        AsyncReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .create_reader(reader)
+    }
+
+    async fn infer_fields<S>(&self, names: Vec<String>, rows: &mut S) -> Result<Vec<Field>>
+    where
+        S: Stream<Item = csv_async::Result<StringRecord>> + Unpin,
+    {
+        let mut candidates: Vec<ColumnInference> = names.iter().map(|_| ColumnInference::default()).collect();
+        let mut sampled = 0;
+        while sampled < self.sample_size {
+            let Some(row) = rows.next().await.transpose().map_err(csv_error)? else {
+                break;
+            };
+            for (candidate, cell) in candidates.iter_mut().zip(row.iter()) {
+                candidate.observe(cell);
+            }
+            sampled += 1;
+        }
+
+        Ok(names
+            .into_iter()
+            .zip(candidates)
+            .map(|(name, candidate)| Field {
+                name,
+                data_type: candidate.data_type(),
+                nullable: candidate.nullable,
+                description: None,
+            })
+            .collect())
+    }
+
+    async fn read_records(&self, field_names: Vec<String>) -> Result<RecordStream> {
+        let reader = open_decompressed(&self.file_path, self.compression).await?;
+        let stream = self
+            .csv_reader(reader)
+            .into_records()
+            .skip(usize::from(self.has_header))
+            .map(move |row| {
+                let row = row.map_err(csv_error)?;
+                Ok(record_from_fields(row.iter(), &field_names))
+            });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn read_range(&self, start: u64, end: u64, field_names: Vec<String>) -> Result<RecordStream> {
+        let mut reader = BufReader::new(File::open(&self.file_path).await?);
+        let mut offset = 0;
+
+        if start > 0 {
+            // Back up one byte so a range starting exactly on a line boundary
+            // keeps that line instead of discarding it as a partial record.
+            reader.seek(SeekFrom::Start(start - 1)).await?;
+            let mut partial = Vec::new();
+            offset = start - 1 + reader.read_until(b'\n', &mut partial).await? as u64;
+        }
+
+        let has_header = self.has_header;
+        let row_start = move |row: &StringRecord| offset + row.position().map_or(0, |p| p.byte());
+
+        let stream = self
+            .csv_reader(reader)
+            .into_records()
+            .take_while(move |row| future::ready(row.as_ref().map_or(true, |row| row_start(row) < end)))
+            .filter_map(move |row| {
+                let record = match row {
+                    Ok(row) if has_header && row_start(&row) == 0 => None,
+                    Ok(row) => Some(Ok(record_from_fields(row.iter(), &field_names))),
+                    Err(e) => Some(Err(csv_error(e))),
+                };
+                future::ready(record)
+            });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+fn csv_error(e: csv_async::Error) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Invalid CSV: {}", e))
+}
+
+/// Which types every non-empty sampled cell of a column parses as.
+pub(crate) struct ColumnInference {
+    integer: bool,
+    float: bool,
+    boolean: bool,
+    date: bool,
+    timestamp: bool,
+    seen_value: bool,
+    pub(crate) nullable: bool,
+}
+
+impl Default for ColumnInference {
+    fn default() -> Self {
+        Self {
+            integer: true,
+            float: true,
+            boolean: true,
+            date: true,
+            timestamp: true,
+            seen_value: false,
+            nullable: false,
+        }
+    }
+}
+
+impl ColumnInference {
+    pub(crate) fn observe(&mut self, cell: &str) {
+        let cell = cell.trim();
+        if cell.is_empty() {
+            self.nullable = true;
+            return;
+        }
+        self.seen_value = true;
+        self.integer &= cell.parse::<i64>().is_ok();
+        self.float &= parse_float(cell).is_some();
+        self.boolean &= parse_bool(cell).is_some();
+        self.date &= chrono::NaiveDate::parse_from_str(cell, "%Y-%m-%d").is_ok();
+        self.timestamp &= chrono::DateTime::parse_from_rfc3339(cell).is_ok();
+    }
+
+    pub(crate) fn data_type(&self) -> DataType {
+        match self {
+            Self { seen_value: false, .. } => DataType::String,
+            Self { integer: true, .. } => DataType::Integer,
+            Self { float: true, .. } => DataType::Float,
+            Self { boolean: true, .. } => DataType::Boolean,
+            Self { date: true, .. } => DataType::Date,
+            Self { timestamp: true, .. } => DataType::Timestamp,
+            _ => DataType::String,
+        }
+    }
+}
+
+// Rejects "NaN" and "inf", which `f64` parses but JSON cannot represent.
+fn parse_float(cell: &str) -> Option<f64> {
+    cell.parse::<f64>().ok().filter(|f| f.is_finite())
+}
+
+fn parse_bool(cell: &str) -> Option<bool> {
+    if cell.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if cell.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Parses the string cells of `record` into values of the inferred types.
+pub(crate) fn apply_column_types(mut record: Record, fields: &[Field]) -> Record {
+    for field in fields {
+        let Some(Value::String(cell)) = record.data.get(&field.name) else {
+            continue;
+        };
+        let cell = cell.trim();
+        let typed = if cell.is_empty() {
+            Some(Value::Null)
+        } else {
+            match field.data_type {
+                DataType::Integer => cell.parse::<i64>().ok().map(Value::from),
+                DataType::Float => parse_float(cell).map(Value::from),
+                DataType::Boolean => parse_bool(cell).map(Value::Bool),
+                _ => None,
+            }
+        };
+        if let Some(typed) = typed {
+            record.data.insert(field.name.clone(), typed);
+        }
+    }
+    record
+}
+
+pub(crate) fn record_from_fields<'a>(values: impl Iterator<Item = &'a str>, field_names: &[String]) -> Record {
+    let data = field_names
+        .iter()
+        .zip(values)
+        .map(|(field_name, value)| (field_name.clone(), Value::String(value.to_string())))
+        .collect::<IndexMap<_, _>>();
+
+    Record::with_data(data)
+}
+
+/// Parses one line of delimited text, honouring RFC 4180 quoting. Values
+/// are kept as written and cells beyond `field_names` are ignored.
+pub(crate) fn parse_delimited_line(line: &str, delimiter: u8, field_names: &[String]) -> Record {
+    let row = split_delimited_line(line, delimiter);
+    record_from_fields(row.iter().map(String::as_str), field_names)
+}
+
+/// Splits one line of delimited text into its unquoted cells.
+pub(crate) fn split_delimited_line(line: &str, delimiter: u8) -> Vec<String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(line.as_bytes());
+    reader
+        .records()
+        .next()
+        .and_then(|row| row.ok())
+        .map(|row| row.iter().map(str::to_string).collect())
+        .unwrap_or_default()
 }
 
 #[async_trait]
 impl Source for CsvSource {
     async fn get_schema(&self) -> Result<Schema> {
-        let file = File::open(&self.file_path).await?;
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
+        let reader = open_decompressed(&self.file_path, self.compression).await?;
+        let mut rows = self.csv_reader(reader).into_records();
         
-        if let Some(first_line) = lines.next_line().await? {
+        if let Some(first_row) = rows.next().await.transpose().map_err(csv_error)? {
             let headers: Vec<String> = if self.has_header {
-                first_line.split(self.delimiter as char)
-                    .map(|s| s.trim().to_string())
-                    .collect()
+                first_row.iter().map(|name| name.trim().to_string()).collect()
             } else {
-                (0..first_line.split(self.delimiter as char).count())
+                (0..first_row.len())
                     .map(|i| format!("column_{}", i))
                     .collect()
             };
             
+            if self.infer_types {
+                if !self.has_header {
+                    // The first row is data, so sample it as well.
+                    let reader = open_decompressed(&self.file_path, self.compression).await?;
+                    rows = self.csv_reader(reader).into_records();
+                }
+                return Ok(Schema::new(self.infer_fields(headers, &mut rows).await?));
+            }
+
             let fields = headers.into_iter()
                 .map(|name| Field {
                     name,
@@ -68,110 +326,103 @@ impl Source for CsvSource {
     }
     
     async fn read(&self) -> Result<RecordStream> {
-        let file = File::open(&self.file_path).await?;
-        let reader = BufReader::new(file);
-        let lines = LinesStream::new(reader.lines());
-        
         let schema = self.get_schema().await?;
         let field_names: Vec<String> = schema.field_names().into_iter().map(|s| s.to_string()).collect();
-        let has_header = self.has_header;
-        let delimiter = self.delimiter;
-        
-        let stream = lines
-            .enumerate()
-            .filter_map(move |(index, line_result)| {
-                let field_names = field_names.clone();
-                async move {
-                    match line_result {
-                        Ok(line) => {
-                            if has_header && index == 0 {
-                                return None;
-                            }
-                            
-                            let values: Vec<&str> = line.split(delimiter as char).collect();
-                            let mut data = HashMap::new();
-                            
-                            for (i, value) in values.iter().enumerate() {
-                                if let Some(field_name) = field_names.get(i) {
-                                    data.insert(
-                                        field_name.clone(),
-                                        Value::String(value.trim().to_string())
-                                    );
-                                }
-                            }
-                            
-                            Some(Ok(Record::with_data(data)))
-                        }
-                        Err(e) => Some(Err(PipelineError::Io(e))),
-                    }
-                }
-            });
-        
-        Ok(Box::pin(stream))
+
+        let stream = if let Some((start, end)) = self.byte_range {
+            if self.compression != Compression::None {
+                return Err(PipelineError::Config(
+                    "Byte ranges cannot be read from a compressed file".to_string()
+                ));
+            }
+            self.read_range(start, end, field_names).await?
+        } else {
+            self.read_records(field_names).await?
+        };
+
+        if !self.infer_types {
+            return Ok(stream);
+        }
+        let fields = schema.fields;
+        Ok(Box::pin(stream.map(move |result| result.map(|record| apply_column_types(record, &fields)))))
+    }
+}
+
+/// Infers a schema from the keys of one JSON Lines line, typing every field
+/// as nullable `Json`.
+pub(crate) fn schema_from_json_line(line: &str) -> Result<Schema> {
+    let json_value: Value = serde_json::from_str(line)?;
+
+    if let Some(obj) = json_value.as_object() {
+        let fields = obj.keys()
+            .map(|key| Field {
+                name: key.clone(),
+                data_type: DataType::Json,
+                nullable: true,
+                description: None,
+            })
+            .collect();
+
+        Ok(Schema::new(fields))
+    } else {
+        Err(PipelineError::Schema("First line is not a JSON object".to_string()))
+    }
+}
+
+pub(crate) fn parse_json_line(line: &str) -> Result<Record> {
+    match serde_json::from_str::<Value>(line) {
+        Ok(Value::Object(obj)) => {
+            let data = obj.into_iter().collect();
+            Ok(Record::with_data(data))
+        }
+        Ok(_) => Err(PipelineError::Schema(
+            "Line is not a JSON object".to_string()
+        )),
+        Err(e) => Err(PipelineError::Serialization(e)),
     }
 }
 
 pub struct JsonLinesSource {
     file_path: String,
+    compression: Compression,
 }
 
 impl JsonLinesSource {
     pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
         Self {
             file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            compression: Compression::from_path(&file_path),
         }
     }
+
+    /// Overrides the compression inferred from the file extension.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
 }
 
 #[async_trait]
 impl Source for JsonLinesSource {
     async fn get_schema(&self) -> Result<Schema> {
-        let file = File::open(&self.file_path).await?;
-        let reader = BufReader::new(file);
+        let reader = open_decompressed(&self.file_path, self.compression).await?;
         let mut lines = reader.lines();
         
         if let Some(first_line) = lines.next_line().await? {
-            let json_value: Value = serde_json::from_str(&first_line)?;
-            
-            if let Some(obj) = json_value.as_object() {
-                let fields = obj.keys()
-                    .map(|key| Field {
-                        name: key.clone(),
-                        data_type: DataType::Json,
-                        nullable: true,
-                        description: None,
-                    })
-                    .collect();
-                
-                Ok(Schema::new(fields))
-            } else {
-                Err(PipelineError::Schema("First line is not a JSON object".to_string()))
-            }
+            schema_from_json_line(&first_line)
         } else {
             Err(PipelineError::Source(anyhow::anyhow!("Empty JSON Lines file")))
         }
     }
     
     async fn read(&self) -> Result<RecordStream> {
-        let file = File::open(&self.file_path).await?;
-        let reader = BufReader::new(file);
+        let reader = open_decompressed(&self.file_path, self.compression).await?;
         let lines = LinesStream::new(reader.lines());
         
         let stream = lines.filter_map(|line_result| {
             async move {
                 match line_result {
-                    Ok(line) => {
-                        match serde_json::from_str::<Value>(&line) {
-                            Ok(Value::Object(obj)) => {
-                                let data = obj.into_iter().collect();
-                                Some(Ok(Record::with_data(data)))
-                            }
-                            Ok(_) => Some(Err(PipelineError::Schema(
-                                "Line is not a JSON object".to_string()
-                            ))),
-                            Err(e) => Some(Err(PipelineError::Serialization(e))),
-                        }
-                    }
+                    Ok(line) => Some(parse_json_line(&line)),
                     Err(e) => Some(Err(PipelineError::Io(e))),
                 }
             }
@@ -179,4 +430,55 @@ impl Source for JsonLinesSource {
         
         Ok(Box::pin(stream))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+
+    async fn read_all(source: &CsvSource) -> Vec<Record> {
+        source.read().await.unwrap().try_collect().await.unwrap()
+    }
+
+    fn ids(records: &[Record]) -> Vec<&str> {
+        records.iter().map(|r| r.get_field("id").unwrap().as_str().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn complementary_byte_ranges_cover_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        let contents = "id,name\n1,alice\n2,bob\n3,\"carol, jr\"\n4,dave\n";
+        std::fs::write(&path, contents).unwrap();
+        let full = read_all(&CsvSource::new(&path)).await;
+        assert_eq!(ids(&full), ["1", "2", "3", "4"]);
+
+        // Every split point, including ones on and inside line boundaries.
+        let len = contents.len() as u64;
+        for split in 0..=len {
+            let mut records = read_all(&CsvSource::new(&path).with_byte_range(0, split)).await;
+            records.extend(read_all(&CsvSource::new(&path).with_byte_range(split, len)).await);
+            assert_eq!(ids(&records), ids(&full), "split at {}", split);
+        }
+    }
+
+    #[tokio::test]
+    async fn parses_rfc_4180_quoting() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quoted.csv");
+        let contents = r#"id,note
+1,"Smith, John"
+2,"she said ""hi"""
+3,"first line
+second line"
+4,plain
+5,"  padded  "
+"#;
+        std::fs::write(&path, contents).unwrap();
+
+        let records = read_all(&CsvSource::new(&path)).await;
+        assert_eq!(ids(&records), ["1", "2", "3", "4", "5"]);
+        let notes: Vec<&str> = records.iter().map(|r| r.get_field("note").unwrap().as_str().unwrap()).collect();
+        assert_eq!(notes, ["Smith, John", "she said \"hi\"", "first line\nsecond line", "plain", "  padded  "]);
+    }
+}
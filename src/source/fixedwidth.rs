@@ -0,0 +1,273 @@
+use crate::core::{open_decompressed, Compression, DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use crate::source::file::{apply_column_types, ColumnInference};
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::path::Path;
+use tokio::io::AsyncBufReadExt;
+use tokio_stream::wrappers::LinesStream;
+
+/// A column spanning `width` characters from the zero-based character
+/// offset `start`.
+#[derive(Clone)]
+struct FixedWidthColumn {
+    name: String,
+    start: usize,
+    width: usize,
+}
+
+impl FixedWidthColumn {
+    /// Returns this column's trimmed cell in `line`, where `bounds` holds the
+    /// byte offset of each character of `line` followed by its length, or
+    /// `None` when the line ends before the column starts.
+    fn cell<'a>(&self, line: &'a str, bounds: &[usize]) -> Option<&'a str> {
+        let chars = bounds.len() - 1;
+        if self.start >= chars {
+            return None;
+        }
+        let end = self.start.saturating_add(self.width).min(chars);
+        Some(line[bounds[self.start]..bounds[end]].trim())
+    }
+}
+
+/// Reads a file of fixed-width columns, slicing each line into the given
+/// columns and trimming their padding. Offsets count characters, not bytes.
+///
+/// A line too short to reach a column gives it `Value::Null`, and one that
+/// ends partway through a column gives what is there; characters outside
+/// every column are ignored. Blank lines are skipped.
+pub struct FixedWidthSource {
+    file_path: String,
+    columns: Vec<FixedWidthColumn>,
+    skip_lines: usize,
+    compression: Compression,
+    infer_types: bool,
+    sample_size: usize,
+}
+
+impl FixedWidthSource {
+    pub fn new<P: AsRef<Path>>(file_path: P, columns: Vec<(String, usize, usize)>) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            columns: columns
+                .into_iter()
+                .map(|(name, start, width)| FixedWidthColumn { name, start, width })
+                .collect(),
+            skip_lines: 0,
+            compression: Compression::from_path(&file_path),
+            infer_types: false,
+            sample_size: 100,
+        }
+    }
+
+    /// Skips this many leading lines, such as a header or banner.
+    pub fn with_skip_lines(mut self, skip_lines: usize) -> Self {
+        self.skip_lines = skip_lines;
+        self
+    }
+
+    /// Overrides the compression inferred from the file extension.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Types each column from the first `sample_size` lines as
+    /// [`CsvSource::with_type_inference`](crate::source::file::CsvSource::with_type_inference)
+    /// does, parsing numbers and booleans into matching values. Empty and
+    /// missing cells become `Value::Null`.
+    pub fn with_type_inference(mut self, infer_types: bool) -> Self {
+        self.infer_types = infer_types;
+        self
+    }
+
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size.max(1);
+        self
+    }
+
+    async fn lines(&self) -> Result<impl futures::Stream<Item = Result<String>> + Send + use<>> {
+        let reader = open_decompressed(&self.file_path, self.compression).await?;
+        Ok(LinesStream::new(reader.lines())
+            .skip(self.skip_lines)
+            .filter_map(|line| async move {
+                match line {
+                    Ok(line) if line.trim().is_empty() => None,
+                    Ok(line) => Some(Ok(line)),
+                    Err(e) => Some(Err(PipelineError::Io(e))),
+                }
+            }))
+    }
+}
+
+fn char_bounds(line: &str) -> Vec<usize> {
+    line.char_indices().map(|(i, _)| i).chain([line.len()]).collect()
+}
+
+fn parse_line(line: &str, columns: &[FixedWidthColumn]) -> Record {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let bounds = char_bounds(line);
+    let data = columns
+        .iter()
+        .map(|column| {
+            let value = column
+                .cell(line, &bounds)
+                .map_or(Value::Null, |cell| Value::String(cell.to_string()));
+            (column.name.clone(), value)
+        })
+        .collect::<IndexMap<_, _>>();
+
+    Record::with_data(data)
+}
+
+#[async_trait]
+impl Source for FixedWidthSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        if !self.infer_types {
+            let fields = self
+                .columns
+                .iter()
+                .map(|column| Field {
+                    name: column.name.clone(),
+                    data_type: DataType::String,
+                    nullable: true,
+                    description: None,
+                })
+                .collect();
+            return Ok(Schema::new(fields));
+        }
+
+        let mut candidates: Vec<ColumnInference> = self.columns.iter().map(|_| ColumnInference::default()).collect();
+        let mut lines = Box::pin(self.lines().await?.take(self.sample_size));
+        while let Some(line) = lines.next().await {
+            let line = line?;
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            let bounds = char_bounds(line);
+            for (candidate, column) in candidates.iter_mut().zip(&self.columns) {
+                candidate.observe(column.cell(line, &bounds).unwrap_or(""));
+            }
+        }
+
+        let fields = self
+            .columns
+            .iter()
+            .zip(candidates)
+            .map(|(column, candidate)| Field {
+                name: column.name.clone(),
+                data_type: candidate.data_type(),
+                nullable: candidate.nullable,
+                description: None,
+            })
+            .collect();
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let columns = self.columns.clone();
+        let stream = self
+            .lines()
+            .await?
+            .map(move |line| line.map(|line| parse_line(&line, &columns)));
+
+        if !self.infer_types {
+            return Ok(Box::pin(stream));
+        }
+        let fields = self.get_schema().await?.fields;
+        Ok(Box::pin(stream.map(move |result| result.map(|record| apply_column_types(record, &fields)))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use serde_json::json;
+
+    fn columns() -> Vec<(String, usize, usize)> {
+        vec![("id".to_string(), 0, 3), ("name".to_string(), 3, 6), ("score".to_string(), 9, 4)]
+    }
+
+    async fn read_all(source: &FixedWidthSource) -> Vec<Value> {
+        let records: Vec<Record> = source.read().await.unwrap().try_collect().await.unwrap();
+        records
+            .into_iter()
+            .map(|record| Value::Object(record.data.into_iter().collect()))
+            .collect()
+    }
+
+    fn write(dir: &tempfile::TempDir, contents: &str) -> std::path::PathBuf {
+        let path = dir.path().join("data.txt");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn short_lines_give_partial_or_null_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "1  alice   42\n2  bo\n3\n");
+
+        let records = read_all(&FixedWidthSource::new(path, columns())).await;
+        assert_eq!(
+            records,
+            [
+                json!({"id": "1", "name": "alice", "score": "42"}),
+                json!({"id": "2", "name": "bo", "score": null}),
+                json!({"id": "3", "name": null, "score": null}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn offsets_count_characters_not_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "1  zoë   7\n2  日本語   12\n");
+
+        let records = read_all(&FixedWidthSource::new(path, columns())).await;
+        assert_eq!(
+            records,
+            [
+                json!({"id": "1", "name": "zoë", "score": "7"}),
+                json!({"id": "2", "name": "日本語", "score": "12"}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn crlf_line_endings_and_skipped_lines_are_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "ID NAME  SCORE\r\n-------------\r\n1  alice   42\r\n\r\n2  bob     7\r\n");
+
+        let records = read_all(&FixedWidthSource::new(path, columns()).with_skip_lines(2)).await;
+        assert_eq!(
+            records,
+            [
+                json!({"id": "1", "name": "alice", "score": "42"}),
+                json!({"id": "2", "name": "bob", "score": "7"}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn type_inference_parses_numbers_and_nulls() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "1  alice   42\n2  bob\n");
+        let source = FixedWidthSource::new(path, columns()).with_type_inference(true);
+
+        let schema = source.get_schema().await.unwrap();
+        let types: Vec<(DataType, bool)> = schema.fields.iter().map(|f| (f.data_type.clone(), f.nullable)).collect();
+        assert_eq!(
+            types,
+            [(DataType::Integer, false), (DataType::String, false), (DataType::Integer, true)]
+        );
+
+        let records = read_all(&source).await;
+        assert_eq!(
+            records,
+            [
+                json!({"id": 1, "name": "alice", "score": 42}),
+                json!({"id": 2, "name": "bob", "score": null}),
+            ]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::core::arrow::{records_from_batch, schema_from_arrow};
+use crate::core::{PipelineError, Record, RecordStream, Result, Schema, Source};
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::{FlightClient, FlightDescriptor, Ticket};
+use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use tonic::transport::{ClientTlsConfig, Endpoint};
+
+/// What to ask the Flight service for.
+#[derive(Debug, Clone)]
+pub enum FlightRequest {
+    /// Fetch a ticket obtained out of band with a single `DoGet`.
+    Ticket(Vec<u8>),
+    /// Resolve a command (such as a query) with `GetFlightInfo`, then `DoGet`
+    /// each returned endpoint's ticket in order.
+    Command(Vec<u8>),
+}
+
+fn source_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Arrow Flight error: {}", e))
+}
+
+/// Streams the `RecordBatch`es served by an Arrow Flight endpoint as records,
+/// one record per row. `https://` endpoints use TLS with the webpki roots.
+///
+/// Tickets are fetched from the configured endpoint even when the service
+/// reports other locations for them.
+pub struct ArrowFlightSource {
+    endpoint: String,
+    request: FlightRequest,
+    headers: Vec<(String, String)>,
+}
+
+impl ArrowFlightSource {
+    pub fn new(endpoint: &str, request: FlightRequest) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            request,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds a gRPC metadata header, e.g. `authorization`, to every call.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    async fn client(&self) -> Result<FlightClient> {
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone()).map_err(source_error)?;
+        if self.endpoint.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_webpki_roots())
+                .map_err(source_error)?;
+        }
+        let channel = endpoint.connect().await.map_err(source_error)?;
+
+        let mut client = FlightClient::new(channel);
+        for (name, value) in &self.headers {
+            client.add_header(name, value).map_err(source_error)?;
+        }
+        Ok(client)
+    }
+
+    async fn tickets(&self, client: &mut FlightClient) -> Result<Vec<Ticket>> {
+        match self.request {
+            FlightRequest::Ticket(ref ticket) => Ok(vec![Ticket::new(ticket.clone())]),
+            FlightRequest::Command(ref command) => {
+                let info = client
+                    .get_flight_info(FlightDescriptor::new_cmd(command.clone()))
+                    .await
+                    .map_err(source_error)?;
+                Ok(info.endpoint.into_iter().filter_map(|endpoint| endpoint.ticket).collect())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Source for ArrowFlightSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let mut client = self.client().await?;
+        if let FlightRequest::Command(ref command) = self.request {
+            let info = client
+                .get_flight_info(FlightDescriptor::new_cmd(command.clone()))
+                .await
+                .map_err(source_error)?;
+            let schema = info.try_decode_schema().map_err(source_error)?;
+            return Ok(schema_from_arrow(&schema));
+        }
+
+        // A bare ticket has no descriptor to ask about, so read until the
+        // stream has announced its schema.
+        let ticket = self.tickets(&mut client).await?.remove(0);
+        let mut batches = client.do_get(ticket).await.map_err(source_error)?;
+        while batches.schema().is_none() {
+            if batches.try_next().await.map_err(source_error)?.is_none() {
+                break;
+            }
+        }
+        let schema = batches
+            .schema()
+            .ok_or_else(|| source_error("stream ended without a schema"))?;
+        Ok(schema_from_arrow(schema))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let mut client = self.client().await?;
+        let tickets = self.tickets(&mut client).await?;
+
+        // Tickets are fetched one after another over the same client.
+        let batches = stream::unfold(
+            (client, tickets.into_iter(), None::<FlightRecordBatchStream>),
+            |(mut client, mut tickets, mut current)| async move {
+                loop {
+                    if let Some(mut batches) = current.take()
+                        && let Some(batch) = batches.next().await
+                    {
+                        return Some((batch.map_err(source_error), (client, tickets, Some(batches))));
+                    }
+                    let ticket = tickets.next()?;
+                    match client.do_get(ticket).await {
+                        Ok(batches) => current = Some(batches),
+                        Err(e) => return Some((Err(source_error(e)), (client, tickets, None))),
+                    }
+                }
+            },
+        );
+
+        let stream = batches.flat_map(|batch| {
+            let items: Vec<Result<Record>> = match batch.and_then(|batch| records_from_batch(&batch)) {
+                Ok(records) => records.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+    use arrow_flight::encode::FlightDataEncoderBuilder;
+    use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+    use arrow_flight::{
+        Action, ActionType, Criteria, Empty, FlightData, FlightEndpoint, FlightInfo, HandshakeRequest,
+        HandshakeResponse, PollInfo, PutResult, SchemaResult,
+    };
+    use arrow_schema::{DataType as ArrowType, Field as ArrowField, Schema as ArrowSchema, SchemaRef};
+    use futures::stream::BoxStream;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+    use tonic::{Request, Response, Status, Streaming};
+
+    fn arrow_schema() -> SchemaRef {
+        Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", ArrowType::Int64, false),
+            ArrowField::new("name", ArrowType::Utf8, true),
+        ]))
+    }
+
+    fn batch(ids: Vec<i64>, names: Vec<Option<&str>>) -> RecordBatch {
+        let columns: Vec<ArrayRef> = vec![Arc::new(Int64Array::from(ids)), Arc::new(StringArray::from(names))];
+        RecordBatch::try_new(arrow_schema(), columns).unwrap()
+    }
+
+    /// Serves two tickets of one small batch each; the command `all` lists
+    /// both.
+    struct MockFlight;
+
+    #[tonic::async_trait]
+    impl FlightService for MockFlight {
+        type HandshakeStream = BoxStream<'static, std::result::Result<HandshakeResponse, Status>>;
+        type ListFlightsStream = BoxStream<'static, std::result::Result<FlightInfo, Status>>;
+        type DoGetStream = BoxStream<'static, std::result::Result<FlightData, Status>>;
+        type DoPutStream = BoxStream<'static, std::result::Result<PutResult, Status>>;
+        type DoActionStream = BoxStream<'static, std::result::Result<arrow_flight::Result, Status>>;
+        type ListActionsStream = BoxStream<'static, std::result::Result<ActionType, Status>>;
+        type DoExchangeStream = BoxStream<'static, std::result::Result<FlightData, Status>>;
+
+        async fn get_flight_info(
+            &self,
+            request: Request<FlightDescriptor>,
+        ) -> std::result::Result<Response<FlightInfo>, Status> {
+            if request.into_inner().cmd.as_ref() != b"all" {
+                return Err(Status::not_found("unknown command"));
+            }
+            let info = FlightInfo::new()
+                .try_with_schema(&arrow_schema())
+                .map_err(|e| Status::internal(e.to_string()))?
+                .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new("first")))
+                .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new("second")));
+            Ok(Response::new(info))
+        }
+
+        async fn do_get(&self, request: Request<Ticket>) -> std::result::Result<Response<Self::DoGetStream>, Status> {
+            let batch = match request.into_inner().ticket.as_ref() {
+                b"first" => batch(vec![1, 2], vec![Some("ada"), None]),
+                b"second" => batch(vec![3], vec![Some("grace")]),
+                _ => return Err(Status::not_found("unknown ticket")),
+            };
+            let stream = FlightDataEncoderBuilder::new()
+                .build(stream::iter([Ok(batch)]))
+                .map_err(Status::from);
+            Ok(Response::new(Box::pin(stream)))
+        }
+
+        async fn handshake(
+            &self,
+            _request: Request<Streaming<HandshakeRequest>>,
+        ) -> std::result::Result<Response<Self::HandshakeStream>, Status> {
+            Err(Status::unimplemented("handshake"))
+        }
+
+        async fn list_flights(
+            &self,
+            _request: Request<Criteria>,
+        ) -> std::result::Result<Response<Self::ListFlightsStream>, Status> {
+            Err(Status::unimplemented("list_flights"))
+        }
+
+        async fn poll_flight_info(
+            &self,
+            _request: Request<FlightDescriptor>,
+        ) -> std::result::Result<Response<PollInfo>, Status> {
+            Err(Status::unimplemented("poll_flight_info"))
+        }
+
+        async fn get_schema(
+            &self,
+            _request: Request<FlightDescriptor>,
+        ) -> std::result::Result<Response<SchemaResult>, Status> {
+            Err(Status::unimplemented("get_schema"))
+        }
+
+        async fn do_put(
+            &self,
+            _request: Request<Streaming<FlightData>>,
+        ) -> std::result::Result<Response<Self::DoPutStream>, Status> {
+            Err(Status::unimplemented("do_put"))
+        }
+
+        async fn do_exchange(
+            &self,
+            _request: Request<Streaming<FlightData>>,
+        ) -> std::result::Result<Response<Self::DoExchangeStream>, Status> {
+            Err(Status::unimplemented("do_exchange"))
+        }
+
+        async fn do_action(
+            &self,
+            _request: Request<Action>,
+        ) -> std::result::Result<Response<Self::DoActionStream>, Status> {
+            Err(Status::unimplemented("do_action"))
+        }
+
+        async fn list_actions(
+            &self,
+            _request: Request<Empty>,
+        ) -> std::result::Result<Response<Self::ListActionsStream>, Status> {
+            Err(Status::unimplemented("list_actions"))
+        }
+    }
+
+    async fn serve() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(FlightServiceServer::new(MockFlight))
+                .serve_with_incoming(tonic::transport::server::TcpIncoming::from(listener)),
+        );
+        endpoint
+    }
+
+    async fn read_all(source: &ArrowFlightSource) -> Result<Vec<Value>> {
+        source
+            .read()
+            .await?
+            .map_ok(|record| Value::Object(record.data.into_iter().collect()))
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn command_reads_every_endpoint_in_order() {
+        let source = ArrowFlightSource::new(&serve().await, FlightRequest::Command(b"all".to_vec()));
+
+        assert_eq!(source.get_schema().await.unwrap().field_names(), vec!["id", "name"]);
+        assert_eq!(
+            read_all(&source).await.unwrap(),
+            vec![
+                json!({"id": 1, "name": "ada"}),
+                json!({"id": 2, "name": null}),
+                json!({"id": 3, "name": "grace"}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn ticket_reads_a_single_stream() {
+        let endpoint = serve().await;
+        let source = ArrowFlightSource::new(&endpoint, FlightRequest::Ticket(b"second".to_vec()));
+
+        assert_eq!(source.get_schema().await.unwrap().field_names(), vec!["id", "name"]);
+        assert_eq!(read_all(&source).await.unwrap(), vec![json!({"id": 3, "name": "grace"})]);
+
+        let unknown = ArrowFlightSource::new(&endpoint, FlightRequest::Ticket(b"third".to_vec()));
+        assert!(matches!(read_all(&unknown).await, Err(PipelineError::Source(_))));
+    }
+}
@@ -0,0 +1,420 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, RetryPolicy, Schema, Source};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    /// The first page not yet fully emitted, or `None` once pagination finished.
+    next: Option<String>,
+}
+
+/// Reads records from a paginated JSON API. Each page's records are taken
+/// from `records_pointer` and the next page's URL from `next_pointer`;
+/// pagination stops when that value is absent or null.
+///
+/// With a checkpoint file the URL of each page is persisted before it is
+/// fetched, which is only after the previous page was fully consumed. A
+/// restarted job resumes from that page, so completed pages are never
+/// processed twice. A finished run is recorded too and yields nothing until
+/// the checkpoint file is removed.
+///
+/// Headers added with `with_header`, such as `Authorization`, are sent with
+/// every page request.
+pub struct HttpJsonSource {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    records_pointer: String,
+    next_pointer: String,
+    checkpoint_path: Option<PathBuf>,
+    retry: RetryPolicy,
+}
+
+/// The paginating REST source, under the name it is usually looked for by.
+/// Pages are fetched lazily as records are consumed, following the URL at
+/// `next_pointer` until it is absent; checkpointing and retries are those
+/// of [`HttpJsonSource`].
+pub type HttpSource = HttpJsonSource;
+
+impl HttpJsonSource {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            headers: Vec::new(),
+            records_pointer: "/data".to_string(),
+            next_pointer: "/next".to_string(),
+            checkpoint_path: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_records_pointer(mut self, pointer: &str) -> Self {
+        self.records_pointer = pointer.to_string();
+        self
+    }
+
+    pub fn with_next_pointer(mut self, pointer: &str) -> Self {
+        self.next_pointer = pointer.to_string();
+        self
+    }
+
+    pub fn with_checkpoint<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.checkpoint_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    async fn start_url(&self) -> Result<Option<String>> {
+        let Some(ref path) = self.checkpoint_path else {
+            return Ok(Some(self.url.clone()));
+        };
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(serde_json::from_slice::<Checkpoint>(&bytes)?.next),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Some(self.url.clone())),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+struct PageFetcher {
+    client: reqwest::Client,
+    headers: Vec<(String, String)>,
+    records_pointer: String,
+    next_pointer: String,
+    checkpoint_path: Option<PathBuf>,
+    retry: RetryPolicy,
+}
+
+impl PageFetcher {
+    async fn save_checkpoint(&self, next: Option<&str>) -> Result<()> {
+        if let Some(ref path) = self.checkpoint_path {
+            let checkpoint = Checkpoint {
+                next: next.map(str::to_string),
+            };
+            tokio::fs::write(path, serde_json::to_vec(&checkpoint)?).await?;
+        }
+        Ok(())
+    }
+
+    async fn fetch_once(&self, url: &str) -> std::result::Result<Value, (bool, String)> {
+        let mut request = self.client.get(url);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        // A builder error, such as an invalid header, fails the same way on
+        // every attempt.
+        let response = request.send().await.map_err(|e| (!e.is_builder(), e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retryable = status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+            return Err((retryable, format!("HTTP {}", status)));
+        }
+        response.json::<Value>().await.map_err(|e| (true, e.to_string()))
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Value> {
+        let mut attempt = 1;
+        loop {
+            match self.fetch_once(url).await {
+                Ok(page) => return Ok(page),
+                Err((true, reason)) if attempt < self.retry.max_attempts => {
+                    tracing::warn!("Fetching {} failed (attempt {}): {}", url, attempt, reason);
+                    tokio::time::sleep(self.retry.delay_for(attempt)).await;
+                    attempt += 1;
+                }
+                Err((_, reason)) => {
+                    return Err(PipelineError::Source(anyhow::anyhow!(
+                        "Fetching {} failed after {} attempt(s): {}",
+                        url,
+                        attempt,
+                        reason
+                    )));
+                }
+            }
+        }
+    }
+
+    fn parse_page(&self, page: &Value) -> Result<(Vec<Record>, Option<String>)> {
+        let items = match page.pointer(&self.records_pointer) {
+            Some(Value::Array(items)) => items,
+            _ => {
+                return Err(PipelineError::Source(anyhow::anyhow!(
+                    "Response has no array at '{}'",
+                    self.records_pointer
+                )));
+            }
+        };
+
+        let records = items
+            .iter()
+            .map(|item| match item {
+                Value::Object(obj) => Ok(Record::with_data(obj.clone().into_iter().collect())),
+                _ => Err(PipelineError::Schema("Page item is not a JSON object".to_string())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let next = page
+            .pointer(&self.next_pointer)
+            .and_then(Value::as_str)
+            .filter(|next| !next.is_empty())
+            .map(str::to_string);
+
+        Ok((records, next))
+    }
+}
+
+struct PageState {
+    fetcher: PageFetcher,
+    next_url: Option<String>,
+    buffer: VecDeque<Record>,
+    finished: bool,
+}
+
+impl PageState {
+    async fn next_record(&mut self) -> Result<Option<Record>> {
+        loop {
+            if let Some(record) = self.buffer.pop_front() {
+                return Ok(Some(record));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+
+            let Some(url) = self.next_url.take() else {
+                self.fetcher.save_checkpoint(None).await?;
+                self.finished = true;
+                return Ok(None);
+            };
+
+            self.fetcher.save_checkpoint(Some(&url)).await?;
+            let page = self.fetcher.fetch(&url).await?;
+            let (records, next) = self.fetcher.parse_page(&page)?;
+            self.buffer.extend(records);
+            self.next_url = next;
+        }
+    }
+}
+
+#[async_trait]
+impl Source for HttpJsonSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let fetcher = PageFetcher {
+            client: self.client.clone(),
+            headers: self.headers.clone(),
+            records_pointer: self.records_pointer.clone(),
+            next_pointer: self.next_pointer.clone(),
+            checkpoint_path: None,
+            retry: self.retry.clone(),
+        };
+        let page = fetcher.fetch(&self.url).await?;
+        let (records, _) = fetcher.parse_page(&page)?;
+
+        let first = records
+            .into_iter()
+            .next()
+            .ok_or_else(|| PipelineError::Source(anyhow::anyhow!("First page has no records")))?;
+        let fields = first
+            .data
+            .into_keys()
+            .map(|name| Field {
+                name,
+                data_type: DataType::Json,
+                nullable: true,
+                description: None,
+            })
+            .collect();
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let next_url = self.start_url().await?;
+        let state = PageState {
+            fetcher: PageFetcher {
+                client: self.client.clone(),
+                headers: self.headers.clone(),
+                records_pointer: self.records_pointer.clone(),
+                next_pointer: self.next_pointer.clone(),
+                checkpoint_path: self.checkpoint_path.clone(),
+                retry: self.retry.clone(),
+            },
+            finished: next_url.is_none(),
+            next_url,
+            buffer: VecDeque::new(),
+        };
+
+        let stream = futures::stream::unfold(state, |mut state| async move {
+            match state.next_record().await {
+                Ok(Some(record)) => Some((Ok(record), state)),
+                Ok(None) => None,
+                Err(e) => {
+                    state.finished = true;
+                    state.buffer.clear();
+                    Some((Err(e), state))
+                }
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves `/page/<n>` for `n` in `0..pages`, two records per page, each
+    /// linking to the next. The first request for each path in `fail_once`
+    /// gets a 503. Returns the base URL and the number of requests per path.
+    async fn serve_pages(pages: usize, fail_once: &[&str]) -> (String, Arc<Mutex<HashMap<String, usize>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(Mutex::new(HashMap::new()));
+        let fail_once: Vec<String> = fail_once.iter().map(|path| path.to_string()).collect();
+
+        let (server_base, server_hits) = (base.clone(), hits.clone());
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut chunk = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut chunk).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&chunk[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let seen = {
+                    let mut hits = server_hits.lock().unwrap();
+                    let count = hits.entry(path.clone()).or_insert(0);
+                    *count += 1;
+                    *count
+                };
+
+                let page: Option<usize> = path.strip_prefix("/page/").and_then(|n| n.parse().ok());
+                let (status, body) = match page {
+                    _ if seen == 1 && fail_once.contains(&path) => ("503 Service Unavailable", String::new()),
+                    Some(n) if n < pages => {
+                        let next = (n + 1 < pages).then(|| format!("{}/page/{}", server_base, n + 1));
+                        let body = json!({
+                            "data": {"items": [{"page": n, "item": 0}, {"page": n, "item": 1}]},
+                            "next": next,
+                        });
+                        ("200 OK", body.to_string())
+                    }
+                    _ => ("404 Not Found", String::new()),
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (base, hits)
+    }
+
+    fn no_delay() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            jitter: false,
+        }
+    }
+
+    fn pages_of(records: &[Record]) -> Vec<(i64, i64)> {
+        records
+            .iter()
+            .map(|r| (r.get_field("page").and_then(Value::as_i64).unwrap(), r.get_field("item").and_then(Value::as_i64).unwrap()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn restart_resumes_without_repeating_completed_pages() {
+        let (base, hits) = serve_pages(3, &[]).await;
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint = dir.path().join("cursor.json");
+        let source = || {
+            HttpJsonSource::new(&format!("{}/page/0", base))
+                .with_records_pointer("/data/items")
+                .with_checkpoint(&checkpoint)
+                .with_retry(no_delay())
+        };
+
+        // Stop partway through the second page, as a crashed job would.
+        let first_run: Vec<Record> = source().read().await.unwrap().take(3).map(|r| r.unwrap()).collect().await;
+        assert_eq!(pages_of(&first_run), vec![(0, 0), (0, 1), (1, 0)]);
+
+        let second_run: Vec<Record> = source().read().await.unwrap().map(|r| r.unwrap()).collect().await;
+        assert_eq!(pages_of(&second_run), vec![(1, 0), (1, 1), (2, 0), (2, 1)]);
+        assert_eq!(hits.lock().unwrap().get("/page/0"), Some(&1));
+
+        // A finished run yields nothing until the checkpoint is removed.
+        assert_eq!(source().read().await.unwrap().count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_fetches_are_retried() {
+        let (base, hits) = serve_pages(2, &["/page/1"]).await;
+        let source = HttpJsonSource::new(&format!("{}/page/0", base))
+            .with_records_pointer("/data/items")
+            .with_retry(no_delay());
+
+        let records: Vec<Record> = source.read().await.unwrap().map(|r| r.unwrap()).collect().await;
+        assert_eq!(records.len(), 4);
+        assert_eq!(hits.lock().unwrap().get("/page/1"), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn pages_are_fetched_as_records_are_consumed() {
+        let (base, hits) = serve_pages(3, &[]).await;
+        let source = HttpSource::new(&format!("{}/page/0", base))
+            .with_records_pointer("/data/items")
+            .with_header("Authorization", "Bearer token");
+        let mut stream = source.read().await.unwrap();
+        assert!(hits.lock().unwrap().is_empty());
+
+        stream.next().await.unwrap().unwrap();
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(hits.lock().unwrap().len(), 1);
+
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(hits.lock().unwrap().get("/page/1"), Some(&1));
+        assert_eq!(stream.count().await, 3);
+        assert_eq!(hits.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn schema_keeps_document_order() {
+        let (base, _) = serve_pages(1, &[]).await;
+        let source = HttpJsonSource::new(&format!("{}/page/0", base)).with_records_pointer("/data/items");
+        assert_eq!(source.get_schema().await.unwrap().field_names(), vec!["page", "item"]);
+    }
+}
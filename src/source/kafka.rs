@@ -0,0 +1,147 @@
+use crate::core::{PipelineError, Record, RecordStream, Result, Schema, Source};
+use crate::source::file::{parse_json_line, schema_from_json_line};
+use async_trait::async_trait;
+use rdkafka::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{BorrowedMessage, Message};
+use std::time::Duration;
+
+// How long `get_schema` waits for a message to infer the schema from.
+const SCHEMA_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn source_error(e: KafkaError) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Kafka error: {}", e))
+}
+
+/// Parses a message's JSON payload, or returns `None` for a tombstone.
+fn message_payload<'a>(message: &'a BorrowedMessage<'_>) -> Result<Option<&'a str>> {
+    match message.payload_view::<str>() {
+        None => Ok(None),
+        Some(Ok(payload)) => Ok(Some(payload)),
+        Some(Err(e)) => Err(PipelineError::Source(anyhow::anyhow!(
+            "Message at {}/{} offset {} is not UTF-8: {}",
+            message.topic(),
+            message.partition(),
+            message.offset(),
+            e
+        ))),
+    }
+}
+
+fn message_to_record(message: &BorrowedMessage<'_>) -> Result<Option<Record>> {
+    let Some(payload) = message_payload(message)? else {
+        return Ok(None);
+    };
+    let mut record = parse_json_line(payload)?;
+    if let Some(key) = message.key() {
+        record.set_metadata("kafka_key".to_string(), String::from_utf8_lossy(key).into_owned());
+    }
+    record.set_metadata("kafka_topic".to_string(), message.topic().to_string());
+    record.set_metadata("kafka_partition".to_string(), message.partition().to_string());
+    record.set_metadata("kafka_offset".to_string(), message.offset().to_string());
+    Ok(Some(record))
+}
+
+/// Consumes JSON messages from a Kafka topic as part of a consumer group,
+/// one record per message, like [`JsonLinesSource`](crate::source::file::JsonLinesSource)
+/// does per line. The stream never ends.
+///
+/// The message key, topic, partition and offset are in the `kafka_key`,
+/// `kafka_topic`, `kafka_partition` and `kafka_offset` metadata. Messages
+/// without a payload (tombstones) are skipped. Offsets are committed by the
+/// consumer's auto-commit unless `with_config` turns it off.
+///
+/// Without an explicit schema, `get_schema` infers one from the next
+/// message in the group without committing it, so `read` still yields it.
+pub struct KafkaSource {
+    config: ClientConfig,
+    topic: String,
+    schema: Option<Schema>,
+}
+
+impl KafkaSource {
+    pub fn new(brokers: &str, group_id: &str, topic: &str) -> Self {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers)
+            .set("group.id", group_id)
+            .set("auto.offset.reset", "earliest");
+        Self {
+            config,
+            topic: topic.to_string(),
+            schema: None,
+        }
+    }
+
+    /// Sets a librdkafka consumer property, such as `security.protocol` or
+    /// `sasl.username`.
+    pub fn with_config(mut self, key: &str, value: &str) -> Self {
+        self.config.set(key, value);
+        self
+    }
+
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    fn subscribe(&self, config: &ClientConfig) -> Result<StreamConsumer> {
+        let consumer: StreamConsumer = config.create().map_err(source_error)?;
+        consumer.subscribe(&[&self.topic]).map_err(source_error)?;
+        Ok(consumer)
+    }
+
+    async fn infer_schema(&self) -> Result<Schema> {
+        let mut config = self.config.clone();
+        config.set("enable.auto.commit", "false");
+        let consumer = self.subscribe(&config)?;
+
+        let deadline = tokio::time::Instant::now() + SCHEMA_TIMEOUT;
+        loop {
+            let message = tokio::time::timeout_at(deadline, consumer.recv())
+                .await
+                .map_err(|_| {
+                    PipelineError::Source(anyhow::anyhow!(
+                        "No message arrived on topic '{}' within {:?} to infer the schema from",
+                        self.topic,
+                        SCHEMA_TIMEOUT
+                    ))
+                })?
+                .map_err(source_error)?;
+            if let Some(payload) = message_payload(&message)? {
+                return schema_from_json_line(payload);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Source for KafkaSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        match self.schema {
+            Some(ref schema) => Ok(schema.clone()),
+            None => self.infer_schema().await,
+        }
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let consumer = self.subscribe(&self.config)?;
+
+        let stream = futures::stream::unfold(consumer, |consumer| async move {
+            loop {
+                let record = match consumer.recv().await {
+                    Ok(message) => message_to_record(&message),
+                    Err(e) => Err(source_error(e)),
+                };
+                match record {
+                    Ok(Some(record)) => return Some((Ok(record), consumer)),
+                    Ok(None) => continue,
+                    Err(e) => return Some((Err(e), consumer)),
+                }
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,320 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source, SourceMode};
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use regex::Regex;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader, SeekFrom};
+
+const GROK_PATTERNS: &[(&str, &str)] = &[
+    ("TIMESTAMP_ISO8601", r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?"),
+    ("LOGLEVEL", r"(?i:trace|debug|info|notice|warn(?:ing)?|err(?:or)?|crit(?:ical)?|fatal|severe|emerg(?:ency)?)"),
+    ("IP", r"(?:\d{1,3}\.){3}\d{1,3}"),
+    ("NUMBER", r"[+-]?\d+(?:\.\d+)?"),
+    ("INT", r"[+-]?\d+"),
+    ("WORD", r"\w+"),
+    ("NOTSPACE", r"\S+"),
+    ("DATA", r".*?"),
+    ("GREEDYDATA", r".*"),
+];
+
+/// Translates a grok-style pattern into an anchored regex. Text outside
+/// `%{NAME:field}` is matched literally.
+fn grok_to_regex(pattern: &str) -> Result<String> {
+    let mut regex = String::from("^");
+    let mut rest = pattern;
+    while let Some(start) = rest.find("%{") {
+        regex.push_str(&regex::escape(&rest[..start]));
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| PipelineError::Config(format!("Unclosed '%{{' in grok pattern '{}'", pattern)))?;
+        let (name, field) = match rest[start + 2..end].split_once(':') {
+            Some((name, field)) => (name, Some(field)),
+            None => (&rest[start + 2..end], None),
+        };
+        let body = GROK_PATTERNS
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, body)| *body)
+            .ok_or_else(|| PipelineError::Config(format!("Unknown grok pattern '{}'", name)))?;
+        match field {
+            Some(field) => regex.push_str(&format!("(?P<{}>{})", field, body)),
+            None => regex.push_str(&format!("(?:{})", body)),
+        }
+        rest = &rest[end + 1..];
+    }
+    regex.push_str(&regex::escape(rest));
+    regex.push('$');
+    Ok(regex)
+}
+
+/// Reads a log file line by line, turning each line into a record of the
+/// pattern's named capture groups, typically `timestamp`, `level` and
+/// `message`. Lines that do not match are dead-lettered: they pass through
+/// with the raw line as `message` and the reason in `error` metadata.
+///
+/// In `SourceMode::Stream` the file is followed like `tail -F`: new lines
+/// are picked up every poll interval, and a truncated file is re-read from
+/// the start, as is a file renamed away and replaced by a new one.
+/// `SourceMode::Batch` stops at the end of the file.
+pub struct LogTailSource {
+    path: PathBuf,
+    pattern: Regex,
+    mode: SourceMode,
+    poll_interval: Duration,
+    start_at_end: bool,
+}
+
+impl LogTailSource {
+    /// Parses lines with a regex whose named groups become fields.
+    pub fn new<P: AsRef<Path>>(path: P, pattern: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| PipelineError::Config(format!("Invalid log pattern '{}': {}", pattern, e)))?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            pattern,
+            mode: SourceMode::Stream,
+            poll_interval: Duration::from_millis(500),
+            start_at_end: false,
+        })
+    }
+
+    /// Parses lines with a grok-style pattern such as
+    /// `%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}`.
+    /// Supported patterns are `TIMESTAMP_ISO8601`, `LOGLEVEL`, `IP`,
+    /// `NUMBER`, `INT`, `WORD`, `NOTSPACE`, `DATA` and `GREEDYDATA`.
+    pub fn with_grok<P: AsRef<Path>>(path: P, pattern: &str) -> Result<Self> {
+        Self::new(path, &grok_to_regex(pattern)?)
+    }
+
+    pub fn with_mode(mut self, mode: SourceMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Skips the lines already in the file when reading starts.
+    pub fn with_start_at_end(mut self, start_at_end: bool) -> Self {
+        self.start_at_end = start_at_end;
+        self
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+fn parse_line(pattern: &Regex, line: &str) -> Record {
+    let Some(captures) = pattern.captures(line) else {
+        let mut record = Record::new();
+        record.set_field("message".to_string(), Value::String(line.to_string()));
+        record.set_metadata("error".to_string(), "Log line does not match the pattern".to_string());
+        return record;
+    };
+
+    let data: IndexMap<String, Value> = pattern
+        .capture_names()
+        .flatten()
+        .map(|name| {
+            let value = captures
+                .name(name)
+                .map(|m| Value::String(m.as_str().to_string()))
+                .unwrap_or(Value::Null);
+            (name.to_string(), value)
+        })
+        .collect();
+    Record::with_data(data)
+}
+
+struct TailState {
+    path: PathBuf,
+    pattern: Regex,
+    mode: SourceMode,
+    poll_interval: Duration,
+    reader: BufReader<File>,
+    file_id: Option<(u64, u64)>,
+    position: u64,
+    partial: String,
+}
+
+impl TailState {
+    async fn open(path: &Path, start_at_end: bool) -> Result<(BufReader<File>, Option<(u64, u64)>, u64)> {
+        let mut file = File::open(path).await?;
+        let metadata = file.metadata().await?;
+        let position = if start_at_end { metadata.len() } else { 0 };
+        file.seek(SeekFrom::Start(position)).await?;
+        Ok((BufReader::new(file), file_id(&metadata), position))
+    }
+
+    /// Reopens the file from the start if it was truncated or replaced.
+    async fn check_rotation(&mut self) -> Result<()> {
+        let Ok(metadata) = tokio::fs::metadata(&self.path).await else {
+            // Mid-rotation the path may briefly not exist; keep the old handle.
+            return Ok(());
+        };
+        let replaced = file_id(&metadata).is_some() && file_id(&metadata) != self.file_id;
+        if replaced || metadata.len() < self.position {
+            tracing::info!("Log file {} was rotated, reading from the start", self.path.display());
+            let (reader, file_id, position) = Self::open(&self.path, false).await?;
+            self.reader = reader;
+            self.file_id = file_id;
+            self.position = position;
+            self.partial.clear();
+        }
+        Ok(())
+    }
+
+    async fn next_line(&mut self) -> Result<Option<String>> {
+        loop {
+            let mut chunk = String::new();
+            let read = self.reader.read_line(&mut chunk).await?;
+            self.position += read as u64;
+            self.partial.push_str(&chunk);
+
+            if self.partial.ends_with('\n') {
+                let line = std::mem::take(&mut self.partial);
+                return Ok(Some(line.trim_end_matches(['\n', '\r']).to_string()));
+            }
+            if read > 0 {
+                continue;
+            }
+
+            // At end of file; a trailing line without newline may still be
+            // being written, so it is only taken as complete in batch mode.
+            if self.mode == SourceMode::Batch {
+                if self.partial.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(std::mem::take(&mut self.partial)));
+            }
+            tokio::time::sleep(self.poll_interval).await;
+            self.check_rotation().await?;
+        }
+    }
+}
+
+#[async_trait]
+impl Source for LogTailSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let fields = self
+            .pattern
+            .capture_names()
+            .flatten()
+            .map(|name| Field {
+                name: name.to_string(),
+                data_type: DataType::String,
+                nullable: true,
+                description: None,
+            })
+            .collect();
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let (reader, file_id, position) = TailState::open(&self.path, self.start_at_end).await?;
+        let state = TailState {
+            path: self.path.clone(),
+            pattern: self.pattern.clone(),
+            mode: self.mode,
+            poll_interval: self.poll_interval,
+            reader,
+            file_id,
+            position,
+            partial: String::new(),
+        };
+
+        let stream = futures::stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_line().await {
+                Ok(Some(line)) => {
+                    let record = parse_line(&state.pattern, &line);
+                    Some((Ok(record), Some(state)))
+                }
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::io::Write;
+
+    const PATTERN: &str = "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{GREEDYDATA:message}";
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    async fn next(stream: &mut RecordStream) -> Record {
+        tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("no log line within 5s")
+            .unwrap()
+            .unwrap()
+    }
+
+    fn field<'a>(record: &'a Record, name: &str) -> &'a str {
+        record.get_field(name).and_then(Value::as_str).unwrap()
+    }
+
+    #[tokio::test]
+    async fn follows_lines_appended_to_the_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        append(file.path(), "2024-05-01T10:00:00Z INFO started\n");
+        let source = LogTailSource::with_grok(file.path(), PATTERN)
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10));
+        let mut stream = source.read().await.unwrap();
+
+        let first = next(&mut stream).await;
+        assert_eq!(field(&first, "timestamp"), "2024-05-01T10:00:00Z");
+        assert_eq!(field(&first, "level"), "INFO");
+        assert_eq!(field(&first, "message"), "started");
+
+        append(file.path(), "2024-05-01T10:00:01Z ERROR disk ");
+        append(file.path(), "full\nnot a log line\n");
+        let second = next(&mut stream).await;
+        assert_eq!(field(&second, "level"), "ERROR");
+        assert_eq!(field(&second, "message"), "disk full");
+        assert!(second.get_metadata("error").is_none());
+
+        let unparsed = next(&mut stream).await;
+        assert_eq!(field(&unparsed, "message"), "not a log line");
+        assert!(unparsed.get_metadata("error").is_some());
+    }
+
+    #[tokio::test]
+    async fn rereads_a_truncated_file_from_the_start() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        append(file.path(), "2024-05-01T10:00:00Z INFO a fairly long first line\n");
+        let source = LogTailSource::with_grok(file.path(), PATTERN)
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10));
+        let mut stream = source.read().await.unwrap();
+        next(&mut stream).await;
+
+        std::fs::write(file.path(), "2024-05-01T11:00:00Z WARN rotated\n").unwrap();
+        assert_eq!(field(&next(&mut stream).await, "message"), "rotated");
+    }
+}
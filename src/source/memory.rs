@@ -0,0 +1,40 @@
+use crate::core::{PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+
+/// Streams a fixed list of records, for tests and small in-process inputs.
+/// Every `read` streams the whole list again.
+///
+/// Without an explicit schema, `get_schema` infers one from all the records
+/// with [`Schema::infer_from_records`].
+pub struct VecSource {
+    records: Vec<Record>,
+    schema: Option<Schema>,
+}
+
+impl VecSource {
+    pub fn new(records: Vec<Record>) -> Self {
+        Self { records, schema: None }
+    }
+
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+}
+
+#[async_trait]
+impl Source for VecSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        if let Some(ref schema) = self.schema {
+            return Ok(schema.clone());
+        }
+        if self.records.is_empty() {
+            return Err(PipelineError::Schema("No records to infer the schema from".to_string()));
+        }
+        Ok(Schema::infer_from_records(&self.records))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        Ok(Box::pin(futures::stream::iter(self.records.clone().into_iter().map(Ok))))
+    }
+}
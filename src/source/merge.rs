@@ -0,0 +1,273 @@
+use crate::core::{timestamp_millis, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutOfOrderPolicy {
+    /// Emit the record as soon as it is read, breaking global ordering.
+    Emit,
+    /// Silently discard the record.
+    Drop,
+    /// Fail the stream.
+    Error,
+}
+
+/// K-way merges several sources, each already sorted by `timestamp_field`,
+/// into a single stream ordered by that field. Timestamps are compared as
+/// instants with [`timestamp_millis`], so RFC 3339 strings with different
+/// offsets and epoch-millisecond numbers interleave correctly; a timestamp
+/// that cannot be read fails the stream. A record whose timestamp is lower
+/// than the previous one from the same source is handled according to the
+/// configured [`OutOfOrderPolicy`].
+pub struct MergeSortedSource {
+    sources: Vec<Box<dyn Source>>,
+    timestamp_field: String,
+    policy: OutOfOrderPolicy,
+}
+
+impl MergeSortedSource {
+    pub fn new(sources: Vec<Box<dyn Source>>, timestamp_field: &str) -> Self {
+        Self {
+            sources,
+            timestamp_field: timestamp_field.to_string(),
+            policy: OutOfOrderPolicy::Emit,
+        }
+    }
+
+    pub fn with_out_of_order_policy(mut self, policy: OutOfOrderPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+struct MergeInput {
+    stream: RecordStream,
+    head: Option<(i64, Record)>,
+    /// The previous timestamp taken from this input, in epoch milliseconds
+    /// and as written.
+    last: Option<(i64, Value)>,
+    exhausted: bool,
+}
+
+struct MergeState {
+    inputs: Vec<MergeInput>,
+    timestamp_field: String,
+    policy: OutOfOrderPolicy,
+    failed: bool,
+}
+
+impl MergeState {
+    /// Refills the head of one input. Returns a record that must be emitted
+    /// immediately when it arrived out of order under `OutOfOrderPolicy::Emit`.
+    async fn fill(&mut self, index: usize) -> Result<Option<Record>> {
+        let input = &mut self.inputs[index];
+        while input.head.is_none() && !input.exhausted {
+            let Some(record) = input.stream.next().await else {
+                input.exhausted = true;
+                break;
+            };
+            let record = record?;
+            let timestamp = record.get_field(&self.timestamp_field).cloned().ok_or_else(|| {
+                PipelineError::Source(anyhow::anyhow!(
+                    "Record is missing timestamp field '{}'",
+                    self.timestamp_field
+                ))
+            })?;
+            let millis = timestamp_millis(&timestamp).ok_or_else(|| {
+                PipelineError::Source(anyhow::anyhow!(
+                    "Invalid timestamp {} in field '{}' of source {}",
+                    timestamp,
+                    self.timestamp_field,
+                    index
+                ))
+            })?;
+
+            let late = input.last.as_ref().is_some_and(|(last, _)| millis < *last);
+            if late {
+                match self.policy {
+                    OutOfOrderPolicy::Emit => return Ok(Some(record)),
+                    OutOfOrderPolicy::Drop => continue,
+                    OutOfOrderPolicy::Error => {
+                        return Err(PipelineError::Source(anyhow::anyhow!(
+                            "Out-of-order record in source {}: {} precedes {}",
+                            index,
+                            timestamp,
+                            input.last.as_ref().map_or(&Value::Null, |(_, last)| last)
+                        )));
+                    }
+                }
+            }
+
+            input.last = Some((millis, timestamp));
+            input.head = Some((millis, record));
+        }
+        Ok(None)
+    }
+
+    async fn next_record(&mut self) -> Result<Option<Record>> {
+        for index in 0..self.inputs.len() {
+            if let Some(record) = self.fill(index).await? {
+                return Ok(Some(record));
+            }
+        }
+
+        let next = self
+            .inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, input)| input.head.as_ref().map(|(millis, _)| (index, *millis)))
+            .min_by_key(|&(_, millis)| millis)
+            .map(|(index, _)| index);
+
+        Ok(next.and_then(|index| self.inputs[index].head.take().map(|(_, record)| record)))
+    }
+}
+
+#[async_trait]
+impl Source for MergeSortedSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let mut fields: Vec<Field> = Vec::new();
+        for source in &self.sources {
+            for field in source.get_schema().await?.fields {
+                if !fields.iter().any(|f| f.name == field.name) {
+                    fields.push(field);
+                }
+            }
+        }
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let mut inputs = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            inputs.push(MergeInput {
+                stream: source.read().await?,
+                head: None,
+                last: None,
+                exhausted: false,
+            });
+        }
+
+        let state = MergeState {
+            inputs,
+            timestamp_field: self.timestamp_field.clone(),
+            policy: self.policy,
+            failed: false,
+        };
+
+        let stream = futures::stream::unfold(state, |mut state| async move {
+            if state.failed {
+                return None;
+            }
+            match state.next_record().await {
+                Ok(Some(record)) => Some((Ok(record), state)),
+                Ok(None) => None,
+                Err(e) => {
+                    state.failed = true;
+                    Some((Err(e), state))
+                }
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn close(&self) -> Result<()> {
+        for source in &self.sources {
+            source.close().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::memory::VecSource;
+    use futures::TryStreamExt;
+    use serde_json::json;
+
+    fn source(events: &[(i64, &str)]) -> Box<dyn Source> {
+        let records = events
+            .iter()
+            .map(|(ts, name)| {
+                let mut record = Record::new();
+                record.set_field("ts".to_string(), json!(ts));
+                record.set_field("name".to_string(), json!(name));
+                record
+            })
+            .collect();
+        Box::new(VecSource::new(records))
+    }
+
+    async fn merged_names(merge: MergeSortedSource) -> Result<Vec<String>> {
+        let records: Vec<Record> = merge.read().await?.try_collect().await?;
+        Ok(records
+            .iter()
+            .map(|r| r.get_field("name").unwrap().as_str().unwrap().to_string())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn merges_sorted_sources_in_timestamp_order() {
+        let merge = MergeSortedSource::new(
+            vec![
+                source(&[(1, "a1"), (4, "a4"), (5, "a5")]),
+                source(&[(2, "b2"), (3, "b3"), (6, "b6")]),
+            ],
+            "ts",
+        );
+        assert_eq!(merged_names(merge).await.unwrap(), ["a1", "b2", "b3", "a4", "a5", "b6"]);
+    }
+
+    #[tokio::test]
+    async fn out_of_order_records_follow_the_policy() {
+        let sources = || vec![source(&[(1, "a1"), (3, "a3"), (2, "a2"), (4, "a4")]), source(&[(5, "b5")])];
+
+        let emitted = MergeSortedSource::new(sources(), "ts");
+        assert_eq!(merged_names(emitted).await.unwrap(), ["a1", "a3", "a2", "a4", "b5"]);
+
+        let dropped = MergeSortedSource::new(sources(), "ts").with_out_of_order_policy(OutOfOrderPolicy::Drop);
+        assert_eq!(merged_names(dropped).await.unwrap(), ["a1", "a3", "a4", "b5"]);
+
+        let failed = MergeSortedSource::new(sources(), "ts").with_out_of_order_policy(OutOfOrderPolicy::Error);
+        assert!(merged_names(failed).await.is_err());
+    }
+
+    fn timed(events: &[(Value, &str)]) -> Box<dyn Source> {
+        let records = events
+            .iter()
+            .map(|(ts, name)| {
+                let mut record = Record::new();
+                record.set_field("ts".to_string(), ts.clone());
+                record.set_field("name".to_string(), json!(name));
+                record
+            })
+            .collect();
+        Box::new(VecSource::new(records))
+    }
+
+    #[tokio::test]
+    async fn timestamps_are_compared_as_instants() {
+        let merge = MergeSortedSource::new(
+            vec![
+                // 08:00Z, then 09:30Z.
+                timed(&[(json!("2024-01-01T10:00:00+02:00"), "a1"), (json!("2024-01-01T09:30:00Z"), "a2")]),
+                timed(&[(json!("2024-01-01T09:00:00Z"), "b1"), (json!("2024-01-01T05:15:00-05:00"), "b2")]),
+                // 2024-01-01T09:10:00Z in epoch milliseconds.
+                timed(&[(json!(1_704_100_200_000i64), "c1")]),
+            ],
+            "ts",
+        )
+        .with_out_of_order_policy(OutOfOrderPolicy::Error);
+        assert_eq!(merged_names(merge).await.unwrap(), ["a1", "b1", "c1", "a2", "b2"]);
+    }
+
+    #[tokio::test]
+    async fn unreadable_timestamp_fails_the_stream() {
+        let merge = MergeSortedSource::new(vec![timed(&[(json!("yesterday"), "a1")])], "ts");
+        let error = merged_names(merge).await.unwrap_err();
+        assert!(matches!(error, PipelineError::Source(ref e) if e.to_string().contains("yesterday")), "{}", error);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::StreamExt;
+use mongodb::bson::{Bson, Document};
+use mongodb::{Client, Collection};
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+
+/// Converts BSON to JSON: ObjectIds become hex strings, dates RFC 3339
+/// strings, binary base64 strings and decimals strings, so no precision is
+/// lost. Remaining exotic types use relaxed extended JSON.
+fn bson_to_value(bson: Bson) -> Value {
+    match bson {
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::Boolean(b) => Value::Bool(b),
+        Bson::Int32(i) => Value::from(i),
+        Bson::Int64(i) => Value::from(i),
+        Bson::Double(f) => Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        Bson::String(s) | Bson::Symbol(s) => Value::String(s),
+        Bson::ObjectId(id) => Value::String(id.to_hex()),
+        Bson::DateTime(dt) => dt
+            .try_to_rfc3339_string()
+            .map(Value::String)
+            .unwrap_or_else(|_| Value::from(dt.timestamp_millis())),
+        Bson::Decimal128(d) => Value::String(d.to_string()),
+        Bson::Binary(binary) => Value::String(BASE64.encode(binary.bytes)),
+        Bson::Array(items) => Value::Array(items.into_iter().map(bson_to_value).collect()),
+        Bson::Document(doc) => Value::Object(doc.into_iter().map(|(k, v)| (k, bson_to_value(v))).collect()),
+        other => other.into_relaxed_extjson(),
+    }
+}
+
+fn bson_data_type(bson: &Bson) -> DataType {
+    match bson {
+        Bson::Boolean(_) => DataType::Boolean,
+        Bson::Int32(_) | Bson::Int64(_) => DataType::Integer,
+        Bson::Double(_) => DataType::Float,
+        Bson::String(_) | Bson::Symbol(_) | Bson::ObjectId(_) | Bson::Decimal128(_) => DataType::String,
+        Bson::DateTime(_) => DataType::Timestamp,
+        Bson::Binary(_) => DataType::Bytes,
+        _ => DataType::Json,
+    }
+}
+
+fn source_error(e: mongodb::error::Error) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("MongoDB error: {}", e))
+}
+
+/// Streams the documents matched by a `find` query as records.
+///
+/// Without an explicit schema, `get_schema` samples the first
+/// `sample_size` matching documents: a field seen with one BSON type gets
+/// the matching `DataType` and mixed types fall back to `Json`. Fields
+/// missing from, or null in, any sampled document are nullable.
+pub struct MongoSource {
+    uri: String,
+    database: String,
+    collection: String,
+    filter: Document,
+    projection: Option<Document>,
+    schema: Option<Schema>,
+    sample_size: i64,
+}
+
+impl MongoSource {
+    pub fn new(uri: &str, database: &str, collection: &str) -> Self {
+        Self {
+            uri: uri.to_string(),
+            database: database.to_string(),
+            collection: collection.to_string(),
+            filter: Document::new(),
+            projection: None,
+            schema: None,
+            sample_size: 100,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Document) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_projection(mut self, projection: Document) -> Self {
+        self.projection = Some(projection);
+        self
+    }
+
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    pub fn with_sample_size(mut self, sample_size: i64) -> Self {
+        self.sample_size = sample_size.max(1);
+        self
+    }
+
+    async fn collection(&self) -> Result<Collection<Document>> {
+        let client = Client::with_uri_str(&self.uri).await.map_err(source_error)?;
+        Ok(client.database(&self.database).collection(&self.collection))
+    }
+
+    async fn find(&self, limit: Option<i64>) -> Result<mongodb::Cursor<Document>> {
+        let collection = self.collection().await?;
+        let mut find = collection.find(self.filter.clone());
+        if let Some(ref projection) = self.projection {
+            find = find.projection(projection.clone());
+        }
+        if let Some(limit) = limit {
+            find = find.limit(limit);
+        }
+        find.await.map_err(source_error)
+    }
+}
+
+#[async_trait]
+impl Source for MongoSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        if let Some(ref schema) = self.schema {
+            return Ok(schema.clone());
+        }
+
+        let mut cursor = self.find(Some(self.sample_size)).await?;
+        // name -> (type, nullable, first seen order)
+        let mut fields: HashMap<String, (Option<DataType>, bool, usize)> = HashMap::new();
+        let mut sampled = 0;
+        while let Some(doc) = cursor.next().await {
+            let doc = doc.map_err(source_error)?;
+            for (name, value) in doc.iter() {
+                let order = fields.len();
+                let entry = fields.entry(name.clone()).or_insert((None, sampled > 0, order));
+                if matches!(value, Bson::Null | Bson::Undefined) {
+                    entry.1 = true;
+                    continue;
+                }
+                let data_type = bson_data_type(value);
+                entry.0 = match entry.0.take() {
+                    None => Some(data_type),
+                    Some(existing) if existing == data_type => Some(existing),
+                    Some(_) => Some(DataType::Json),
+                };
+            }
+            for (name, entry) in fields.iter_mut() {
+                if !doc.contains_key(name) {
+                    entry.1 = true;
+                }
+            }
+            sampled += 1;
+        }
+
+        if sampled == 0 {
+            return Err(PipelineError::Source(anyhow::anyhow!(
+                "Collection '{}' has no matching documents to infer a schema from",
+                self.collection
+            )));
+        }
+
+        let mut fields: Vec<(String, (Option<DataType>, bool, usize))> = fields.into_iter().collect();
+        fields.sort_by_key(|(_, (_, _, order))| *order);
+        Ok(Schema::new(
+            fields
+                .into_iter()
+                .map(|(name, (data_type, nullable, _))| Field {
+                    name,
+                    data_type: data_type.unwrap_or(DataType::Json),
+                    nullable,
+                    description: None,
+                })
+                .collect(),
+        ))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let cursor = self.find(None).await?;
+        let stream = cursor.map(|doc| {
+            let doc = doc.map_err(source_error)?;
+            let data = doc.into_iter().map(|(k, v)| (k, bson_to_value(v))).collect();
+            Ok(Record::with_data(data))
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::{doc, DateTime as BsonDateTime};
+    use serde_json::json;
+
+    #[test]
+    fn converts_bson_types_to_json() {
+        let id = ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap();
+        let document = doc! {
+            "_id": id,
+            "created": BsonDateTime::from_millis(1_704_067_200_000),
+            "count": 3i64,
+            "tags": ["a", "b"],
+            "nested": {"ok": true, "missing": Bson::Null},
+        };
+        assert_eq!(
+            bson_to_value(Bson::Document(document)),
+            json!({
+                "_id": "65a1b2c3d4e5f60718293a4b",
+                "created": "2024-01-01T00:00:00Z",
+                "count": 3,
+                "tags": ["a", "b"],
+                "nested": {"ok": true, "missing": null},
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a MongoDB server at MONGODB_URI"]
+    async fn reads_filtered_projected_documents_from_a_running_server() {
+        let uri = std::env::var("MONGODB_URI").unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
+        let name = format!("dpipeline_test_{}", std::process::id());
+        let collection: Collection<Document> =
+            Client::with_uri_str(&uri).await.unwrap().database(&name).collection("users");
+        collection
+            .insert_many([
+                doc! {"name": "ada", "age": 36, "joined": BsonDateTime::from_millis(0), "secret": "x"},
+                doc! {"name": "grace", "age": 85, "secret": "y"},
+                doc! {"name": "alan", "age": 41, "joined": Bson::Null, "secret": "z"},
+            ])
+            .await
+            .unwrap();
+
+        let source = MongoSource::new(&uri, &name, "users")
+            .with_filter(doc! {"age": {"$lt": 50}})
+            .with_projection(doc! {"_id": 0, "secret": 0});
+        let schema = source.get_schema().await.unwrap();
+        let records: Vec<Value> = source
+            .read()
+            .await
+            .unwrap()
+            .map(|r| Value::Object(r.unwrap().data.into_iter().collect()))
+            .collect()
+            .await;
+        collection.drop().await.unwrap();
+
+        assert_eq!(schema.field_names(), vec!["name", "age", "joined"]);
+        assert_eq!(schema.get_field("age").unwrap().data_type, DataType::Integer);
+        assert!(schema.get_field("joined").unwrap().nullable);
+        assert_eq!(
+            records,
+            vec![
+                json!({"name": "ada", "age": 36, "joined": "1970-01-01T00:00:00Z"}),
+                json!({"name": "alan", "age": 41, "joined": null}),
+            ]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::core::arrow::{records_from_batch, schema_from_arrow};
+use crate::core::{PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use parquet::arrow::async_reader::ParquetRecordBatchStreamBuilder;
+use parquet::arrow::ProjectionMask;
+use std::path::Path;
+use tokio::fs::File;
+
+fn source_error(e: parquet::errors::ParquetError) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Parquet error: {}", e))
+}
+
+/// Reads a Parquet file one row group at a time, yielding one record per
+/// row. Column types come from the Arrow schema embedded in (or derived
+/// from) the file: timestamps with a timezone map to `DataType::Timestamp`,
+/// those without to `DataType::DateTime` and dates to `DataType::Date`, all
+/// read as ISO 8601 strings. Lists map to `DataType::Array`, and structs and
+/// maps to `DataType::Json`.
+pub struct ParquetSource {
+    file_path: String,
+    columns: Option<Vec<String>>,
+    batch_size: usize,
+}
+
+impl ParquetSource {
+    pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            columns: None,
+            batch_size: 1024,
+        }
+    }
+
+    /// Reads only the named top-level columns.
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// Sets how many rows are decoded at a time.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn builder(&self) -> Result<ParquetRecordBatchStreamBuilder<File>> {
+        let file = File::open(&self.file_path).await?;
+        let builder = ParquetRecordBatchStreamBuilder::new(file).await.map_err(source_error)?;
+
+        let Some(ref columns) = self.columns else {
+            return Ok(builder);
+        };
+        let roots = columns
+            .iter()
+            .map(|column| {
+                builder.schema().index_of(column).map_err(|_| {
+                    PipelineError::Config(format!("Column '{}' not found in {}", column, self.file_path))
+                })
+            })
+            .collect::<Result<Vec<usize>>>()?;
+        let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+        Ok(builder.with_projection(mask))
+    }
+}
+
+#[async_trait]
+impl Source for ParquetSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let builder = self.builder().await?;
+        let schema = match self.columns {
+            Some(ref columns) => {
+                let indices: Vec<usize> = columns.iter().filter_map(|c| builder.schema().index_of(c).ok()).collect();
+                builder.schema().project(&indices).map_err(|e| source_error(e.into()))?
+            }
+            None => builder.schema().as_ref().clone(),
+        };
+        Ok(schema_from_arrow(&schema))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let batches = self
+            .builder()
+            .await?
+            .with_batch_size(self.batch_size)
+            .build()
+            .map_err(source_error)?;
+
+        let stream = batches.flat_map(|batch| {
+            let items: Vec<Result<Record>> = match batch.map_err(source_error).and_then(|b| records_from_batch(&b)) {
+                Ok(records) => records.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,250 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source, SourceMode};
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use redis::AsyncCommands;
+use redis::aio::MultiplexedConnection;
+use redis::streams::StreamReadOptions;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Consumes a Redis stream through a consumer group. Each entry becomes a
+/// record of string fields with the entry id in the `stream_id` metadata.
+///
+/// Entries are acknowledged once they have been handed downstream: a batch
+/// is acked when the next batch is requested, and the rest on `close`. On
+/// start the consumer's own unacknowledged entries are redelivered first, so
+/// delivery is at-least-once. In `SourceMode::Batch` reading stops once no
+/// new entry arrives within the block timeout; in `SourceMode::Stream` it
+/// never stops.
+pub struct RedisStreamSource {
+    client: redis::Client,
+    stream: String,
+    group: String,
+    consumer: String,
+    mode: SourceMode,
+    batch_size: usize,
+    block: Duration,
+    unacked: Arc<Mutex<Vec<String>>>,
+}
+
+impl RedisStreamSource {
+    pub fn new(url: &str, stream: &str, group: &str, consumer: &str) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| PipelineError::Config(format!("Invalid Redis URL '{}': {}", url, e)))?;
+        Ok(Self {
+            client,
+            stream: stream.to_string(),
+            group: group.to_string(),
+            consumer: consumer.to_string(),
+            mode: SourceMode::Stream,
+            batch_size: 100,
+            block: Duration::from_secs(1),
+            unacked: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    pub fn with_mode(mut self, mode: SourceMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// How long each read waits for new entries.
+    pub fn with_block(mut self, block: Duration) -> Self {
+        self.block = block;
+        self
+    }
+
+    async fn connection(&self) -> Result<MultiplexedConnection> {
+        self.client.get_multiplexed_async_connection().await.map_err(source_error)
+    }
+}
+
+fn source_error(e: redis::RedisError) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Redis error: {}", e))
+}
+
+/// A stream entry's id and fields, in the order they were added. The
+/// crate's own `StreamId` keeps fields in a `HashMap`, which loses it.
+type Entry = (String, Vec<(String, redis::Value)>);
+
+/// An `XREAD`/`XREADGROUP` reply: entries per stream.
+type ReadReply = Option<Vec<(String, Vec<Entry>)>>;
+
+fn entry_to_record((id, fields): Entry) -> Record {
+    let data: IndexMap<String, Value> = fields
+        .into_iter()
+        .map(|(name, value)| {
+            let value = redis::from_redis_value::<String>(value)
+                .map(Value::String)
+                .unwrap_or(Value::Null);
+            (name, value)
+        })
+        .collect();
+    let mut record = Record::with_data(data);
+    record.set_metadata("stream_id".to_string(), id);
+    record
+}
+
+async fn ack(
+    connection: &mut MultiplexedConnection,
+    stream: &str,
+    group: &str,
+    unacked: &Mutex<Vec<String>>,
+) -> Result<()> {
+    let ids = std::mem::take(&mut *unacked.lock().unwrap());
+    if !ids.is_empty() {
+        let _: usize = connection.xack(stream, group, &ids).await.map_err(source_error)?;
+    }
+    Ok(())
+}
+
+struct ReadState {
+    connection: MultiplexedConnection,
+    stream: String,
+    group: String,
+    options: StreamReadOptions,
+    mode: SourceMode,
+    /// `0` while redelivering this consumer's pending entries, then `>`.
+    next_id: &'static str,
+    buffer: VecDeque<Entry>,
+    unacked: Arc<Mutex<Vec<String>>>,
+}
+
+impl ReadState {
+    async fn next_entry(&mut self) -> Result<Option<Entry>> {
+        loop {
+            if let Some(entry) = self.buffer.pop_front() {
+                self.unacked.lock().unwrap().push(entry.0.clone());
+                return Ok(Some(entry));
+            }
+
+            ack(&mut self.connection, &self.stream, &self.group, &self.unacked).await?;
+
+            let reply: ReadReply = self
+                .connection
+                .xread_options(&[&self.stream], &[self.next_id], &self.options)
+                .await
+                .map_err(source_error)?;
+            let entries: Vec<Entry> = reply
+                .map(|reply| reply.into_iter().flat_map(|(_, entries)| entries).collect())
+                .unwrap_or_default();
+
+            if entries.is_empty() {
+                if self.next_id == "0" {
+                    self.next_id = ">";
+                    continue;
+                }
+                if self.mode == SourceMode::Batch {
+                    return Ok(None);
+                }
+            }
+            self.buffer.extend(entries);
+        }
+    }
+}
+
+#[async_trait]
+impl Source for RedisStreamSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let mut connection = self.connection().await?;
+        let reply: Vec<Entry> = connection
+            .xrange_count(&self.stream, "-", "+", 1)
+            .await
+            .map_err(source_error)?;
+        let (_, entry_fields) = reply
+            .into_iter()
+            .next()
+            .ok_or_else(|| PipelineError::Source(anyhow::anyhow!("Redis stream '{}' is empty", self.stream)))?;
+
+        let fields = entry_fields
+            .into_iter()
+            .map(|(name, _)| Field {
+                name,
+                data_type: DataType::String,
+                nullable: true,
+                description: None,
+            })
+            .collect();
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let mut connection = self.connection().await?;
+        let created: redis::RedisResult<()> = connection
+            .xgroup_create_mkstream(&self.stream, &self.group, "0")
+            .await;
+        if let Err(e) = created
+            && e.code() != Some("BUSYGROUP")
+        {
+            return Err(source_error(e));
+        }
+
+        let state = ReadState {
+            connection,
+            stream: self.stream.clone(),
+            group: self.group.clone(),
+            options: StreamReadOptions::default()
+                .group(&self.group, &self.consumer)
+                .count(self.batch_size)
+                .block(self.block.as_millis() as usize),
+            mode: self.mode,
+            next_id: "0",
+            buffer: VecDeque::new(),
+            unacked: self.unacked.clone(),
+        };
+
+        let stream = futures::stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_entry().await {
+                Ok(Some(entry)) => Some((Ok(entry_to_record(entry)), Some(state))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn close(&self) -> Result<()> {
+        if self.unacked.lock().unwrap().is_empty() {
+            return Ok(());
+        }
+        let mut connection = self.connection().await?;
+        ack(&mut connection, &self.stream, &self.group, &self.unacked).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::Value as RedisValue;
+
+    fn bulk(s: &str) -> RedisValue {
+        RedisValue::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn entries_keep_field_order() {
+        // XREADGROUP's RESP2 reply: [[stream, [[id, [field, value, ...]]]]]
+        let fields = ["zeta", "1", "alpha", "2", "mid", "3"].map(bulk).to_vec();
+        let reply = RedisValue::Array(vec![RedisValue::Array(vec![
+            bulk("events"),
+            RedisValue::Array(vec![RedisValue::Array(vec![bulk("1-0"), RedisValue::Array(fields)])]),
+        ])]);
+
+        let reply: ReadReply = redis::from_redis_value(reply).unwrap();
+        let (_, entries) = reply.unwrap().into_iter().next().unwrap();
+        let record = entry_to_record(entries.into_iter().next().unwrap());
+
+        assert_eq!(record.data.keys().collect::<Vec<_>>(), vec!["zeta", "alpha", "mid"]);
+        assert_eq!(record.get_field("alpha"), Some(&Value::String("2".to_string())));
+        assert_eq!(record.get_metadata("stream_id"), Some("1-0"));
+    }
+}
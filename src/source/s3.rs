@@ -0,0 +1,164 @@
+use crate::core::{decompress, Compression, DataType, DecompressedReader, Field, PipelineError, RecordStream, Result, Schema, Source};
+use crate::source::file::{parse_json_line, record_from_fields, schema_from_json_line};
+use async_trait::async_trait;
+use csv_async::AsyncReaderBuilder;
+use futures::stream::{StreamExt, TryStreamExt};
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, ObjectStoreExt};
+use std::sync::Arc;
+use tokio::io::AsyncBufReadExt;
+use tokio_stream::wrappers::LinesStream;
+use tokio_util::io::StreamReader;
+
+fn source_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("S3 error: {}", e))
+}
+
+fn csv_error(e: csv_async::Error) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Invalid CSV: {}", e))
+}
+
+/// How the records of an object are encoded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl Format {
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Reads records from one S3 object, parsed the way [`CsvSource`] and
+/// [`JsonLinesSource`] parse files. The body is streamed, so objects of any
+/// size are read without being held in memory, and a key ending in `.gz` is
+/// decompressed on the fly.
+///
+/// CSV objects must start with a header row; every column is read as a
+/// string.
+///
+/// [`CsvSource`]: crate::source::file::CsvSource
+/// [`JsonLinesSource`]: crate::source::file::JsonLinesSource
+pub struct S3Source {
+    store: Arc<dyn ObjectStore>,
+    key: String,
+    format: Format,
+    delimiter: u8,
+    compression: Compression,
+}
+
+impl S3Source {
+    /// Reads `key` from `bucket` with credentials and region taken from the
+    /// standard `AWS_*` environment variables.
+    pub fn new(bucket: &str, key: &str, format: Format) -> Result<Self> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(source_error)?;
+        Ok(Self::with_store(Arc::new(store), key, format))
+    }
+
+    /// Reads from an already configured store, e.g. an S3-compatible service
+    /// built with a custom endpoint.
+    pub fn with_store(store: Arc<dyn ObjectStore>, key: &str, format: Format) -> Self {
+        Self {
+            store,
+            key: key.to_string(),
+            format,
+            delimiter: b',',
+            compression: Compression::from_path(key),
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Overrides the compression inferred from the key's extension.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    async fn open(&self) -> Result<DecompressedReader> {
+        let body = self
+            .store
+            .get(&ObjectPath::from(self.key.as_str()))
+            .await
+            .map_err(source_error)?
+            .into_stream()
+            .map_err(std::io::Error::other);
+        Ok(decompress(StreamReader::new(body), self.compression))
+    }
+
+    fn csv_reader(&self, reader: DecompressedReader) -> csv_async::AsyncReader<DecompressedReader> {
+        AsyncReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .create_reader(reader)
+    }
+}
+
+#[async_trait]
+impl Source for S3Source {
+    async fn get_schema(&self) -> Result<Schema> {
+        // Only the start of the body is read; dropping the reader ends the
+        // download.
+        let reader = self.open().await?;
+        match self.format {
+            Format::Jsonl => match reader.lines().next_line().await? {
+                Some(first_line) => schema_from_json_line(&first_line),
+                None => Err(PipelineError::Source(anyhow::anyhow!("Empty JSON Lines object"))),
+            },
+            Format::Csv => match self.csv_reader(reader).into_records().next().await.transpose().map_err(csv_error)? {
+                Some(header) => Ok(Schema::new(
+                    header
+                        .iter()
+                        .map(|name| Field {
+                            name: name.trim().to_string(),
+                            data_type: DataType::String,
+                            nullable: true,
+                            description: None,
+                        })
+                        .collect(),
+                )),
+                None => Err(PipelineError::Source(anyhow::anyhow!("Empty CSV object"))),
+            },
+        }
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let reader = self.open().await?;
+        match self.format {
+            Format::Jsonl => {
+                let stream = LinesStream::new(reader.lines()).map(|line| match line {
+                    Ok(line) => parse_json_line(&line),
+                    Err(e) => Err(PipelineError::Io(e)),
+                });
+                Ok(Box::pin(stream))
+            }
+            Format::Csv => {
+                let mut rows = self.csv_reader(reader).into_records();
+                let field_names: Vec<String> = match rows.next().await.transpose().map_err(csv_error)? {
+                    Some(header) => header.iter().map(|name| name.trim().to_string()).collect(),
+                    None => return Ok(Box::pin(futures::stream::empty())),
+                };
+                let stream = rows.map(move |row| {
+                    let row = row.map_err(csv_error)?;
+                    Ok(record_from_fields(row.iter(), &field_names))
+                });
+                Ok(Box::pin(stream))
+            }
+        }
+    }
+}
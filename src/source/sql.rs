@@ -0,0 +1,156 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use futures::StreamExt;
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use sqlx::postgres::{PgConnection, PgRow};
+use sqlx::{Column, Connection, Executor, Row, TypeInfo};
+use tokio_stream::wrappers::ReceiverStream;
+
+// Rows buffered between the query task and the pipeline.
+const ROW_BUFFER: usize = 1024;
+
+fn source_error(e: sqlx::Error) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("PostgreSQL error: {}", e))
+}
+
+fn pg_data_type(type_name: &str) -> Option<DataType> {
+    match type_name {
+        "INT2" | "INT4" | "INT8" => Some(DataType::Integer),
+        "FLOAT4" | "FLOAT8" => Some(DataType::Float),
+        "BOOL" => Some(DataType::Boolean),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => Some(DataType::String),
+        "TIMESTAMPTZ" => Some(DataType::Timestamp),
+        "TIMESTAMP" => Some(DataType::DateTime),
+        "DATE" => Some(DataType::Date),
+        "JSON" | "JSONB" => Some(DataType::Json),
+        "BYTEA" => Some(DataType::Bytes),
+        _ => None,
+    }
+}
+
+fn unsupported_type(column: &str, type_name: &str) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!(
+        "Column '{}' has unsupported PostgreSQL type {}",
+        column,
+        type_name
+    ))
+}
+
+/// Converts column `index` of `row`: timestamps become RFC 3339 strings and
+/// `bytea` base64 strings, matching the other sources.
+fn column_value(row: &PgRow, index: usize) -> Result<Value> {
+    let column = row.column(index);
+    let type_name = column.type_info().name();
+    let value = match type_name {
+        "INT2" => row.try_get::<Option<i16>, _>(index).map(|v| v.map(Value::from)),
+        "INT4" => row.try_get::<Option<i32>, _>(index).map(|v| v.map(Value::from)),
+        "INT8" => row.try_get::<Option<i64>, _>(index).map(|v| v.map(Value::from)),
+        "FLOAT4" => row
+            .try_get::<Option<f32>, _>(index)
+            .map(|v| v.and_then(|f| Number::from_f64(f as f64)).map(Value::Number)),
+        "FLOAT8" => row
+            .try_get::<Option<f64>, _>(index)
+            .map(|v| v.and_then(Number::from_f64).map(Value::Number)),
+        "BOOL" => row.try_get::<Option<bool>, _>(index).map(|v| v.map(Value::Bool)),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => row.try_get::<Option<String>, _>(index).map(|v| v.map(Value::String)),
+        "TIMESTAMPTZ" => row
+            .try_get::<Option<DateTime<Utc>>, _>(index)
+            .map(|v| v.map(|dt| Value::String(dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)))),
+        "TIMESTAMP" => row
+            .try_get::<Option<NaiveDateTime>, _>(index)
+            .map(|v| v.map(|dt| Value::String(dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string()))),
+        "DATE" => row
+            .try_get::<Option<NaiveDate>, _>(index)
+            .map(|v| v.map(|d| Value::String(d.to_string()))),
+        "JSON" | "JSONB" => row.try_get::<Option<Value>, _>(index),
+        "BYTEA" => row
+            .try_get::<Option<Vec<u8>>, _>(index)
+            .map(|v| v.map(|bytes| Value::String(BASE64.encode(bytes)))),
+        _ => return Err(unsupported_type(column.name(), type_name)),
+    };
+    Ok(value.map_err(source_error)?.unwrap_or(Value::Null))
+}
+
+fn row_to_record(row: &PgRow) -> Result<Record> {
+    let mut data = IndexMap::with_capacity(row.len());
+    for (index, column) in row.columns().iter().enumerate() {
+        data.insert(column.name().to_string(), column_value(row, index)?);
+    }
+    Ok(Record::with_data(data))
+}
+
+/// Streams the rows returned by a SQL query as records.
+///
+/// Rows are fetched incrementally, so the result set is never held in
+/// memory. `get_schema` asks the server to describe the query without running
+/// it; columns PostgreSQL cannot prove are `NOT NULL`, such as computed
+/// expressions, are nullable.
+pub struct PostgresSource {
+    connection_string: String,
+    query: String,
+}
+
+impl PostgresSource {
+    pub fn new(connection_string: &str, query: &str) -> Self {
+        Self {
+            connection_string: connection_string.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    async fn connect(&self) -> Result<PgConnection> {
+        PgConnection::connect(&self.connection_string).await.map_err(source_error)
+    }
+}
+
+#[async_trait]
+impl Source for PostgresSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let mut conn = self.connect().await?;
+        let describe = conn.describe(&self.query).await.map_err(source_error)?;
+
+        let fields = describe
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let type_name = column.type_info().name();
+                let data_type = pg_data_type(type_name).ok_or_else(|| unsupported_type(column.name(), type_name))?;
+                Ok(Field {
+                    name: column.name().to_string(),
+                    data_type,
+                    nullable: describe.nullable(index).unwrap_or(true),
+                    description: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        conn.close().await.map_err(source_error)?;
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let mut conn = self.connect().await?;
+        let query = self.query.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(ROW_BUFFER);
+
+        // The row stream borrows the connection, so it is driven from its own
+        // task; dropping the record stream closes the channel and ends it.
+        tokio::spawn(async move {
+            let mut rows = sqlx::query(&query).fetch(&mut conn);
+            while let Some(row) = rows.next().await {
+                let record = row.map_err(source_error).and_then(|row| row_to_record(&row));
+                let failed = record.is_err();
+                if tx.send(record).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+}
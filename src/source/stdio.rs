@@ -0,0 +1,80 @@
+use crate::core::{PipelineError, RecordStream, Result, Schema, Source};
+use crate::source::file::{parse_json_line, schema_from_json_line};
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+use tokio::sync::Mutex;
+use tokio_stream::wrappers::LinesStream;
+
+struct StdinState {
+    lines: Lines<BufReader<Stdin>>,
+    // A line consumed by `get_schema`, replayed first by `read`.
+    peeked: Option<String>,
+}
+
+/// Reads newline-delimited JSON objects from standard input. Blank lines
+/// are skipped.
+///
+/// Stdin can only be consumed once, so `read` may be called a single time;
+/// the line `get_schema` inspects is still yielded as the first record.
+pub struct StdinSource {
+    state: Mutex<Option<StdinState>>,
+}
+
+impl StdinSource {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(Some(StdinState {
+                lines: BufReader::new(tokio::io::stdin()).lines(),
+                peeked: None,
+            })),
+        }
+    }
+}
+
+impl Default for StdinSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn already_read() -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Stdin has already been read"))
+}
+
+#[async_trait]
+impl Source for StdinSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let mut guard = self.state.lock().await;
+        let state = guard.as_mut().ok_or_else(already_read)?;
+
+        if state.peeked.is_none() {
+            while let Some(line) = state.lines.next_line().await? {
+                if !line.trim().is_empty() {
+                    state.peeked = Some(line);
+                    break;
+                }
+            }
+        }
+
+        match state.peeked {
+            Some(ref line) => schema_from_json_line(line),
+            None => Err(PipelineError::Source(anyhow::anyhow!("Stdin is empty"))),
+        }
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let state = self.state.lock().await.take().ok_or_else(already_read)?;
+
+        let peeked = futures::stream::iter(state.peeked.map(Ok));
+        let stream = peeked.chain(LinesStream::new(state.lines)).filter_map(|line_result| async move {
+            match line_result {
+                Ok(line) if line.trim().is_empty() => None,
+                Ok(line) => Some(parse_json_line(&line)),
+                Err(e) => Some(Err(PipelineError::Io(e))),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
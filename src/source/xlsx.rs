@@ -0,0 +1,252 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use calamine::{open_workbook_auto, Data, Range, Reader};
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use std::path::Path;
+
+enum SheetSelection {
+    First,
+    Named(String),
+    All,
+}
+
+/// Reads rows from Excel/ODS workbooks. The first row of every sheet is its
+/// header. With [`XlsxSource::all_sheets`] every sheet is streamed in
+/// workbook order and each record carries its sheet name in a `__sheet`
+/// field and `sheet` metadata.
+pub struct XlsxSource {
+    file_path: String,
+    sheets: SheetSelection,
+}
+
+impl XlsxSource {
+    pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            sheets: SheetSelection::First,
+        }
+    }
+
+    pub fn with_sheet(mut self, sheet_name: &str) -> Self {
+        self.sheets = SheetSelection::Named(sheet_name.to_string());
+        self
+    }
+
+    pub fn all_sheets(mut self) -> Self {
+        self.sheets = SheetSelection::All;
+        self
+    }
+
+    async fn load_sheets(&self) -> Result<Vec<(String, Range<Data>)>> {
+        let file_path = self.file_path.clone();
+        let selected = match self.sheets {
+            SheetSelection::First => None,
+            SheetSelection::Named(ref name) => Some(vec![name.clone()]),
+            SheetSelection::All => Some(Vec::new()),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let mut workbook = open_workbook_auto(&file_path).map_err(xlsx_error)?;
+            let names = match selected {
+                None => workbook.sheet_names().into_iter().take(1).collect(),
+                Some(names) if names.is_empty() => workbook.sheet_names(),
+                Some(names) => names,
+            };
+
+            names
+                .into_iter()
+                .map(|name| {
+                    let range = workbook.worksheet_range(&name).map_err(xlsx_error)?;
+                    Ok((name, range))
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| PipelineError::Source(anyhow::anyhow!("Workbook loading task failed: {}", e)))?
+    }
+
+    fn tag_sheets(&self) -> bool {
+        matches!(self.sheets, SheetSelection::All)
+    }
+}
+
+fn xlsx_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Failed to read workbook: {}", e))
+}
+
+fn sheet_headers(range: &Range<Data>) -> Vec<String> {
+    range
+        .rows()
+        .next()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| match cell.to_string().trim() {
+                    "" => format!("column_{}", i),
+                    name => name.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn cell_to_value(cell: &Data) -> Value {
+    match cell {
+        Data::Int(i) => Value::from(*i),
+        Data::Float(f) => Number::from_f64(*f).map(Value::Number).unwrap_or(Value::Null),
+        Data::String(s) => Value::String(s.clone()),
+        Data::Bool(b) => Value::Bool(*b),
+        Data::DateTime(dt) => dt
+            .as_datetime()
+            .map(|dt| Value::String(dt.to_string().replacen(' ', "T", 1)))
+            .unwrap_or_else(|| Value::String(dt.to_string())),
+        Data::DateTimeIso(s) | Data::DurationIso(s) => Value::String(s.clone()),
+        Data::Error(_) | Data::Empty => Value::Null,
+    }
+}
+
+fn cell_data_type(cell: &Data) -> DataType {
+    match cell {
+        Data::Int(_) => DataType::Integer,
+        Data::Float(_) => DataType::Float,
+        Data::Bool(_) => DataType::Boolean,
+        Data::DateTime(_) | Data::DateTimeIso(_) => DataType::DateTime,
+        _ => DataType::String,
+    }
+}
+
+#[async_trait]
+impl Source for XlsxSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let sheets = self.load_sheets().await?;
+        let mut fields: Vec<Field> = Vec::new();
+
+        if self.tag_sheets() {
+            fields.push(Field {
+                name: "__sheet".to_string(),
+                data_type: DataType::String,
+                nullable: false,
+                description: None,
+            });
+        }
+
+        for (_, range) in &sheets {
+            let first_row = range.rows().nth(1);
+            for (i, name) in sheet_headers(range).into_iter().enumerate() {
+                if fields.iter().any(|f| f.name == name) {
+                    continue;
+                }
+                let data_type = first_row
+                    .and_then(|row| row.get(i))
+                    .map(cell_data_type)
+                    .unwrap_or(DataType::String);
+                fields.push(Field {
+                    name,
+                    data_type,
+                    nullable: true,
+                    description: None,
+                });
+            }
+        }
+
+        if fields.is_empty() {
+            return Err(PipelineError::Source(anyhow::anyhow!("Empty workbook")));
+        }
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let sheets = self.load_sheets().await?;
+        let tag_sheets = self.tag_sheets();
+        let mut records = Vec::new();
+
+        for (sheet_name, range) in &sheets {
+            let headers = sheet_headers(range);
+            for row in range.rows().skip(1) {
+                let mut data = IndexMap::new();
+                for (header, cell) in headers.iter().zip(row.iter()) {
+                    data.insert(header.clone(), cell_to_value(cell));
+                }
+
+                let mut record = Record::with_data(data);
+                if tag_sheets {
+                    record.set_field("__sheet".to_string(), Value::String(sheet_name.clone()));
+                    record.set_metadata("sheet".to_string(), sheet_name.clone());
+                }
+                records.push(Ok(record));
+            }
+        }
+
+        Ok(Box::pin(futures::stream::iter(records)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use rust_xlsxwriter::Workbook;
+    use serde_json::json;
+
+    /// Writes a workbook with a `customers` and an `orders` sheet, each with
+    /// its own header row.
+    fn write_workbook(path: &Path) {
+        let mut workbook = Workbook::new();
+        let customers = workbook.add_worksheet().set_name("customers").unwrap();
+        customers.write(0, 0, "id").unwrap();
+        customers.write(0, 1, "name").unwrap();
+        customers.write(1, 0, 1).unwrap();
+        customers.write(1, 1, "alice").unwrap();
+        customers.write(2, 0, 2).unwrap();
+        customers.write(2, 1, "bob").unwrap();
+
+        let orders = workbook.add_worksheet().set_name("orders").unwrap();
+        orders.write(0, 0, "order_id").unwrap();
+        orders.write(0, 1, "paid").unwrap();
+        orders.write(1, 0, 10.5).unwrap();
+        orders.write(1, 1, true).unwrap();
+        workbook.save(path).unwrap();
+    }
+
+    async fn read_all(source: &XlsxSource) -> Vec<Record> {
+        source.read().await.unwrap().try_collect().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn all_sheets_tags_records_with_their_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.xlsx");
+        write_workbook(&path);
+        let source = XlsxSource::new(&path).all_sheets();
+
+        let records = read_all(&source).await;
+        assert_eq!(records.len(), 3);
+        let sheets: Vec<Option<&str>> = records.iter().map(|r| r.get_metadata("sheet")).collect();
+        assert_eq!(sheets, [Some("customers"), Some("customers"), Some("orders")]);
+        assert_eq!(records[1].get_field("__sheet"), Some(&json!("customers")));
+        assert_eq!(records[1].get_field("name"), Some(&json!("bob")));
+        assert_eq!(records[2].get_field("__sheet"), Some(&json!("orders")));
+        assert_eq!(records[2].get_field("order_id"), Some(&json!(10.5)));
+        assert_eq!(records[2].get_field("paid"), Some(&json!(true)));
+
+        let schema = source.get_schema().await.unwrap();
+        assert_eq!(schema.field_names(), ["__sheet", "id", "name", "order_id", "paid"]);
+    }
+
+    #[tokio::test]
+    async fn default_and_named_sheets_are_untagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.xlsx");
+        write_workbook(&path);
+
+        let first = read_all(&XlsxSource::new(&path)).await;
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].get_field("__sheet"), None);
+        assert!(first[0].metadata.is_empty());
+
+        let orders = read_all(&XlsxSource::new(&path).with_sheet("orders")).await;
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].get_field("paid"), Some(&json!(true)));
+    }
+}
@@ -0,0 +1,363 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Source};
+use async_trait::async_trait;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::BufReader;
+
+// Key for the text of an element that also has attributes or children.
+const TEXT_KEY: &str = "#text";
+
+fn xml_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Source(anyhow::anyhow!("Invalid XML: {}", e))
+}
+
+fn local_name(element: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+/// Adds `value` under `name`, collecting repeated names into an array.
+fn insert_value(fields: &mut Map<String, Value>, name: String, value: Value) {
+    match fields.get_mut(&name) {
+        None => {
+            fields.insert(name, value);
+        }
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+    }
+}
+
+fn resolve_reference(reference: &BytesRef<'_>) -> Result<String> {
+    if let Some(c) = reference.resolve_char_ref().map_err(xml_error)? {
+        return Ok(c.to_string());
+    }
+    let name = reference.decode().map_err(xml_error)?;
+    resolve_predefined_entity(&name)
+        .map(str::to_string)
+        .ok_or_else(|| xml_error(format!("unknown entity '&{};'", name)))
+}
+
+/// An element being built while its content is read.
+struct Node {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+impl Node {
+    fn start(element: &BytesStart<'_>) -> Result<Self> {
+        let mut fields = Map::new();
+        for attribute in element.attributes() {
+            let attribute = attribute.map_err(xml_error)?;
+            if attribute.key.as_ref().starts_with(b"xmlns") {
+                continue;
+            }
+            let name = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+            let value = attribute.normalized_value(XmlVersion::Implicit1_0).map_err(xml_error)?;
+            insert_value(&mut fields, name, Value::String(value.into_owned()));
+        }
+        Ok(Self {
+            name: local_name(element),
+            fields,
+            text: String::new(),
+        })
+    }
+
+    /// A leaf element becomes its trimmed text, or null when self-closing;
+    /// any other element an object of its attributes and children.
+    fn into_value(mut self, self_closing: bool) -> Value {
+        let text = self.text.trim();
+        if self.fields.is_empty() {
+            return match (self_closing, text.is_empty()) {
+                (true, _) => Value::Null,
+                (false, true) => Value::String(String::new()),
+                (false, false) => Value::String(text.to_string()),
+            };
+        }
+        if !text.is_empty() {
+            self.fields.insert(TEXT_KEY.to_string(), Value::String(text.to_string()));
+        }
+        Value::Object(self.fields)
+    }
+
+    fn into_record(self, self_closing: bool) -> Record {
+        let data = match self.into_value(self_closing) {
+            Value::Object(fields) => fields.into_iter().collect(),
+            Value::String(text) if !text.is_empty() => [(TEXT_KEY.to_string(), Value::String(text))].into_iter().collect(),
+            _ => Default::default(),
+        };
+        Record::with_data(data)
+    }
+}
+
+struct RecordMatcher {
+    record_element: String,
+    record_path: Option<Vec<String>>,
+    /// Names of the open elements enclosing the current position.
+    path: Vec<String>,
+}
+
+impl RecordMatcher {
+    fn is_record(&self, name: &str) -> bool {
+        name == self.record_element && self.record_path.as_ref().is_none_or(|path| *path == self.path)
+    }
+}
+
+struct XmlState {
+    reader: Reader<BufReader<File>>,
+    buf: Vec<u8>,
+    matcher: RecordMatcher,
+}
+
+impl XmlState {
+    async fn next_record(&mut self) -> Result<Option<Record>> {
+        // The record being read and its open descendants, outermost first.
+        let mut nodes: Vec<Node> = Vec::new();
+        loop {
+            self.buf.clear();
+            let event = self.reader.read_event_into_async(&mut self.buf).await.map_err(xml_error)?;
+            match event {
+                Event::Start(element) => {
+                    if nodes.is_empty() && !self.matcher.is_record(&local_name(&element)) {
+                        self.matcher.path.push(local_name(&element));
+                        continue;
+                    }
+                    nodes.push(Node::start(&element)?);
+                }
+                Event::Empty(element) => match nodes.last_mut() {
+                    Some(parent) => {
+                        let node = Node::start(&element)?;
+                        insert_value(&mut parent.fields, node.name.clone(), node.into_value(true));
+                    }
+                    None if self.matcher.is_record(&local_name(&element)) => {
+                        return Ok(Some(Node::start(&element)?.into_record(true)));
+                    }
+                    None => {}
+                },
+                Event::End(_) => {
+                    let Some(node) = nodes.pop() else {
+                        self.matcher.path.pop();
+                        continue;
+                    };
+                    match nodes.last_mut() {
+                        Some(parent) => insert_value(&mut parent.fields, node.name.clone(), node.into_value(false)),
+                        None => return Ok(Some(node.into_record(false))),
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(node) = nodes.last_mut() {
+                        node.text.push_str(&text.xml10_content().map_err(xml_error)?);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(node) = nodes.last_mut() {
+                        node.text.push_str(&data.decode().map_err(xml_error)?);
+                    }
+                }
+                Event::GeneralRef(reference) => {
+                    if let Some(node) = nodes.last_mut() {
+                        node.text.push_str(&resolve_reference(&reference)?);
+                    }
+                }
+                Event::Eof => {
+                    return match nodes.first() {
+                        Some(node) => Err(xml_error(format!("document ends inside <{}>", node.name))),
+                        None => Ok(None),
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Streams one record per `record_element` element of an XML document,
+/// reading the file incrementally so only the current element is held in
+/// memory.
+///
+/// Attributes and child elements become fields, by local name without a
+/// namespace prefix. A child with only text becomes a string, and one with
+/// attributes or children of its own an object; repeated children are
+/// collected into an array, and text next to attributes or children is kept
+/// under `#text`. Values are not typed, so numbers stay strings.
+///
+/// Records are matched at any depth unless `with_record_path` names the
+/// container they must sit directly under.
+pub struct XmlSource {
+    file_path: String,
+    record_element: String,
+    record_path: Option<Vec<String>>,
+}
+
+impl XmlSource {
+    pub fn new<P: AsRef<Path>>(file_path: P, record_element: &str) -> Self {
+        Self {
+            file_path: file_path.as_ref().to_string_lossy().into_owned(),
+            record_element: record_element.to_string(),
+            record_path: None,
+        }
+    }
+
+    /// Only matches records directly under this `/`-separated path of
+    /// element names from the root, such as `export/items`.
+    pub fn with_record_path(mut self, path: &str) -> Self {
+        self.record_path = Some(
+            path.split('/')
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+        );
+        self
+    }
+
+    async fn open(&self) -> Result<XmlState> {
+        let file = File::open(&self.file_path).await?;
+        Ok(XmlState {
+            reader: Reader::from_reader(BufReader::new(file)),
+            buf: Vec::new(),
+            matcher: RecordMatcher {
+                record_element: self.record_element.clone(),
+                record_path: self.record_path.clone(),
+                path: Vec::new(),
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl Source for XmlSource {
+    async fn get_schema(&self) -> Result<Schema> {
+        let fields = match self.open().await?.next_record().await? {
+            Some(record) => record
+                .data
+                .iter()
+                .map(|(name, value)| Field {
+                    name: name.clone(),
+                    data_type: if value.is_string() { DataType::String } else { DataType::Json },
+                    nullable: true,
+                    description: None,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(Schema::new(fields))
+    }
+
+    async fn read(&self) -> Result<RecordStream> {
+        let state = self.open().await?;
+
+        let stream = futures::stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_record().await {
+                Ok(Some(record)) => Some((Ok(record), Some(state))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use serde_json::json;
+
+    async fn read_xml(contents: &str, source: impl FnOnce(&Path) -> XmlSource) -> Result<Vec<Value>> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.xml");
+        std::fs::write(&path, contents).unwrap();
+        let records: Vec<Record> = source(&path).read().await?.try_collect().await?;
+        Ok(records
+            .into_iter()
+            .map(|record| Value::Object(record.data.into_iter().collect()))
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn attributes_and_children_become_fields() {
+        let contents = r#"<?xml version="1.0"?>
+<items>
+  <item id="1" status="new">
+    <name>
+      Alice
+    </name>
+    <tag>a</tag>
+    <tag>b</tag>
+    <price currency="EUR">9.50</price>
+    <note/>
+    <empty></empty>
+  </item>
+  <item id="2"/>
+</items>"#;
+
+        let records = read_xml(contents, |path| XmlSource::new(path, "item")).await.unwrap();
+        assert_eq!(
+            records,
+            [
+                json!({
+                    "id": "1",
+                    "status": "new",
+                    "name": "Alice",
+                    "tag": ["a", "b"],
+                    "price": {"currency": "EUR", "#text": "9.50"},
+                    "note": null,
+                    "empty": "",
+                }),
+                json!({"id": "2"}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn namespace_prefixes_and_declarations_are_dropped() {
+        let contents = r#"<feed xmlns="urn:feed" xmlns:x="urn:x">
+  <x:entry x:lang="en"><x:title>Hello</x:title></x:entry>
+</feed>"#;
+
+        let records = read_xml(contents, |path| XmlSource::new(path, "entry")).await.unwrap();
+        assert_eq!(records, [json!({"lang": "en", "title": "Hello"})]);
+    }
+
+    #[tokio::test]
+    async fn cdata_and_entity_references_are_decoded() {
+        let contents = r#"<rows>
+  <row><text><![CDATA[<b>bold</b> & more]]></text><escaped>fish &amp; chips &#x263A; &lt;3</escaped></row>
+</rows>"#;
+
+        let records = read_xml(contents, |path| XmlSource::new(path, "row")).await.unwrap();
+        assert_eq!(records, [json!({"text": "<b>bold</b> & more", "escaped": "fish & chips \u{263A} <3"})]);
+    }
+
+    #[tokio::test]
+    async fn record_path_only_matches_records_under_that_container() {
+        let contents = r#"<export>
+  <items><item><id>1</id></item><item><id>2</id></item></items>
+  <archive><items><item><id>3</id></item></items></archive>
+  <items><group><item><id>4</id></item></group></items>
+</export>"#;
+
+        let anywhere = read_xml(contents, |path| XmlSource::new(path, "item")).await.unwrap();
+        assert_eq!(anywhere, [json!({"id": "1"}), json!({"id": "2"}), json!({"id": "3"}), json!({"id": "4"})]);
+
+        let under_path =
+            read_xml(contents, |path| XmlSource::new(path, "item").with_record_path("/export/items/")).await.unwrap();
+        assert_eq!(under_path, [json!({"id": "1"}), json!({"id": "2"})]);
+    }
+
+    #[tokio::test]
+    async fn unterminated_document_is_an_error() {
+        let contents = "<items><item><name>Alice</name>";
+
+        let error = read_xml(contents, |path| XmlSource::new(path, "item")).await.unwrap_err();
+        assert!(matches!(error, PipelineError::Source(ref e) if e.to_string().contains("<item>")), "{}", error);
+    }
+}
@@ -0,0 +1,101 @@
+//! Helpers shared by unit tests: record fixtures, and a mock server for
+//! HTTP-based sources, transforms and sinks.
+
+use crate::core::Record;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A record with the fields of `fields`, which must be a JSON object, in
+/// order.
+pub(crate) fn record(fields: Value) -> Record {
+    match fields {
+        Value::Object(map) => Record::with_data(map.into_iter().collect()),
+        other => panic!("Test records are built from JSON objects, not {}", other),
+    }
+}
+
+/// A request received by [`serve`].
+#[derive(Debug, Clone)]
+pub(crate) struct MockRequest {
+    pub method: String,
+    /// The path and query string.
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+/// Serves HTTP/1.1 on a local port, answering each request with the status
+/// and JSON body `handler` returns. Returns the base URL and every request
+/// received so far, in order.
+pub(crate) async fn serve<F>(handler: F) -> (String, Arc<Mutex<Vec<MockRequest>>>)
+where
+    F: Fn(&MockRequest) -> (u16, String) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let received = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let Some(request) = read_request(&mut socket).await else {
+                continue;
+            };
+            let (status, body) = handler(&request);
+            received.lock().unwrap().push(request);
+
+            let response = format!(
+                "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    (base, requests)
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> Option<MockRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).into_owned();
+    let mut request_line = head.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = buffer.split_off(header_end);
+    while body.len() < content_length {
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => body.extend_from_slice(&chunk[..n]),
+        }
+    }
+    Some(MockRequest { method, path, body })
+}
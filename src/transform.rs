@@ -0,0 +1,43 @@
+#[cfg(feature = "libpostal")]
+pub mod address;
+pub mod aggregate;
+pub mod blank;
+pub mod branch;
+pub mod cast;
+pub mod completeness;
+pub mod dedupe;
+pub mod drift;
+pub mod encrypt;
+pub mod enforce;
+pub mod explode;
+pub mod filter;
+pub mod flatten;
+pub mod flexible_date;
+pub mod fpe;
+pub mod geohash;
+#[cfg(feature = "http")]
+pub mod http_enrich;
+#[cfg(feature = "jq")]
+pub mod jq;
+pub mod limit;
+pub mod lookup;
+pub mod map;
+pub mod moving_avg;
+pub mod period;
+pub mod rename;
+pub mod reorder;
+#[cfg(feature = "rrule")]
+pub mod rrule;
+pub mod rules;
+pub mod scale;
+pub mod select;
+pub mod sessionize;
+pub mod shard;
+pub mod sort;
+pub mod split;
+pub mod typed;
+pub mod url;
+pub mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod windowed_dedup;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use postal::{Context, InitOptions, ParseAddressOptions};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+const COMPONENT_FIELDS: [&str; 4] = ["street", "city", "postcode", "country"];
+
+// libpostal is not thread-safe to initialise, so a single context is shared
+// by every transform in the process.
+static CONTEXT: OnceLock<std::result::Result<Context, String>> = OnceLock::new();
+
+fn context() -> Result<&'static Context> {
+    CONTEXT
+        .get_or_init(|| {
+            let mut ctx = Context::new();
+            ctx.init(InitOptions {
+                expand_address: false,
+                parse_address: true,
+            })
+            .map(|_| ctx)
+            .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| PipelineError::Config(format!("Failed to initialise libpostal: {}", e)))
+}
+
+/// Splits a freeform address field into `street`, `city`, `postcode` and
+/// `country` fields using libpostal. Records whose address yields neither a
+/// street nor a city get `address_parsed=false` in their metadata.
+pub struct AddressParseTransform {
+    source_field: String,
+}
+
+impl AddressParseTransform {
+    pub fn new(source_field: &str) -> Result<Self> {
+        context()?;
+        Ok(Self {
+            source_field: source_field.to_string(),
+        })
+    }
+
+    fn parse(&self, address: &str) -> Result<[Option<String>; 4]> {
+        let mut opts = ParseAddressOptions::new();
+        let components = context()?
+            .parse_address(address, &mut opts)
+            .map_err(|e| PipelineError::Transform(format!("Address parsing failed: {}", e)))?;
+
+        let mut house_number = None;
+        let mut road = None;
+        let mut parsed: [Option<String>; 4] = Default::default();
+        for component in components {
+            match component.label {
+                "house_number" => house_number = Some(component.value.to_string()),
+                "road" => road = Some(component.value.to_string()),
+                "city" => parsed[1] = Some(component.value.to_string()),
+                "postcode" => parsed[2] = Some(component.value.to_string()),
+                "country" => parsed[3] = Some(component.value.to_string()),
+                _ => {}
+            }
+        }
+
+        parsed[0] = match (house_number, road) {
+            (Some(number), Some(road)) => Some(format!("{} {}", number, road)),
+            (None, road) => road,
+            (number, None) => number,
+        };
+        Ok(parsed)
+    }
+}
+
+#[async_trait]
+impl Transform for AddressParseTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let parsed = match record.get_field(&self.source_field) {
+            Some(Value::String(address)) => self.parse(address)?,
+            _ => Default::default(),
+        };
+
+        let recognised = parsed[0].is_some() || parsed[1].is_some();
+        for (name, value) in COMPONENT_FIELDS.iter().zip(parsed) {
+            record.set_field(name.to_string(), value.map(Value::String).unwrap_or(Value::Null));
+        }
+        if !recognised {
+            record.set_metadata("address_parsed".to_string(), "false".to_string());
+        }
+
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for name in COMPONENT_FIELDS {
+            if schema.get_field(name).is_none() {
+                schema.fields.push(Field {
+                    name: name.to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                    description: None,
+                });
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn parse(address: Value) -> Record {
+        let mut record = Record::new();
+        record.set_field("address".to_string(), address);
+        let transform = AddressParseTransform::new("address").unwrap();
+        transform.transform(record).await.unwrap().remove(0)
+    }
+
+    #[tokio::test]
+    async fn parses_sample_addresses() {
+        // libpostal normalises components to lower case.
+        let record = parse(json!("781 Franklin Ave Crown Heights Brooklyn NYC NY 11216 USA")).await;
+        assert_eq!(record.get_field("street"), Some(&json!("781 franklin ave")));
+        assert_eq!(record.get_field("city"), Some(&json!("nyc")));
+        assert_eq!(record.get_field("postcode"), Some(&json!("11216")));
+        assert_eq!(record.get_field("country"), Some(&json!("usa")));
+        assert_eq!(record.get_metadata("address_parsed"), None);
+
+        let record = parse(json!("10 Downing Street, London SW1A 2AA, United Kingdom")).await;
+        assert_eq!(record.get_field("street"), Some(&json!("10 downing street")));
+        assert_eq!(record.get_field("city"), Some(&json!("london")));
+        assert_eq!(record.get_field("postcode"), Some(&json!("sw1a 2aa")));
+        assert_eq!(record.get_field("country"), Some(&json!("united kingdom")));
+    }
+
+    #[tokio::test]
+    async fn flags_records_without_an_address() {
+        let record = parse(json!(42)).await;
+        for name in COMPONENT_FIELDS {
+            assert_eq!(record.get_field(name), Some(&Value::Null));
+        }
+        assert_eq!(record.get_metadata("address_parsed"), Some("false"));
+    }
+}
@@ -0,0 +1,453 @@
+use crate::core::{DataType, Field, PipelineError, Record, RecordStream, Result, Schema, Transform};
+use async_trait::async_trait;
+use futures::StreamExt;
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use std::sync::Mutex;
+
+/// How missing, `null` and blank values are treated by numeric aggregations.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NullPolicy {
+    /// Ignore the value; it does not count towards `count` or `mean`.
+    #[default]
+    Skip,
+    /// Aggregate the value as `0`.
+    TreatAsZero,
+    /// Fail the aggregation.
+    Error,
+}
+
+/// How `NaN` floats are treated by numeric aggregations.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NanPolicy {
+    /// Any `NaN` input makes `sum`, `mean`, `min` and `max` `NaN`.
+    #[default]
+    Propagate,
+    /// Drop `NaN` inputs as if they were absent.
+    Ignore,
+}
+
+/// Running sum/count/min/max over a numeric column. Numbers and numeric
+/// strings are accepted; anything else is an error.
+#[derive(Debug, Clone)]
+pub struct NumericAccumulator {
+    null_policy: NullPolicy,
+    nan_policy: NanPolicy,
+    count: u64,
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    running_mean: f64,
+    m2: f64,
+    saw_nan: bool,
+}
+
+impl NumericAccumulator {
+    pub fn new(null_policy: NullPolicy, nan_policy: NanPolicy) -> Self {
+        Self {
+            null_policy,
+            nan_policy,
+            count: 0,
+            sum: 0.0,
+            min: None,
+            max: None,
+            running_mean: 0.0,
+            m2: 0.0,
+            saw_nan: false,
+        }
+    }
+
+    /// Adds one value; `None` means the field was absent from the record.
+    pub fn add(&mut self, value: Option<&Value>) -> Result<()> {
+        if let Some(number) = self.accepts(value)? {
+            self.push(number);
+        }
+        Ok(())
+    }
+
+    /// The number `add` would aggregate for `value`, or `None` if it would
+    /// leave the totals as they are, without changing them.
+    fn accepts(&self, value: Option<&Value>) -> Result<Option<f64>> {
+        let number = match value {
+            None | Some(Value::Null) => None,
+            Some(Value::Number(n)) => n.as_f64(),
+            Some(Value::String(s)) if s.trim().is_empty() => None,
+            Some(Value::String(s)) => Some(s.trim().parse::<f64>().map_err(|_| {
+                PipelineError::Transform(format!("Cannot aggregate non-numeric value '{}'", s))
+            })?),
+            Some(other) => {
+                return Err(PipelineError::Transform(
+                    format!("Cannot aggregate non-numeric value {}", other)
+                ));
+            }
+        };
+
+        let number = match (number, self.null_policy) {
+            (Some(n), _) => n,
+            (None, NullPolicy::Skip) => return Ok(None),
+            (None, NullPolicy::TreatAsZero) => 0.0,
+            (None, NullPolicy::Error) => {
+                return Err(PipelineError::Transform("Cannot aggregate null value".to_string()));
+            }
+        };
+
+        if number.is_nan() && self.nan_policy == NanPolicy::Ignore {
+            return Ok(None);
+        }
+        Ok(Some(number))
+    }
+
+    fn push(&mut self, number: f64) {
+        self.saw_nan |= number.is_nan();
+        self.count += 1;
+        self.sum += number;
+        self.min = Some(self.min.map_or(number, |min| min.min(number)));
+        self.max = Some(self.max.map_or(number, |max| max.max(number)));
+
+        // Welford's update keeps the variance stable over long columns.
+        let delta = number - self.running_mean;
+        self.running_mean += delta / self.count as f64;
+        self.m2 += delta * (number - self.running_mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> f64 {
+        if self.saw_nan { f64::NAN } else { self.sum }
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.sum() / self.count as f64)
+    }
+
+    /// Population standard deviation.
+    pub fn std_dev(&self) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        if self.saw_nan {
+            return Some(f64::NAN);
+        }
+        Some((self.m2 / self.count as f64).sqrt())
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        if self.saw_nan { Some(f64::NAN) } else { self.min }
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        if self.saw_nan { Some(f64::NAN) } else { self.max }
+    }
+}
+
+/// Converts an aggregate result into a JSON value. JSON has no `NaN` or
+/// infinity, so those become `Value::Null`.
+pub fn number_to_value(number: f64) -> Value {
+    Number::from_f64(number).map(Value::Number).unwrap_or(Value::Null)
+}
+
+/// One aggregate column computed per group by a [`GroupByAggregator`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Agg {
+    /// Records in the group, as `count`.
+    Count,
+    /// Sum of a numeric field, as `sum_{field}`.
+    Sum(String),
+    /// Mean of a numeric field, as `avg_{field}`.
+    Avg(String),
+    /// Smallest value of a numeric field, as `min_{field}`.
+    Min(String),
+    /// Largest value of a numeric field, as `max_{field}`.
+    Max(String),
+}
+
+impl Agg {
+    fn field(&self) -> Option<&str> {
+        match self {
+            Agg::Count => None,
+            Agg::Sum(field) | Agg::Avg(field) | Agg::Min(field) | Agg::Max(field) => Some(field),
+        }
+    }
+
+    pub fn output_name(&self) -> String {
+        match self {
+            Agg::Count => "count".to_string(),
+            Agg::Sum(field) => format!("sum_{}", field),
+            Agg::Avg(field) => format!("avg_{}", field),
+            Agg::Min(field) => format!("min_{}", field),
+            Agg::Max(field) => format!("max_{}", field),
+        }
+    }
+
+    fn output_value(&self, count: u64, accumulator: &NumericAccumulator) -> Value {
+        let number = match self {
+            Agg::Count => return Value::from(count),
+            Agg::Sum(_) => Some(accumulator.sum()),
+            Agg::Avg(_) => accumulator.mean(),
+            Agg::Min(_) => accumulator.min(),
+            Agg::Max(_) => accumulator.max(),
+        };
+        number.map_or(Value::Null, number_to_value)
+    }
+}
+
+struct Group {
+    key_values: Vec<Value>,
+    count: u64,
+    accumulators: Vec<NumericAccumulator>,
+}
+
+/// Groups records by the values of `group_keys` and emits one record per
+/// group with those values followed by the aggregate columns. Records with a
+/// missing key field are grouped under null. Without group keys the whole
+/// input forms one group, which is emitted even when the input is empty.
+///
+/// Nothing is emitted until the input ends, when groups come out in the
+/// order their first record arrived. As a pipeline transform, every record
+/// is consumed and the groups are emitted on `flush`; [`aggregate`] runs it
+/// over a stream directly.
+///
+/// Every group's running totals are held in memory until the end, so memory
+/// grows with the number of distinct keys rather than the number of
+/// records. Grouping by a high-cardinality field such as an id can hold
+/// close to one entry per record; pre-aggregate in a database for those.
+///
+/// [`aggregate`]: GroupByAggregator::aggregate
+pub struct GroupByAggregator {
+    group_keys: Vec<String>,
+    aggregations: Vec<Agg>,
+    null_policy: NullPolicy,
+    nan_policy: NanPolicy,
+    groups: Mutex<IndexMap<String, Group>>,
+}
+
+impl GroupByAggregator {
+    pub fn new(group_keys: Vec<String>, aggregations: Vec<Agg>) -> Self {
+        Self {
+            group_keys,
+            aggregations,
+            null_policy: NullPolicy::default(),
+            nan_policy: NanPolicy::default(),
+            groups: Mutex::new(IndexMap::new()),
+        }
+    }
+
+    pub fn with_null_policy(mut self, null_policy: NullPolicy) -> Self {
+        self.null_policy = null_policy;
+        self
+    }
+
+    pub fn with_nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    fn new_group(&self, key_values: Vec<Value>) -> Group {
+        Group {
+            key_values,
+            count: 0,
+            accumulators: self
+                .aggregations
+                .iter()
+                .map(|_| NumericAccumulator::new(self.null_policy, self.nan_policy))
+                .collect(),
+        }
+    }
+
+    fn add(&self, record: &Record) -> Result<()> {
+        let key_values: Vec<Value> = self
+            .group_keys
+            .iter()
+            .map(|key| record.get_field(key).cloned().unwrap_or(Value::Null))
+            .collect();
+        let key = serde_json::to_string(&key_values)?;
+
+        // Every value is checked before any total changes, so a rejected
+        // record leaves the groups as they were. Whether a value is
+        // accepted depends only on the policies, not the running totals.
+        let check = NumericAccumulator::new(self.null_policy, self.nan_policy);
+        let numbers = self
+            .aggregations
+            .iter()
+            .map(|aggregation| aggregation.field().map_or(Ok(None), |field| check.accepts(record.get_field(field))))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut groups = self.groups.lock().unwrap();
+        let group = groups.entry(key).or_insert_with(|| self.new_group(key_values));
+        group.count += 1;
+        for (accumulator, number) in group.accumulators.iter_mut().zip(numbers) {
+            if let Some(number) = number {
+                accumulator.push(number);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the aggregated groups, leaving the aggregator empty.
+    fn take_groups(&self) -> Vec<Record> {
+        let mut groups = std::mem::take(&mut *self.groups.lock().unwrap());
+        if groups.is_empty() && self.group_keys.is_empty() {
+            groups.insert(String::new(), self.new_group(Vec::new()));
+        }
+
+        groups
+            .into_values()
+            .map(|group| {
+                let mut record = Record::new();
+                for (key, value) in self.group_keys.iter().zip(group.key_values) {
+                    record.set_field(key.clone(), value);
+                }
+                for (aggregation, accumulator) in self.aggregations.iter().zip(&group.accumulators) {
+                    record.set_field(aggregation.output_name(), aggregation.output_value(group.count, accumulator));
+                }
+                record
+            })
+            .collect()
+    }
+
+    /// Consumes `records` and returns a stream of the aggregated groups.
+    pub async fn aggregate(&self, mut records: RecordStream) -> Result<RecordStream> {
+        while let Some(record) = records.next().await {
+            self.add(&record?)?;
+        }
+        Ok(Box::pin(futures::stream::iter(self.take_groups().into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl Transform for GroupByAggregator {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        self.add(&record)?;
+        Ok(vec![])
+    }
+
+    async fn flush(&self) -> Result<Vec<Record>> {
+        Ok(self.take_groups())
+    }
+
+    fn buffers(&self) -> bool {
+        true
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut fields: Vec<Field> = self
+            .group_keys
+            .iter()
+            .map(|key| {
+                input_schema.get_field(key).cloned().unwrap_or(Field {
+                    name: key.clone(),
+                    data_type: DataType::Json,
+                    nullable: true,
+                    description: None,
+                })
+            })
+            .collect();
+        for aggregation in &self.aggregations {
+            let (data_type, nullable) = match aggregation {
+                Agg::Count => (DataType::Integer, false),
+                // NaN and infinite results, and empty groups, come out as null.
+                _ => (DataType::Float, true),
+            };
+            fields.push(Field {
+                name: aggregation.output_name(),
+                data_type,
+                nullable,
+                description: None,
+            });
+        }
+        Ok(Schema::new(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    /// A sparse column: two numbers, a null, a blank string and an absent
+    /// value.
+    fn sparse_column() -> Vec<Option<Value>> {
+        vec![Some(json!(2)), Some(Value::Null), Some(json!("4.5")), Some(json!(" ")), None]
+    }
+
+    fn accumulate(values: &[Option<Value>], null_policy: NullPolicy, nan_policy: NanPolicy) -> Result<NumericAccumulator> {
+        let mut accumulator = NumericAccumulator::new(null_policy, nan_policy);
+        for value in values {
+            accumulator.add(value.as_ref())?;
+        }
+        Ok(accumulator)
+    }
+
+    #[test]
+    fn skip_ignores_nulls() {
+        let accumulator = accumulate(&sparse_column(), NullPolicy::Skip, NanPolicy::Propagate).unwrap();
+        assert_eq!(accumulator.count(), 2);
+        assert_eq!(accumulator.sum(), 6.5);
+        assert_eq!(accumulator.mean(), Some(3.25));
+        assert_eq!(accumulator.min(), Some(2.0));
+    }
+
+    #[test]
+    fn treat_as_zero_counts_nulls_as_zero() {
+        let accumulator = accumulate(&sparse_column(), NullPolicy::TreatAsZero, NanPolicy::Propagate).unwrap();
+        assert_eq!(accumulator.count(), 5);
+        assert_eq!(accumulator.sum(), 6.5);
+        assert_eq!(accumulator.mean(), Some(1.3));
+        assert_eq!(accumulator.min(), Some(0.0));
+    }
+
+    #[test]
+    fn error_rejects_nulls() {
+        let result = accumulate(&sparse_column(), NullPolicy::Error, NanPolicy::Propagate);
+        assert!(matches!(result, Err(PipelineError::Transform(_))));
+    }
+
+    #[test]
+    fn nan_propagates_or_is_ignored() {
+        let values = vec![Some(json!(1)), Some(json!("NaN")), Some(json!(3))];
+
+        let propagated = accumulate(&values, NullPolicy::Skip, NanPolicy::Propagate).unwrap();
+        assert!(propagated.sum().is_nan());
+        assert!(propagated.max().unwrap().is_nan());
+        assert_eq!(number_to_value(propagated.sum()), Value::Null);
+
+        let ignored = accumulate(&values, NullPolicy::Skip, NanPolicy::Ignore).unwrap();
+        assert_eq!(ignored.count(), 2);
+        assert_eq!(ignored.sum(), 4.0);
+        assert_eq!(ignored.max(), Some(3.0));
+    }
+
+    #[test]
+    fn non_numeric_values_are_errors() {
+        let result = accumulate(&[Some(json!("abc"))], NullPolicy::Skip, NanPolicy::Propagate);
+        assert!(matches!(result, Err(PipelineError::Transform(_))));
+    }
+
+    #[tokio::test]
+    async fn rejected_record_leaves_its_group_unchanged() {
+        let aggregator = GroupByAggregator::new(
+            vec!["region".to_string()],
+            vec![Agg::Count, Agg::Sum("units".to_string()), Agg::Sum("price".to_string())],
+        );
+
+        aggregator.transform(record(json!({"region": "eu", "units": 2, "price": 10}))).await.unwrap();
+        // `units` is fine but `price` is not, so neither is added.
+        let rejected = aggregator.transform(record(json!({"region": "eu", "units": 5, "price": "n/a"}))).await;
+        assert!(matches!(rejected, Err(PipelineError::Transform(_))));
+        // A rejected record does not start a group either.
+        let rejected = aggregator.transform(record(json!({"region": "us", "units": 1, "price": "n/a"}))).await;
+        assert!(rejected.is_err());
+
+        let groups = aggregator.flush().await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].get_field("count"), Some(&json!(1)));
+        assert_eq!(groups[0].get_field("sum_units"), Some(&json!(2.0)));
+        assert_eq!(groups[0].get_field("sum_price"), Some(&json!(10.0)));
+    }
+}
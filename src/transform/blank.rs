@@ -0,0 +1,82 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Replaces empty and whitespace-only string values with `Value::Null`, in
+/// the given fields or, by default, in every field.
+pub struct BlankToNullTransform {
+    fields: Option<Vec<String>>,
+}
+
+impl BlankToNullTransform {
+    pub fn new() -> Self {
+        Self { fields: None }
+    }
+
+    pub fn with_fields(mut self, fields: Vec<String>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    fn applies_to(&self, field: &str) -> bool {
+        match self.fields {
+            Some(ref fields) => fields.iter().any(|f| f == field),
+            None => true,
+        }
+    }
+}
+
+impl Default for BlankToNullTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Transform for BlankToNullTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        for (name, value) in record.data.iter_mut() {
+            if let Value::String(s) = value
+                && s.trim().is_empty()
+                && self.applies_to(name)
+            {
+                *value = Value::Null;
+            }
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if self.applies_to(&field.name) {
+                field.nullable = true;
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn messy() -> Record {
+        record(json!({"a": "   ", "b": "x", "c": "", "d": " \t\n", "e": 0}))
+    }
+
+    #[tokio::test]
+    async fn blanks_become_null_in_every_field() {
+        let out = BlankToNullTransform::new().transform(messy()).await.unwrap();
+        assert_eq!(out[0].data, record(json!({"a": null, "b": "x", "c": null, "d": null, "e": 0})).data);
+    }
+
+    #[tokio::test]
+    async fn only_the_given_fields_are_touched() {
+        let transform = BlankToNullTransform::new().with_fields(vec!["a".to_string(), "b".to_string()]);
+        let out = transform.transform(messy()).await.unwrap();
+        assert_eq!(out[0].data, record(json!({"a": null, "b": "x", "c": "", "d": " \t\n", "e": 0})).data);
+    }
+}
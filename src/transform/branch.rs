@@ -0,0 +1,95 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+
+type Predicate = Box<dyn Fn(&Record) -> bool + Send + Sync>;
+
+/// Labels each record with the name of the first branch whose predicate it
+/// satisfies, falling back to a default branch. The label is written to the
+/// `branch` metadata key so a downstream sink can route on it.
+pub struct BranchTransform {
+    rules: Vec<(String, Predicate)>,
+    default_branch: String,
+    metadata_key: String,
+}
+
+impl BranchTransform {
+    pub fn new(default_branch: &str) -> Self {
+        Self {
+            rules: Vec::new(),
+            default_branch: default_branch.to_string(),
+            metadata_key: "branch".to_string(),
+        }
+    }
+
+    /// Adds a rule; rules are evaluated in the order they were added.
+    pub fn branch<F>(mut self, name: &str, predicate: F) -> Self
+    where
+        F: Fn(&Record) -> bool + Send + Sync + 'static,
+    {
+        self.rules.push((name.to_string(), Box::new(predicate)));
+        self
+    }
+
+    pub fn with_metadata_key(mut self, key: &str) -> Self {
+        self.metadata_key = key.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for BranchTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let branch = self
+            .rules
+            .iter()
+            .find(|(_, predicate)| predicate(&record))
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| self.default_branch.clone());
+
+        record.set_metadata(self.metadata_key.clone(), branch);
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn amount(record: &Record) -> f64 {
+        record.get_field("amount").and_then(Value::as_f64).unwrap_or_default()
+    }
+
+    async fn branches(transform: &BranchTransform, key: &str) -> Vec<String> {
+        let mut branches = Vec::new();
+        for value in [1500.0, 50.0, -5.0, 250.0] {
+            let mut record = Record::new();
+            record.set_field("amount".to_string(), json!(value));
+            let out = transform.transform(record).await.unwrap();
+            branches.push(out[0].get_metadata(key).unwrap().to_string());
+        }
+        branches
+    }
+
+    fn transform() -> BranchTransform {
+        BranchTransform::new("normal")
+            .branch("refund", |r| amount(r) < 0.0)
+            .branch("large", |r| amount(r) > 1000.0)
+            .branch("medium", |r| amount(r) > 100.0)
+    }
+
+    #[tokio::test]
+    async fn tags_the_first_matching_branch_or_the_default() {
+        assert_eq!(branches(&transform(), "branch").await, ["large", "normal", "refund", "medium"]);
+    }
+
+    #[tokio::test]
+    async fn metadata_key_is_configurable() {
+        let transform = transform().with_metadata_key("route");
+        assert_eq!(branches(&transform, "route").await, ["large", "normal", "refund", "medium"]);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::core::{timestamp_millis, DataType, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, SecondsFormat};
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+
+/// Converts `value` into the representation expected for `data_type`,
+/// returning `None` when no sensible conversion exists. Strings are trimmed
+/// before parsing and blank strings become `Value::Null` for non-string types.
+pub fn cast_value(value: &Value, data_type: &DataType) -> Option<Value> {
+    if value.is_null() {
+        return Some(Value::Null);
+    }
+
+    if let Value::String(s) = value
+        && s.trim().is_empty()
+        && *data_type != DataType::String
+    {
+        return Some(Value::Null);
+    }
+
+    match data_type {
+        DataType::String => match value {
+            Value::String(s) => Some(Value::String(s.clone())),
+            Value::Number(n) => Some(Value::String(n.to_string())),
+            Value::Bool(b) => Some(Value::String(b.to_string())),
+            _ => serde_json::to_string(value).ok().map(Value::String),
+        },
+        DataType::Integer => match value {
+            Value::Number(n) if n.is_i64() => Some(value.clone()),
+            Value::Number(n) => n
+                .as_f64()
+                .filter(|f| f.fract() == 0.0 && *f >= i64::MIN as f64 && *f <= i64::MAX as f64)
+                .map(|f| Value::from(f as i64)),
+            Value::String(s) => s.trim().parse::<i64>().ok().map(Value::from),
+            Value::Bool(b) => Some(Value::from(*b as i64)),
+            _ => None,
+        },
+        DataType::Float => match value {
+            Value::Number(n) => n.as_f64().and_then(Number::from_f64).map(Value::Number),
+            Value::String(s) => s
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number),
+            _ => None,
+        },
+        DataType::Boolean => match value {
+            Value::Bool(_) => Some(value.clone()),
+            Value::Number(n) => match n.as_i64() {
+                Some(0) => Some(Value::Bool(false)),
+                Some(1) => Some(Value::Bool(true)),
+                _ => None,
+            },
+            Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => Some(Value::Bool(true)),
+                "false" | "0" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        },
+        DataType::DateTime | DataType::Bytes => match value {
+            Value::String(_) => Some(value.clone()),
+            _ => None,
+        },
+        DataType::Date => match value {
+            Value::String(s) if NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").is_ok() => {
+                Some(Value::String(s.trim().to_string()))
+            }
+            _ => timestamp_millis(value)
+                .and_then(DateTime::from_timestamp_millis)
+                .map(|dt| Value::String(dt.date_naive().to_string())),
+        },
+        DataType::Timestamp => match value {
+            Value::String(s) if DateTime::parse_from_rfc3339(s.trim()).is_ok() => Some(Value::String(s.trim().to_string())),
+            // Values without an offset are taken to be UTC.
+            _ => timestamp_millis(value)
+                .and_then(DateTime::from_timestamp_millis)
+                .map(|dt| Value::String(dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))),
+        },
+        DataType::Json => Some(value.clone()),
+        DataType::Array(item_type) => match value {
+            Value::Array(items) => items
+                .iter()
+                .map(|item| cast_value(item, item_type))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array),
+            _ => None,
+        },
+    }
+}
+
+/// Converts the listed fields to the given types with [`cast_value`], so
+/// `" 42 "` becomes `42` for an integer and blank strings become null.
+///
+/// A value that cannot be converted is an error, or null when lenient.
+/// Fields missing from a record are left out.
+pub struct CastTransform {
+    types: HashMap<String, DataType>,
+    lenient: bool,
+}
+
+impl CastTransform {
+    pub fn new(types: HashMap<String, DataType>) -> Self {
+        Self { types, lenient: false }
+    }
+
+    pub fn cast(mut self, field: &str, data_type: DataType) -> Self {
+        self.types.insert(field.to_string(), data_type);
+        self
+    }
+
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for CastTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        for (name, data_type) in &self.types {
+            let Some(value) = record.data.get_mut(name) else {
+                continue;
+            };
+            *value = match cast_value(value, data_type) {
+                Some(cast) => cast,
+                None if self.lenient => Value::Null,
+                None => {
+                    return Err(PipelineError::Transform(format!(
+                        "Cannot cast field '{}' value {} to {:?}",
+                        name, value, data_type
+                    )));
+                }
+            };
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if let Some(data_type) = self.types.get(&field.name) {
+                field.data_type = data_type.clone();
+                // Blank strings, and failed casts when lenient, come out null.
+                field.nullable |= self.lenient || *data_type != DataType::String;
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Field;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    #[test]
+    fn whitespace_around_values_is_ignored() {
+        assert_eq!(cast_value(&json!(" 42 "), &DataType::Integer), Some(json!(42)));
+        assert_eq!(cast_value(&json!("\t-1.5\n"), &DataType::Float), Some(json!(-1.5)));
+        assert_eq!(cast_value(&json!(" TRUE "), &DataType::Boolean), Some(json!(true)));
+        assert_eq!(cast_value(&json!(" 0 "), &DataType::Boolean), Some(json!(false)));
+        assert_eq!(cast_value(&json!(" 2024-03-01 "), &DataType::Date), Some(json!("2024-03-01")));
+        // Strings are kept as they are.
+        assert_eq!(cast_value(&json!(" padded "), &DataType::String), Some(json!(" padded ")));
+    }
+
+    #[test]
+    fn blank_strings_become_null_except_for_strings() {
+        for data_type in [DataType::Integer, DataType::Float, DataType::Boolean, DataType::Date] {
+            assert_eq!(cast_value(&json!(""), &data_type), Some(Value::Null), "{:?}", data_type);
+            assert_eq!(cast_value(&json!("   "), &data_type), Some(Value::Null), "{:?}", data_type);
+        }
+        assert_eq!(cast_value(&json!(""), &DataType::String), Some(json!("")));
+    }
+
+    #[test]
+    fn unconvertible_values_have_no_cast() {
+        assert_eq!(cast_value(&json!("4 2"), &DataType::Integer), None);
+        assert_eq!(cast_value(&json!("1.5"), &DataType::Integer), None);
+        assert_eq!(cast_value(&json!(1.5), &DataType::Integer), None);
+        assert_eq!(cast_value(&json!("1,5"), &DataType::Float), None);
+        assert_eq!(cast_value(&json!("yes"), &DataType::Boolean), None);
+        assert_eq!(cast_value(&json!(2), &DataType::Boolean), None);
+        assert_eq!(cast_value(&json!("01/03/2024"), &DataType::Date), None);
+    }
+
+    #[tokio::test]
+    async fn failed_cast_is_an_error_unless_lenient() {
+        let input = record(json!({"id": " 7 ", "active": "maybe"}));
+        let cast = || CastTransform::new(HashMap::new()).cast("id", DataType::Integer).cast("active", DataType::Boolean);
+
+        let error = cast().transform(input.clone()).await.unwrap_err();
+        assert!(matches!(error, PipelineError::Transform(ref e) if e.contains("'active'")), "{}", error);
+
+        let output = cast().lenient(true).transform(input).await.unwrap();
+        assert_eq!(output[0].get_field("id"), Some(&json!(7)));
+        assert_eq!(output[0].get_field("active"), Some(&Value::Null));
+    }
+
+    #[tokio::test]
+    async fn missing_fields_are_left_out() {
+        let output = CastTransform::new(HashMap::new())
+            .cast("count", DataType::Integer)
+            .transform(record(json!({"name": "a"})))
+            .await
+            .unwrap();
+        assert_eq!(output[0].get_field("count"), None);
+    }
+
+    #[tokio::test]
+    async fn output_schema_has_the_cast_types() {
+        let field = |name: &str| Field {
+            name: name.to_string(),
+            data_type: DataType::String,
+            nullable: false,
+            description: None,
+        };
+        let input = Schema::new(vec![field("id"), field("name")]);
+
+        let strict = CastTransform::new(HashMap::new())
+            .cast("id", DataType::Integer)
+            .cast("name", DataType::String)
+            .get_output_schema(&input)
+            .await
+            .unwrap();
+        assert_eq!(strict.fields[0].data_type, DataType::Integer);
+        assert!(strict.fields[0].nullable, "blank strings cast to null");
+        assert_eq!(strict.fields[1].data_type, DataType::String);
+        assert!(!strict.fields[1].nullable);
+
+        let lenient = CastTransform::new(HashMap::new())
+            .cast("name", DataType::String)
+            .lenient(true)
+            .get_output_schema(&input)
+            .await
+            .unwrap();
+        assert!(lenient.fields[1].nullable);
+    }
+}
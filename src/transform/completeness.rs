@@ -0,0 +1,105 @@
+use crate::core::{DataType, Field, Record, Result, Schema, Transform};
+use crate::transform::aggregate::number_to_value;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Scores each record by the fraction of `expected_fields` that are present
+/// and non-null, from `0.0` to `1.0`, and writes it to `completeness`. An
+/// empty field list scores every record `1.0`.
+pub struct CompletenessTransform {
+    expected_fields: Vec<String>,
+    output_field: String,
+    metadata_key: Option<String>,
+}
+
+impl CompletenessTransform {
+    pub fn new(expected_fields: Vec<String>) -> Self {
+        Self {
+            expected_fields,
+            output_field: "completeness".to_string(),
+            metadata_key: None,
+        }
+    }
+
+    pub fn with_output_field(mut self, field: &str) -> Self {
+        self.output_field = field.to_string();
+        self
+    }
+
+    /// Also records the score as a string under metadata `key`.
+    pub fn with_metadata_key(mut self, key: &str) -> Self {
+        self.metadata_key = Some(key.to_string());
+        self
+    }
+
+    fn score(&self, record: &Record) -> f64 {
+        if self.expected_fields.is_empty() {
+            return 1.0;
+        }
+        let populated = self
+            .expected_fields
+            .iter()
+            .filter(|field| !matches!(record.get_field(field), None | Some(Value::Null)))
+            .count();
+        populated as f64 / self.expected_fields.len() as f64
+    }
+}
+
+#[async_trait]
+impl Transform for CompletenessTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let score = self.score(&record);
+        if let Some(ref key) = self.metadata_key {
+            record.set_metadata(key.clone(), score.to_string());
+        }
+        record.set_field(self.output_field.clone(), number_to_value(score));
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|f| f.name != self.output_field);
+        schema.fields.push(Field {
+            name: self.output_field.clone(),
+            data_type: DataType::Float,
+            nullable: false,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn expected() -> Vec<String> {
+        ["name", "email", "phone", "city"].iter().map(|f| f.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn scores_the_fraction_of_populated_fields() {
+        let transform = CompletenessTransform::new(expected()).with_metadata_key("quality");
+        let cases = [
+            (json!({"name": "a", "email": "b", "phone": "c", "city": "d"}), 1.0),
+            (json!({"name": "a", "email": null, "phone": "", "extra": 1}), 0.5),
+            (json!({"city": "d"}), 0.25),
+            (json!({}), 0.0),
+        ];
+        for (input, score) in cases {
+            let out = transform.transform(record(input)).await.unwrap();
+            assert_eq!(out[0].get_field("completeness"), Some(&json!(score)));
+            assert_eq!(out[0].get_metadata("quality"), Some(score.to_string().as_str()));
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_field_list_scores_one() {
+        let transform = CompletenessTransform::new(Vec::new()).with_output_field("score");
+        let out = transform.transform(Record::new()).await.unwrap();
+        assert_eq!(out[0].get_field("score"), Some(&json!(1.0)));
+        assert!(out[0].metadata.is_empty());
+    }
+}
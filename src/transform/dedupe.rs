@@ -0,0 +1,138 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use lru::LruCache;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+enum SeenKeys {
+    Unbounded(HashSet<String>),
+    Bounded(LruCache<String, ()>),
+}
+
+impl SeenKeys {
+    /// Records `key` as seen, returning whether it was already.
+    fn insert(&mut self, key: String) -> bool {
+        match self {
+            SeenKeys::Unbounded(seen) => !seen.insert(key),
+            SeenKeys::Bounded(seen) => seen.put(key, ()).is_some(),
+        }
+    }
+}
+
+/// Lets through only the first record for each combination of `key_fields`
+/// values, dropping later repeats. A missing key field counts as null.
+///
+/// Every distinct key is remembered for the rest of the run as its
+/// serialized values, so distinct keys never collide and memory grows with
+/// the number and size of distinct keys. With
+/// `with_capacity` only the `n` most recently seen keys are kept: memory is
+/// bounded, but a duplicate whose key was evicted since it last appeared is
+/// let through again.
+pub struct DedupeTransform {
+    key_fields: Vec<String>,
+    seen: Mutex<SeenKeys>,
+}
+
+impl DedupeTransform {
+    pub fn new(key_fields: Vec<String>) -> Self {
+        Self {
+            key_fields,
+            seen: Mutex::new(SeenKeys::Unbounded(HashSet::new())),
+        }
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        self.seen = Mutex::new(SeenKeys::Bounded(LruCache::new(capacity)));
+        self
+    }
+
+    /// The key values as a JSON array, which tells apart tuples whose
+    /// values would run together when concatenated.
+    fn key(&self, record: &Record) -> String {
+        let values: Vec<&Value> = self
+            .key_fields
+            .iter()
+            .map(|field| record.get_field(field).unwrap_or(&Value::Null))
+            .collect();
+        serde_json::to_string(&values).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl Transform for DedupeTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        let key = self.key(&record);
+        if self.seen.lock().unwrap().insert(key) {
+            return Ok(vec![]);
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: Value, name: &str) -> Record {
+        let mut record = Record::new();
+        record.set_field("id".to_string(), id);
+        record.set_field("name".to_string(), json!(name));
+        record
+    }
+
+    async fn names(transform: &DedupeTransform, records: Vec<Record>) -> Vec<String> {
+        let mut names = Vec::new();
+        for record in records {
+            for out in transform.transform(record).await.unwrap() {
+                names.push(out.get_field("name").unwrap().as_str().unwrap().to_string());
+            }
+        }
+        names
+    }
+
+    #[tokio::test]
+    async fn drops_records_with_a_repeated_key() {
+        let transform = DedupeTransform::new(vec!["id".to_string()]);
+        let records = vec![record(json!(1), "a"), record(json!(2), "b"), record(json!(1), "c")];
+        assert_eq!(names(&transform, records).await, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn compares_the_whole_key_tuple() {
+        let transform = DedupeTransform::new(vec!["id".to_string(), "name".to_string()]);
+        let records = vec![
+            record(json!(1), "a"),
+            record(json!(1), "b"),
+            record(json!("1"), "a"),
+            record(json!(1), "a"),
+        ];
+        assert_eq!(names(&transform, records).await, ["a", "b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn missing_key_field_counts_as_null() {
+        let transform = DedupeTransform::new(vec!["missing".to_string()]);
+        let records = vec![record(json!(1), "a"), record(json!(2), "b")];
+        assert_eq!(names(&transform, records).await, ["a"]);
+    }
+
+    #[tokio::test]
+    async fn bounded_capacity_forgets_evicted_keys() {
+        let transform = DedupeTransform::new(vec!["id".to_string()]).with_capacity(1);
+        let records = vec![
+            record(json!(1), "a"),
+            record(json!(1), "b"),
+            record(json!(2), "c"),
+            record(json!(1), "d"),
+        ];
+        assert_eq!(names(&transform, records).await, ["a", "c", "d"]);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Compares each record's keys with an expected `Schema` and records any
+/// difference in metadata as comma-separated `drift_missing` / `drift_extra`
+/// lists. Records always pass through unchanged otherwise.
+pub struct DriftDetectTransform {
+    expected: Schema,
+    counter: Option<Arc<AtomicU64>>,
+}
+
+impl DriftDetectTransform {
+    pub fn new(expected: Schema) -> Self {
+        Self {
+            expected,
+            counter: None,
+        }
+    }
+
+    /// Increments `counter` once for every record that drifted.
+    pub fn with_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for DriftDetectTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let missing: Vec<&str> = self
+            .expected
+            .field_names()
+            .into_iter()
+            .filter(|name| !record.data.contains_key(*name))
+            .collect();
+
+        let mut extra: Vec<&str> = record
+            .data
+            .keys()
+            .map(|key| key.as_str())
+            .filter(|key| self.expected.get_field(key).is_none())
+            .collect();
+        extra.sort_unstable();
+
+        if missing.is_empty() && extra.is_empty() {
+            return Ok(vec![record]);
+        }
+
+        let missing = missing.join(",");
+        let extra = extra.join(",");
+        if !missing.is_empty() {
+            record.set_metadata("drift_missing".to_string(), missing);
+        }
+        if !extra.is_empty() {
+            record.set_metadata("drift_extra".to_string(), extra);
+        }
+        if let Some(ref counter) = self.counter {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DataType, Field};
+    use serde_json::json;
+
+    fn expected() -> Schema {
+        let field = |name: &str| Field {
+            name: name.to_string(),
+            data_type: DataType::String,
+            nullable: true,
+            description: None,
+        };
+        Schema::new(vec![field("id"), field("name")])
+    }
+
+    fn record(fields: &[&str]) -> Record {
+        let mut record = Record::new();
+        for field in fields {
+            record.set_field(field.to_string(), json!("x"));
+        }
+        record
+    }
+
+    #[tokio::test]
+    async fn tags_extra_and_missing_fields() {
+        let counter = Arc::new(AtomicU64::new(0));
+        let transform = DriftDetectTransform::new(expected()).with_counter(counter.clone());
+
+        let out = transform.transform(record(&["id", "name", "email"])).await.unwrap();
+        assert_eq!(out[0].get_metadata("drift_extra"), Some("email"));
+        assert_eq!(out[0].get_metadata("drift_missing"), None);
+        assert_eq!(out[0].data.len(), 3);
+
+        let out = transform.transform(record(&["zip", "age"])).await.unwrap();
+        assert_eq!(out[0].get_metadata("drift_extra"), Some("age,zip"));
+        assert_eq!(out[0].get_metadata("drift_missing"), Some("id,name"));
+
+        assert_eq!(counter.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn matching_records_are_left_untagged() {
+        let counter = Arc::new(AtomicU64::new(0));
+        let transform = DriftDetectTransform::new(expected()).with_counter(counter.clone());
+        let out = transform.transform(record(&["name", "id"])).await.unwrap();
+        assert!(out[0].metadata.is_empty());
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+}
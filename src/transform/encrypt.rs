@@ -0,0 +1,186 @@
+use crate::core::{DataType, PipelineError, Record, Result, Schema, Transform};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::Value;
+
+const NONCE_LEN: usize = 12;
+
+/// Encrypts the values of selected fields with AES-256-GCM, leaving every
+/// other field untouched. Each value is serialized to JSON, encrypted with a
+/// fresh random nonce and stored as base64 of `nonce || ciphertext`.
+pub struct FieldEncryptTransform {
+    cipher: Aes256Gcm,
+    fields: Vec<String>,
+}
+
+impl FieldEncryptTransform {
+    pub fn new(key: &[u8; 32], fields: Vec<String>) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            fields,
+        }
+    }
+
+    fn encrypt_value(&self, value: &Value) -> Result<Value> {
+        let plaintext = serde_json::to_vec(value)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| PipelineError::Transform(format!("Encryption failed: {}", e)))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(Value::String(BASE64.encode(payload)))
+    }
+}
+
+#[async_trait]
+impl Transform for FieldEncryptTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        for field in &self.fields {
+            if let Some(value) = record.data.get(field) {
+                if value.is_null() {
+                    continue;
+                }
+                let encrypted = self.encrypt_value(value)?;
+                record.data.insert(field.clone(), encrypted);
+            }
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if self.fields.contains(&field.name) {
+                field.data_type = DataType::String;
+            }
+        }
+        Ok(schema)
+    }
+}
+
+/// Reverses [`FieldEncryptTransform`] given the same key, restoring each
+/// field's original JSON value.
+pub struct FieldDecryptTransform {
+    cipher: Aes256Gcm,
+    fields: Vec<String>,
+}
+
+impl FieldDecryptTransform {
+    pub fn new(key: &[u8; 32], fields: Vec<String>) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            fields,
+        }
+    }
+
+    fn decrypt_value(&self, field: &str, value: &Value) -> Result<Value> {
+        let encoded = value.as_str().ok_or_else(|| {
+            PipelineError::Transform(format!("Encrypted field '{}' is not a string", field))
+        })?;
+        let payload = BASE64.decode(encoded).map_err(|e| {
+            PipelineError::Transform(format!("Encrypted field '{}' is not valid base64: {}", field, e))
+        })?;
+        if payload.len() < NONCE_LEN {
+            return Err(PipelineError::Transform(
+                format!("Encrypted field '{}' is too short", field)
+            ));
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|e| PipelineError::Transform(format!("Decryption of field '{}' failed: {}", field, e)))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+#[async_trait]
+impl Transform for FieldDecryptTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        for field in &self.fields {
+            if let Some(value) = record.data.get(field) {
+                if value.is_null() {
+                    continue;
+                }
+                let decrypted = self.decrypt_value(field, value)?;
+                record.data.insert(field.clone(), decrypted);
+            }
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if self.fields.contains(&field.name) {
+                field.data_type = DataType::Json;
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn fields() -> Vec<String> {
+        vec!["ssn".to_string(), "card".to_string()]
+    }
+
+    #[tokio::test]
+    async fn encrypted_fields_decrypt_back() {
+        let original = record(json!({"id": 1, "ssn": "123-45-6789", "card": {"number": 4111, "cvv": "123"}}));
+        let encrypted = FieldEncryptTransform::new(&KEY, fields())
+            .transform(original.clone())
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(encrypted.get_field("id"), Some(&json!(1)));
+        for field in fields() {
+            let value = encrypted.get_field(&field).unwrap();
+            assert!(value.is_string());
+            assert_ne!(value, original.get_field(&field).unwrap());
+        }
+
+        let decrypted = FieldDecryptTransform::new(&KEY, fields())
+            .transform(encrypted)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(decrypted.data, original.data);
+    }
+
+    #[tokio::test]
+    async fn nulls_and_missing_fields_are_left_alone() {
+        let original = record(json!({"ssn": null}));
+        let encrypted = FieldEncryptTransform::new(&KEY, fields())
+            .transform(original.clone())
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(encrypted.data, original.data);
+    }
+
+    #[tokio::test]
+    async fn wrong_key_fails_to_decrypt() {
+        let encrypted = FieldEncryptTransform::new(&KEY, fields())
+            .transform(record(json!({"ssn": "123-45-6789"})))
+            .await
+            .unwrap()
+            .remove(0);
+        let result = FieldDecryptTransform::new(&[8; 32], fields()).transform(encrypted).await;
+        assert!(matches!(result, Err(PipelineError::Transform(_))));
+    }
+}
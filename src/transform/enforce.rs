@@ -0,0 +1,176 @@
+use crate::core::{PipelineError, Record, Result, Schema, Transform};
+use crate::transform::cast::cast_value;
+use async_trait::async_trait;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnforceMode {
+    /// Fail the record with a report of every violating field.
+    Strict,
+    /// Replace every violating field with `Value::Null` and pass the record on.
+    Lenient,
+    /// Pass the record on unchanged with the report stored in its `error` metadata.
+    DeadLetter,
+}
+
+/// Casts each record's fields to the types of a target `Schema` and then
+/// checks nullability, collecting all violations in a single pass.
+pub struct SchemaEnforceTransform {
+    schema: Schema,
+    mode: EnforceMode,
+}
+
+impl SchemaEnforceTransform {
+    pub fn new(schema: Schema, mode: EnforceMode) -> Self {
+        Self { schema, mode }
+    }
+
+    fn enforce(&self, record: &mut Record) -> Vec<(String, String)> {
+        let mut violations = Vec::new();
+
+        for field in &self.schema.fields {
+            let value = match record.data.get(&field.name) {
+                Some(value) => value,
+                None => {
+                    if !field.nullable {
+                        violations.push((field.name.clone(), "required field is missing".to_string()));
+                    }
+                    continue;
+                }
+            };
+
+            match cast_value(value, &field.data_type) {
+                Some(Value::Null) if !field.nullable => {
+                    violations.push((field.name.clone(), "required field is null".to_string()));
+                }
+                Some(cast) => {
+                    record.data.insert(field.name.clone(), cast);
+                }
+                None => {
+                    violations.push((
+                        field.name.clone(),
+                        format!("cannot cast {} to {:?}", value, field.data_type),
+                    ));
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+fn format_report(violations: &[(String, String)]) -> String {
+    let details: Vec<String> = violations
+        .iter()
+        .map(|(field, reason)| format!("'{}': {}", field, reason))
+        .collect();
+    format!("{} field(s) violate the schema: {}", violations.len(), details.join("; "))
+}
+
+#[async_trait]
+impl Transform for SchemaEnforceTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        let mut enforced = record.clone();
+        let violations = self.enforce(&mut enforced);
+
+        if violations.is_empty() {
+            return Ok(vec![enforced]);
+        }
+
+        match self.mode {
+            EnforceMode::Strict => Err(PipelineError::Schema(format_report(&violations))),
+            EnforceMode::Lenient => {
+                for (field, _) in &violations {
+                    enforced.data.insert(field.clone(), Value::Null);
+                }
+                Ok(vec![enforced])
+            }
+            EnforceMode::DeadLetter => {
+                let mut record = record;
+                record.set_metadata("error".to_string(), format_report(&violations));
+                Ok(vec![record])
+            }
+        }
+    }
+
+    async fn get_output_schema(&self, _input_schema: &Schema) -> Result<Schema> {
+        Ok(self.schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DataType, Field};
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn field(name: &str, data_type: DataType, nullable: bool) -> Field {
+        Field {
+            name: name.to_string(),
+            data_type,
+            nullable,
+            description: None,
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            field("id", DataType::Integer, false),
+            field("score", DataType::Float, true),
+            field("active", DataType::Boolean, true),
+            field("email", DataType::String, false),
+        ])
+    }
+
+    /// Three violations at once: an uncastable id and score, and a missing
+    /// required email.
+    fn bad_record() -> Record {
+        record(json!({"id": "abc", "score": "high", "active": "true"}))
+    }
+
+    #[tokio::test]
+    async fn casts_a_valid_record() {
+        let transform = SchemaEnforceTransform::new(schema(), EnforceMode::Strict);
+        let out = transform
+            .transform(record(json!({"id": "7", "score": "1.5", "active": "true", "email": "a@b.c"})))
+            .await
+            .unwrap();
+        assert_eq!(out[0].get_field("id"), Some(&json!(7)));
+        assert_eq!(out[0].get_field("score"), Some(&json!(1.5)));
+        assert_eq!(out[0].get_field("active"), Some(&json!(true)));
+    }
+
+    #[tokio::test]
+    async fn strict_reports_every_violation() {
+        let transform = SchemaEnforceTransform::new(schema(), EnforceMode::Strict);
+        match transform.transform(bad_record()).await {
+            Err(PipelineError::Schema(report)) => {
+                assert!(report.starts_with("3 field(s) violate the schema"), "{}", report);
+                assert!(report.contains("'id': cannot cast"), "{}", report);
+                assert!(report.contains("'score': cannot cast"), "{}", report);
+                assert!(report.contains("'email': required field is missing"), "{}", report);
+            }
+            other => panic!("expected a schema error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn lenient_nulls_the_violating_fields() {
+        let transform = SchemaEnforceTransform::new(schema(), EnforceMode::Lenient);
+        let out = transform.transform(bad_record()).await.unwrap();
+        assert_eq!(out[0].get_field("id"), Some(&Value::Null));
+        assert_eq!(out[0].get_field("score"), Some(&Value::Null));
+        assert_eq!(out[0].get_field("email"), Some(&Value::Null));
+        assert_eq!(out[0].get_field("active"), Some(&json!(true)));
+    }
+
+    #[tokio::test]
+    async fn dead_letter_keeps_the_record_with_the_report() {
+        let transform = SchemaEnforceTransform::new(schema(), EnforceMode::DeadLetter);
+        let out = transform.transform(bad_record()).await.unwrap();
+        assert_eq!(out[0].data, bad_record().data);
+        let report = out[0].get_metadata("error").unwrap();
+        assert!(report.starts_with("3 field(s) violate the schema"), "{}", report);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::core::{DataType, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Emits one record per element of an array field, with the field replaced
+/// by that element. A record whose array is empty produces no output.
+///
+/// Records where the field is missing or not an array are passed through
+/// unchanged, unless `with_require_array` makes them an error.
+pub struct ExplodeTransform {
+    field: String,
+    require_array: bool,
+}
+
+impl ExplodeTransform {
+    pub fn new(field: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            require_array: false,
+        }
+    }
+
+    pub fn with_require_array(mut self, require_array: bool) -> Self {
+        self.require_array = require_array;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for ExplodeTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        // Taking the array in place keeps the field's position for the
+        // exploded records.
+        let items = match record.data.get_mut(&self.field) {
+            Some(Value::Array(items)) => std::mem::take(items),
+            other => {
+                if self.require_array {
+                    return Err(PipelineError::Transform(format!(
+                        "Field '{}' is not an array: {}",
+                        self.field,
+                        other.map_or(Value::Null, |value| value.clone())
+                    )));
+                }
+                return Ok(vec![record]);
+            }
+        };
+
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let mut exploded = record.clone();
+                exploded.data.insert(self.field.clone(), item);
+                exploded
+            })
+            .collect())
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if field.name == self.field
+                && let DataType::Array(ref element_type) = field.data_type
+            {
+                field.data_type = (**element_type).clone();
+            }
+        }
+        Ok(schema)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+
+/// Keeps records for which `predicate` returns `true` and drops the rest.
+pub struct FilterTransform {
+    predicate: Box<dyn Fn(&Record) -> bool + Send + Sync>,
+}
+
+impl FilterTransform {
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&Record) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            predicate: Box::new(predicate),
+        }
+    }
+}
+
+#[async_trait]
+impl Transform for FilterTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        if (self.predicate)(&record) {
+            Ok(vec![record])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
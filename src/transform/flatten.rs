@@ -0,0 +1,266 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+/// Recursively flattens `value` into `out` under `key`, joining nested keys
+/// with `separator`. Arrays are expanded by index only when `flatten_arrays`
+/// is set. Two paths producing the same key is an error.
+pub(crate) fn flatten_value(
+    key: String,
+    value: Value,
+    separator: &str,
+    flatten_arrays: bool,
+    out: &mut IndexMap<String, Value>,
+) -> Result<()> {
+    match value {
+        Value::Object(obj) if !obj.is_empty() => {
+            for (child, value) in obj {
+                flatten_value(format!("{}{}{}", key, separator, child), value, separator, flatten_arrays, out)?;
+            }
+            Ok(())
+        }
+        Value::Array(items) if flatten_arrays && !items.is_empty() => {
+            for (i, value) in items.into_iter().enumerate() {
+                flatten_value(format!("{}{}{}", key, separator, i), value, separator, flatten_arrays, out)?;
+            }
+            Ok(())
+        }
+        value => {
+            if out.contains_key(&key) {
+                return Err(PipelineError::Transform(
+                    format!("Flattening produced duplicate field '{}'", key)
+                ));
+            }
+            out.insert(key, value);
+            Ok(())
+        }
+    }
+}
+
+/// Flattens nested objects, joining keys with a separator, and prepends
+/// `prefix` to every resulting key, so `{"addr":{"city":..}}` with prefix `a_`
+/// becomes `a_addr_city`. Useful to keep provenance when merging sources.
+pub struct PrefixFlattenTransform {
+    prefix: String,
+    separator: String,
+    flatten_arrays: bool,
+    sample: Vec<Record>,
+}
+
+impl PrefixFlattenTransform {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            separator: "_".to_string(),
+            flatten_arrays: false,
+            sample: Vec::new(),
+        }
+    }
+
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    pub fn flatten_arrays(mut self, flatten_arrays: bool) -> Self {
+        self.flatten_arrays = flatten_arrays;
+        self
+    }
+
+    /// Input records to learn nested keys from. A schema does not describe
+    /// what is inside a `Json` field, so `get_output_schema` expands such
+    /// fields, and arrays when flattening them, into the flat fields these
+    /// records produce, typed as [`Schema::infer_from_records`] types them.
+    pub fn with_sample(mut self, sample: Vec<Record>) -> Self {
+        self.sample = sample;
+        self
+    }
+
+    fn flatten(&self, data: IndexMap<String, Value>) -> Result<IndexMap<String, Value>> {
+        let mut flat = IndexMap::new();
+        for (key, value) in data {
+            let key = format!("{}{}", self.prefix, key);
+            flatten_value(key, value, &self.separator, self.flatten_arrays, &mut flat)?;
+        }
+        Ok(flat)
+    }
+}
+
+#[async_trait]
+impl Transform for PrefixFlattenTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        record.data = self.flatten(std::mem::take(&mut record.data))?;
+        Ok(vec![record])
+    }
+
+    /// Renames every field with the prefix and expands nested fields into
+    /// those found in the sample; a nested field the sample has nothing
+    /// inside stays as it is. Expanded fields are nullable, since other
+    /// records may not have them, and two fields flattening to the same
+    /// name is an error.
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let sampled = self
+            .sample
+            .iter()
+            .map(|record| self.flatten(record.data.clone()).map(Record::with_data))
+            .collect::<Result<Vec<_>>>()?;
+        let sampled = Schema::infer_from_records(&sampled);
+
+        let mut fields: IndexMap<String, Field> = IndexMap::new();
+        for field in &input_schema.fields {
+            let name = format!("{}{}", self.prefix, field.name);
+            let nested = match field.data_type {
+                DataType::Json => true,
+                DataType::Array(_) => self.flatten_arrays,
+                _ => false,
+            };
+            let child_prefix = format!("{}{}", name, self.separator);
+            let expanded: Vec<Field> = if nested {
+                sampled
+                    .fields
+                    .iter()
+                    .filter(|f| f.name.starts_with(&child_prefix))
+                    .map(|f| Field { nullable: true, ..f.clone() })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let flat = if expanded.is_empty() {
+                vec![Field { name, ..field.clone() }]
+            } else {
+                expanded
+            };
+
+            for field in flat {
+                if fields.contains_key(&field.name) {
+                    return Err(PipelineError::Transform(format!(
+                        "Flattening produced duplicate field '{}'",
+                        field.name
+                    )));
+                }
+                fields.insert(field.name.clone(), field);
+            }
+        }
+        Ok(Schema::new(fields.into_values().collect()))
+    }
+}
+
+/// Flattens nested objects into keys joined with `separator`, so
+/// `{"addr":{"city":"NYC"}}` becomes `{"addr.city":"NYC"}` with `.`. Arrays
+/// are kept as values unless `flatten_arrays` expands them by index, as in
+/// `items.0.id`. Two paths flattening to the same key is an error.
+pub struct FlattenTransform {
+    inner: PrefixFlattenTransform,
+}
+
+impl FlattenTransform {
+    pub fn new(separator: &str) -> Self {
+        Self {
+            inner: PrefixFlattenTransform::new("").with_separator(separator),
+        }
+    }
+
+    pub fn flatten_arrays(mut self, flatten_arrays: bool) -> Self {
+        self.inner = self.inner.flatten_arrays(flatten_arrays);
+        self
+    }
+
+    /// See [`PrefixFlattenTransform::with_sample`].
+    pub fn with_sample(mut self, sample: Vec<Record>) -> Self {
+        self.inner = self.inner.with_sample(sample);
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for FlattenTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        self.inner.transform(record).await
+    }
+
+    /// The flat field list, with nested fields expanded as found in the
+    /// sample given to `with_sample`.
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        self.inner.get_output_schema(input_schema).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn flattens_nested_objects() {
+        let transform = FlattenTransform::new(".");
+        let output = transform
+            .transform(record(json!({"id": 1, "addr": {"city": "NYC", "geo": {"lat": 40.7}}, "tags": ["a"]})))
+            .await
+            .unwrap();
+        let keys: Vec<_> = output[0].data.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["id", "addr.city", "addr.geo.lat", "tags"]);
+        assert_eq!(output[0].get_field("addr.city"), Some(&json!("NYC")));
+        assert_eq!(output[0].get_field("tags"), Some(&json!(["a"])));
+    }
+
+    #[tokio::test]
+    async fn prefixes_every_flattened_key() {
+        let transform = PrefixFlattenTransform::new("a_");
+        let output = transform
+            .transform(record(json!({"id": 1, "addr": {"city": "NYC", "geo": {"lat": 40.7}}})))
+            .await
+            .unwrap();
+        assert_eq!(
+            output[0].data,
+            record(json!({"a_id": 1, "a_addr_city": "NYC", "a_addr_geo_lat": 40.7})).data
+        );
+
+        let schema = transform
+            .get_output_schema(&Schema::infer_from_records(&[record(json!({"id": 1, "name": "x"}))]))
+            .await
+            .unwrap();
+        assert_eq!(schema.field_names(), vec!["a_id", "a_name"]);
+    }
+
+    #[tokio::test]
+    async fn flattens_arrays_by_index() {
+        let transform = FlattenTransform::new(".").flatten_arrays(true);
+        let output = transform.transform(record(json!({"items": [{"id": 1}, {"id": 2}]}))).await.unwrap();
+        assert_eq!(output[0].get_field("items.0.id"), Some(&json!(1)));
+        assert_eq!(output[0].get_field("items.1.id"), Some(&json!(2)));
+    }
+
+    #[tokio::test]
+    async fn collisions_are_errors() {
+        let transform = FlattenTransform::new(".");
+        let err = transform.transform(record(json!({"a": {"b": 1}, "a.b": 2}))).await.unwrap_err();
+        assert!(matches!(err, PipelineError::Transform(_)));
+    }
+
+    #[tokio::test]
+    async fn output_schema_expands_sampled_fields() {
+        let sample = vec![
+            record(json!({"id": 1, "addr": {"city": "NYC", "zip": 10001}})),
+            record(json!({"id": 2, "addr": {"city": "LA"}})),
+        ];
+        let input = Schema::infer_from_records(&sample);
+        let transform = FlattenTransform::new(".").with_sample(sample);
+        let schema = transform.get_output_schema(&input).await.unwrap();
+
+        assert_eq!(schema.field_names(), vec!["id", "addr.city", "addr.zip"]);
+        assert_eq!(schema.get_field("id").map(|f| &f.data_type), Some(&DataType::Integer));
+        assert_eq!(schema.get_field("addr.zip").map(|f| (&f.data_type, f.nullable)), Some((&DataType::Integer, true)));
+    }
+
+    #[tokio::test]
+    async fn output_schema_reports_collisions() {
+        // The sample alone is fine; the clash is with a field of the schema.
+        let sample = vec![record(json!({"a": {"b": 1}}))];
+        let input = Schema::infer_from_records(&[record(json!({"a": {"b": 1}, "a.b": 2}))]);
+        let transform = FlattenTransform::new(".").with_sample(sample);
+        let err = transform.get_output_schema(&input).await.unwrap_err();
+        assert!(matches!(err, PipelineError::Transform(ref m) if m.contains("'a.b'")), "{}", err);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::core::{timestamp_millis, DataType, ErrorPolicy, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::Value;
+
+/// Formats tried after the configured ones. Slashed dates are read
+/// month-first; configure `%d/%m/%Y` to read them day-first instead.
+const DEFAULT_FORMATS: &[&str] = &[
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d",
+    "%d.%m.%Y",
+    "%d %b %Y",
+    "%b %d, %Y",
+    "%a, %d %b %Y %H:%M:%S %z",
+];
+
+/// Parses a datetime field whose values come in mixed formats and rewrites
+/// it as an ISO 8601 UTC string such as `2024-01-15T08:30:00Z`.
+///
+/// Each string is tried against the configured `chrono` formats in order,
+/// then RFC 3339 and the other ISO forms, then a list of common formats
+/// (unless disabled). Numbers, and strings of digits, are read as Unix
+/// epoch seconds, milliseconds, microseconds or nanoseconds depending on
+/// their magnitude. Formats without a UTC offset are taken to be UTC.
+/// Values that no format matches are handled by the [`ErrorPolicy`].
+pub struct FlexibleDateTransform {
+    field: String,
+    output_field: Option<String>,
+    formats: Vec<String>,
+    use_defaults: bool,
+    on_error: ErrorPolicy,
+}
+
+impl FlexibleDateTransform {
+    pub fn new(field: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            output_field: None,
+            formats: Vec::new(),
+            use_defaults: true,
+            on_error: ErrorPolicy::Fail,
+        }
+    }
+
+    /// Sets `chrono` strftime formats to try, in order, before any other.
+    pub fn with_formats(mut self, formats: Vec<String>) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Tries only the configured formats, ISO 8601 and epoch values.
+    pub fn without_default_formats(mut self) -> Self {
+        self.use_defaults = false;
+        self
+    }
+
+    /// Writes the normalized value to `output_field` instead of in place.
+    pub fn with_output_field(mut self, output_field: &str) -> Self {
+        self.output_field = Some(output_field.to_string());
+        self
+    }
+
+    pub fn with_error_policy(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    fn output_field(&self) -> &str {
+        self.output_field.as_deref().unwrap_or(&self.field)
+    }
+
+    fn parse(&self, value: &Value) -> Option<DateTime<Utc>> {
+        match value {
+            Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)).and_then(from_epoch),
+            Value::String(s) => {
+                let s = s.trim();
+                if let Some(dt) = self.formats.iter().find_map(|format| parse_with(s, format)) {
+                    return Some(dt);
+                }
+                if let Some(millis) = timestamp_millis(&Value::String(s.to_string())) {
+                    return DateTime::from_timestamp_millis(millis);
+                }
+                if self.use_defaults
+                    && let Some(dt) = DEFAULT_FORMATS.iter().find_map(|format| parse_with(s, format))
+                {
+                    return Some(dt);
+                }
+                if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+                    return s.parse().ok().and_then(from_epoch);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+fn parse_with(s: &str, format: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_str(s, format) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(s, format)
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Picks the epoch unit that puts `n` between 1973 and 5138.
+fn from_epoch(n: i64) -> Option<DateTime<Utc>> {
+    match n.unsigned_abs() {
+        0..100_000_000_000 => DateTime::from_timestamp(n, 0),
+        100_000_000_000..100_000_000_000_000 => DateTime::from_timestamp_millis(n),
+        100_000_000_000_000..100_000_000_000_000_000 => DateTime::from_timestamp_micros(n),
+        _ => Some(DateTime::from_timestamp_nanos(n)),
+    }
+}
+
+#[async_trait]
+impl Transform for FlexibleDateTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let Some(value) = record.data.get(&self.field).filter(|v| !v.is_null()) else {
+            return Ok(vec![record]);
+        };
+
+        let normalized = match (self.parse(value), self.on_error) {
+            (Some(dt), _) => Value::String(dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+            (None, ErrorPolicy::NullFill) => Value::Null,
+            (None, policy) => {
+                let error = format!("Field '{}' has unrecognized datetime {}", self.field, value);
+                if policy == ErrorPolicy::Fail {
+                    return Err(PipelineError::Transform(error));
+                }
+                record.set_metadata("error".to_string(), error);
+                return Ok(vec![record]);
+            }
+        };
+
+        record.set_field(self.output_field().to_string(), normalized);
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        let nullable = schema
+            .fields
+            .iter()
+            .find(|f| f.name == self.field)
+            .is_none_or(|f| f.nullable)
+            || self.on_error == ErrorPolicy::NullFill;
+        schema.fields.retain(|f| f.name != self.output_field());
+        schema.fields.push(Field {
+            name: self.output_field().to_string(),
+            data_type: DataType::Timestamp,
+            nullable,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dated(value: Value) -> Record {
+        let mut record = Record::new();
+        record.set_field("when".to_string(), value);
+        record
+    }
+
+    #[tokio::test]
+    async fn parses_a_column_of_mixed_formats() {
+        let transform = FlexibleDateTransform::new("when");
+        for (input, expected) in [
+            (json!("01/15/2024"), "2024-01-15T00:00:00Z"),
+            (json!("01/15/2024 08:30"), "2024-01-15T08:30:00Z"),
+            (json!("2024-01-15T08:30:00Z"), "2024-01-15T08:30:00Z"),
+            (json!("2024-01-15T10:30:00+02:00"), "2024-01-15T08:30:00Z"),
+            (json!("2024-01-15"), "2024-01-15T00:00:00Z"),
+            (json!(1705307400), "2024-01-15T08:30:00Z"),
+            (json!("1705307400"), "2024-01-15T08:30:00Z"),
+            (json!(1705307400123i64), "2024-01-15T08:30:00.123Z"),
+        ] {
+            let out = transform.transform(dated(input.clone())).await.unwrap();
+            assert_eq!(out[0].get_field("when"), Some(&json!(expected)), "{}", input);
+        }
+    }
+
+    #[tokio::test]
+    async fn configured_formats_take_precedence() {
+        let transform = FlexibleDateTransform::new("when").with_formats(vec!["%d/%m/%Y".to_string()]);
+        let out = transform.transform(dated(json!("01/02/2024"))).await.unwrap();
+        assert_eq!(out[0].get_field("when"), Some(&json!("2024-02-01T00:00:00Z")));
+    }
+
+    #[tokio::test]
+    async fn only_unparseable_values_are_dead_lettered() {
+        let transform = FlexibleDateTransform::new("when").with_error_policy(ErrorPolicy::DeadLetter);
+        let out = transform.transform(dated(json!("next tuesday"))).await.unwrap();
+        assert_eq!(out[0].get_field("when"), Some(&json!("next tuesday")));
+        assert!(out[0].get_metadata("error").is_some());
+
+        assert!(FlexibleDateTransform::new("when").transform(dated(json!("next tuesday"))).await.is_err());
+    }
+}
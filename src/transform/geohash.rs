@@ -0,0 +1,168 @@
+use crate::core::{DataType, ErrorPolicy, Field, PipelineError, Record, Result, Schema, Transform};
+use crate::transform::cast::cast_value;
+use async_trait::async_trait;
+use serde_json::Value;
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+fn encode(latitude: f64, longitude: f64, precision: usize) -> String {
+    let mut lat_range = (-90.0, 90.0);
+    let mut lon_range = (-180.0, 180.0);
+    let mut hash = String::with_capacity(precision);
+    let mut even_bit = true;
+    let mut bits = 0;
+    let mut index = 0;
+
+    while hash.len() < precision {
+        let (range, value) = if even_bit {
+            (&mut lon_range, longitude)
+        } else {
+            (&mut lat_range, latitude)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        index <<= 1;
+        if value >= mid {
+            index |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even_bit = !even_bit;
+
+        bits += 1;
+        if bits == 5 {
+            hash.push(BASE32[index] as char);
+            bits = 0;
+            index = 0;
+        }
+    }
+    hash
+}
+
+/// Computes a geohash of the given precision (1-12 characters) from
+/// latitude/longitude fields, which may be numbers or numeric strings.
+pub struct GeohashTransform {
+    latitude_field: String,
+    longitude_field: String,
+    precision: usize,
+    output_field: String,
+    on_error: ErrorPolicy,
+}
+
+impl GeohashTransform {
+    pub fn new(latitude_field: &str, longitude_field: &str, precision: usize) -> Self {
+        Self {
+            latitude_field: latitude_field.to_string(),
+            longitude_field: longitude_field.to_string(),
+            precision: precision.clamp(1, 12),
+            output_field: "geohash".to_string(),
+            on_error: ErrorPolicy::Fail,
+        }
+    }
+
+    pub fn with_output_field(mut self, output_field: &str) -> Self {
+        self.output_field = output_field.to_string();
+        self
+    }
+
+    pub fn with_error_policy(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    fn coordinate(&self, record: &Record, field: &str, limit: f64) -> Result<f64> {
+        let value = record.get_field(field).unwrap_or(&Value::Null);
+        match cast_value(value, &DataType::Float) {
+            Some(Value::Number(n)) => match n.as_f64() {
+                Some(c) if c.abs() <= limit => Ok(c),
+                _ => Err(PipelineError::Transform(
+                    format!("Coordinate '{}' is out of range: {}", field, n)
+                )),
+            },
+            _ => Err(PipelineError::Transform(
+                format!("Coordinate '{}' is missing or not numeric: {}", field, value)
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Transform for GeohashTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let coordinates = self
+            .coordinate(&record, &self.latitude_field, 90.0)
+            .and_then(|lat| Ok((lat, self.coordinate(&record, &self.longitude_field, 180.0)?)));
+
+        match (coordinates, self.on_error) {
+            (Ok((lat, lon)), _) => {
+                record.set_field(self.output_field.clone(), Value::String(encode(lat, lon, self.precision)));
+            }
+            (Err(e), ErrorPolicy::Fail) => return Err(e),
+            (Err(_), ErrorPolicy::NullFill) => record.set_field(self.output_field.clone(), Value::Null),
+            (Err(e), ErrorPolicy::DeadLetter) => record.set_metadata("error".to_string(), e.to_string()),
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|f| f.name != self.output_field);
+        schema.fields.push(Field {
+            name: self.output_field.clone(),
+            data_type: DataType::String,
+            nullable: self.on_error != ErrorPolicy::Fail,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(lat: Value, lon: Value) -> Record {
+        let mut record = Record::new();
+        record.set_field("lat".to_string(), lat);
+        record.set_field("lon".to_string(), lon);
+        record
+    }
+
+    async fn geohash(transform: &GeohashTransform, lat: Value, lon: Value) -> Result<Record> {
+        Ok(transform.transform(record(lat, lon)).await?.remove(0))
+    }
+
+    #[test]
+    fn encodes_known_coordinates() {
+        assert_eq!(encode(57.64911, 10.40744, 11), "u4pruydqqvj");
+        assert_eq!(encode(42.605, -5.603, 5), "ezs42");
+    }
+
+    #[tokio::test]
+    async fn writes_the_geohash_at_the_given_precision() {
+        let transform = GeohashTransform::new("lat", "lon", 5);
+        let out = geohash(&transform, json!(42.605), json!("-5.603")).await.unwrap();
+        assert_eq!(out.get_field("geohash"), Some(&json!("ezs42")));
+
+        let transform = GeohashTransform::new("lat", "lon", 11).with_output_field("cell");
+        let out = geohash(&transform, json!(57.64911), json!(10.40744)).await.unwrap();
+        assert_eq!(out.get_field("cell"), Some(&json!("u4pruydqqvj")));
+    }
+
+    #[tokio::test]
+    async fn invalid_coordinates_follow_the_error_policy() {
+        let transform = GeohashTransform::new("lat", "lon", 5);
+        assert!(geohash(&transform, json!(91.0), json!(0.0)).await.is_err());
+        assert!(geohash(&transform, json!("north"), json!(0.0)).await.is_err());
+
+        let transform = GeohashTransform::new("lat", "lon", 5).with_error_policy(ErrorPolicy::NullFill);
+        let out = geohash(&transform, Value::Null, json!(0.0)).await.unwrap();
+        assert_eq!(out.get_field("geohash"), Some(&Value::Null));
+
+        let transform = GeohashTransform::new("lat", "lon", 5).with_error_policy(ErrorPolicy::DeadLetter);
+        let out = geohash(&transform, json!(0.0), json!(-181.0)).await.unwrap();
+        assert_eq!(out.get_field("geohash"), None);
+        assert!(out.get_metadata("error").unwrap().contains("'lon' is out of range"));
+    }
+}
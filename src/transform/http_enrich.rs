@@ -0,0 +1,264 @@
+use crate::core::{DataType, ErrorPolicy, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use lru::LruCache;
+use serde_json::Value;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use tokio::sync::Semaphore;
+
+/// Enriches each record with fields fetched from a JSON API.
+///
+/// The URL is built from a template whose `{field}` placeholders are
+/// replaced by the record's (URL-encoded) values. Responses are cached in an
+/// LRU keyed by the resolved URL, and at most `max_concurrency` requests
+/// are in flight at once across all records.
+pub struct HttpEnrichTransform {
+    client: reqwest::Client,
+    url_template: String,
+    selections: Vec<(String, String)>,
+    on_error: ErrorPolicy,
+    cache: Mutex<LruCache<String, Value>>,
+    permits: Semaphore,
+}
+
+impl HttpEnrichTransform {
+    pub fn new(url_template: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url_template: url_template.to_string(),
+            selections: Vec::new(),
+            on_error: ErrorPolicy::Fail,
+            cache: Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap())),
+            permits: Semaphore::new(8),
+        }
+    }
+
+    /// Copies the response value at JSON pointer `pointer` into
+    /// `target_field`. Without selections every top-level response key is
+    /// merged into the record.
+    pub fn select(mut self, pointer: &str, target_field: &str) -> Self {
+        self.selections.push((pointer.to_string(), target_field.to_string()));
+        self
+    }
+
+    pub fn with_cache_size(self, size: usize) -> Self {
+        let size = NonZeroUsize::new(size).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            cache: Mutex::new(LruCache::new(size)),
+            ..self
+        }
+    }
+
+    pub fn with_max_concurrency(self, max_concurrency: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrency.max(1)),
+            ..self
+        }
+    }
+
+    pub fn with_error_policy(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    fn render_url(&self, record: &Record) -> Result<String> {
+        let mut url = String::with_capacity(self.url_template.len());
+        let mut rest = self.url_template.as_str();
+
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').map(|end| start + end).ok_or_else(|| {
+                PipelineError::Config(format!("Unclosed placeholder in URL template '{}'", self.url_template))
+            })?;
+            let name = &rest[start + 1..end];
+            let value = match record.get_field(name) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => {
+                    return Err(PipelineError::Transform(
+                        format!("Record has no value for URL placeholder '{}'", name)
+                    ));
+                }
+                Some(other) => other.to_string(),
+            };
+
+            url.push_str(&rest[..start]);
+            url.push_str(&percent_encode(&value));
+            rest = &rest[end + 1..];
+        }
+        url.push_str(rest);
+        Ok(url)
+    }
+
+    async fn lookup(&self, url: &str) -> Result<Value> {
+        if let Some(cached) = self.cache.lock().unwrap().get(url) {
+            return Ok(cached.clone());
+        }
+
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|e| PipelineError::Transform(e.to_string()))?;
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| PipelineError::Transform(format!("Lookup of {} failed: {}", url, e)))?;
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| PipelineError::Transform(format!("Lookup of {} returned invalid JSON: {}", url, e)))?;
+
+        self.cache.lock().unwrap().put(url.to_string(), body.clone());
+        Ok(body)
+    }
+
+    fn merge(&self, record: &mut Record, response: Value) {
+        if self.selections.is_empty() {
+            if let Value::Object(obj) = response {
+                record.data.extend(obj);
+            }
+            return;
+        }
+        for (pointer, target) in &self.selections {
+            let value = response.pointer(pointer).cloned().unwrap_or(Value::Null);
+            record.set_field(target.clone(), value);
+        }
+    }
+}
+
+/// Percent-encodes everything except RFC 3986 unreserved characters, so the
+/// value is safe in both path segments and query strings.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[async_trait]
+impl Transform for HttpEnrichTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let response = match self.render_url(&record) {
+            Ok(url) => self.lookup(&url).await,
+            Err(e) => Err(e),
+        };
+
+        match (response, self.on_error) {
+            (Ok(response), _) => self.merge(&mut record, response),
+            (Err(e), ErrorPolicy::Fail) => return Err(e),
+            (Err(_), ErrorPolicy::NullFill) => {
+                for (_, target) in &self.selections {
+                    record.set_field(target.clone(), Value::Null);
+                }
+            }
+            (Err(e), ErrorPolicy::DeadLetter) => {
+                record.set_metadata("error".to_string(), e.to_string());
+            }
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for (_, target) in &self.selections {
+            if schema.get_field(target).is_none() {
+                schema.fields.push(Field {
+                    name: target.clone(),
+                    data_type: DataType::Json,
+                    nullable: true,
+                    description: None,
+                });
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve, MockRequest};
+    use serde_json::json;
+
+    /// Serves `/users/<id>` for ids 1 and 2; anything else is a 404.
+    fn users(request: &MockRequest) -> (u16, String) {
+        let name = match request.path.as_str() {
+            "/users/1" => "alice",
+            "/users/2" => "bob",
+            _ => return (404, String::new()),
+        };
+        (200, json!({"profile": {"name": name, "tier": "gold"}, "id": 0}).to_string())
+    }
+
+    fn record(user_id: Value) -> Record {
+        let mut record = Record::new();
+        record.set_field("user_id".to_string(), user_id);
+        record
+    }
+
+    #[tokio::test]
+    async fn enriches_records_and_caches_lookups() {
+        let (base, requests) = serve(users).await;
+        let transform = HttpEnrichTransform::new(&format!("{}/users/{{user_id}}", base))
+            .select("/profile/name", "name")
+            .select("/profile/missing", "missing");
+
+        let mut names = Vec::new();
+        for user_id in [1, 2, 1, 1] {
+            let out = transform.transform(record(json!(user_id))).await.unwrap();
+            assert_eq!(out[0].get_field("missing"), Some(&Value::Null));
+            names.push(out[0].get_field("name").unwrap().clone());
+        }
+        assert_eq!(names, [json!("alice"), json!("bob"), json!("alice"), json!("alice")]);
+
+        let requests = requests.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/users/1", "/users/2"]);
+        assert!(requests.iter().all(|r| r.method == "GET"));
+    }
+
+    #[tokio::test]
+    async fn merges_the_whole_response_without_selections() {
+        let (base, _) = serve(users).await;
+        let transform = HttpEnrichTransform::new(&format!("{}/users/{{user_id}}", base));
+        let out = transform.transform(record(json!("2"))).await.unwrap();
+        assert_eq!(out[0].get_field("user_id"), Some(&json!("2")));
+        assert_eq!(out[0].get_field("profile"), Some(&json!({"name": "bob", "tier": "gold"})));
+    }
+
+    #[tokio::test]
+    async fn placeholders_are_percent_encoded() {
+        let (base, requests) = serve(users).await;
+        let transform = HttpEnrichTransform::new(&format!("{}/users/{{user_id}}", base))
+            .with_error_policy(ErrorPolicy::NullFill);
+        transform.transform(record(json!("a b/c"))).await.unwrap();
+        assert_eq!(requests.lock().unwrap()[0].path, "/users/a%20b%2Fc");
+    }
+
+    #[tokio::test]
+    async fn failed_lookups_follow_the_error_policy() {
+        let (base, _) = serve(users).await;
+        let template = format!("{}/users/{{user_id}}", base);
+        let transform = |policy| HttpEnrichTransform::new(&template).select("/profile/name", "name").with_error_policy(policy);
+
+        let result = transform(ErrorPolicy::Fail).transform(record(json!(3))).await;
+        assert!(matches!(result, Err(PipelineError::Transform(_))));
+
+        let out = transform(ErrorPolicy::NullFill).transform(record(json!(3))).await.unwrap();
+        assert_eq!(out[0].get_field("name"), Some(&Value::Null));
+
+        let out = transform(ErrorPolicy::DeadLetter).transform(record(json!(3))).await.unwrap();
+        assert_eq!(out[0].get_field("name"), None);
+        assert!(out[0].get_metadata("error").unwrap().contains("404"));
+
+        // A record missing the placeholder field fails the same way.
+        let out = transform(ErrorPolicy::DeadLetter).transform(Record::new()).await.unwrap();
+        assert!(out[0].get_metadata("error").unwrap().contains("'user_id'"));
+    }
+}
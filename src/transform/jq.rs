@@ -0,0 +1,118 @@
+use crate::core::{PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use jaq_core::load::{Arena, File, Loader};
+use jaq_core::{data, unwrap_valr, Compiler, Ctx, Vars};
+use jaq_json::Val;
+use serde_json::Value;
+
+type Filter = jaq_core::Filter<data::JustLut<Val>>;
+
+/// Runs a jq program, via `jaq`, against each record's data. Every object
+/// the program outputs becomes a record carrying the input's metadata, so a
+/// program can drop (`select(...)`), reshape or fan out (`.items[]`)
+/// records. Outputs that are not objects are an error.
+///
+/// The output schema cannot be derived from the program and is left as the
+/// input schema.
+pub struct JqTransform {
+    program: String,
+    filter: Filter,
+}
+
+impl JqTransform {
+    pub fn new(program: &str) -> Result<Self> {
+        let defs = jaq_core::defs().chain(jaq_std::defs()).chain(jaq_json::defs());
+        let funs = jaq_core::funs().chain(jaq_std::funs()).chain(jaq_json::funs());
+
+        let arena = Arena::default();
+        let modules = Loader::new(defs)
+            .load(&arena, File { code: program, path: () })
+            .map_err(|errors| invalid_program(program, &errors))?;
+        let filter = Compiler::default()
+            .with_funs(funs)
+            .compile(modules)
+            .map_err(|errors| invalid_program(program, &errors))?;
+
+        Ok(Self {
+            program: program.to_string(),
+            filter,
+        })
+    }
+}
+
+fn invalid_program<F, E: std::fmt::Debug>(program: &str, errors: &[(F, E)]) -> PipelineError {
+    let errors: Vec<String> = errors.iter().map(|(_, e)| format!("{:?}", e)).collect();
+    PipelineError::Config(format!("Invalid jq program '{}': {}", program, errors.join("; ")))
+}
+
+#[async_trait]
+impl Transform for JqTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        let input = serde_json::to_vec(&record.data)?;
+        let input = jaq_json::read::parse_single(&input)
+            .map_err(|e| PipelineError::Transform(format!("Cannot pass record to jq: {}", e)))?;
+
+        let ctx = Ctx::<data::JustLut<Val>>::new(&self.filter.lut, Vars::new([]));
+        let mut records = Vec::new();
+        for output in self.filter.id.run((ctx, input)).map(unwrap_valr) {
+            let output = output.map_err(|e| {
+                PipelineError::Transform(format!("jq program '{}' failed: {}", self.program, e))
+            })?;
+            match serde_json::from_str::<Value>(&output.to_string())? {
+                Value::Object(obj) => {
+                    let mut output = Record::with_data(obj.into_iter().collect());
+                    output.metadata = record.metadata.clone();
+                    records.push(output);
+                }
+                other => {
+                    return Err(PipelineError::Transform(format!(
+                        "jq program '{}' produced a non-object: {}",
+                        self.program, other
+                    )));
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    async fn run(transform: &JqTransform, input: Value) -> Result<Vec<Value>> {
+        let outputs = transform.transform(record(input)).await?;
+        Ok(outputs
+            .into_iter()
+            .map(|r| Value::Object(r.data.into_iter().collect()))
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn selects_and_renames_fields() {
+        let transform = JqTransform::new("select(.active) | {user: .name, years: .age}").unwrap();
+
+        assert_eq!(
+            run(&transform, json!({"name": "ada", "age": 36, "active": true, "email": "a@x"})).await.unwrap(),
+            vec![json!({"user": "ada", "years": 36})]
+        );
+        assert!(run(&transform, json!({"name": "bob", "age": 20, "active": false})).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn program_errors_surface_as_transform_errors() {
+        assert!(matches!(JqTransform::new("{user: ."), Err(PipelineError::Config(_))));
+
+        let transform = JqTransform::new(".name").unwrap();
+        assert!(matches!(run(&transform, json!({"name": "ada"})).await, Err(PipelineError::Transform(_))));
+
+        let transform = JqTransform::new("error(\"boom\")").unwrap();
+        assert!(matches!(run(&transform, json!({})).await, Err(PipelineError::Transform(_))));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Passes the first `limit` records through and drops the rest.
+///
+/// The source is still read to the end, since a transform cannot stop it;
+/// [`Pipeline::run_limited`](crate::pipeline::Pipeline::run_limited) stops
+/// reading instead. Use this one to limit a single branch, or the output of
+/// a transform that filters or fans out.
+pub struct LimitTransform {
+    limit: usize,
+    seen: AtomicUsize,
+}
+
+impl LimitTransform {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            seen: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl Transform for LimitTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        if self.seen.fetch_add(1, Ordering::Relaxed) < self.limit {
+            Ok(vec![record])
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn passes_the_first_records_then_drops_the_rest() {
+        let transform = LimitTransform::new(2);
+        let mut passed = Vec::new();
+        for id in 0..5 {
+            passed.push(transform.transform(record(json!({"id": id}))).await.unwrap().len());
+        }
+        assert_eq!(passed, [1, 1, 0, 0, 0]);
+    }
+}
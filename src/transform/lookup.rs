@@ -0,0 +1,207 @@
+use crate::core::{Field, Record, Result, Schema, Source, Transform};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// What happens to a record whose key has no match in the reference data.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum JoinKind {
+    /// Pass the record through with null for each reference field it lacks.
+    #[default]
+    Left,
+    /// Drop the record.
+    Inner,
+}
+
+/// Matches keys as strings, so `1` and `"1"` join; null never matches.
+fn join_key(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Enriches records with the fields of the reference row whose
+/// `key_field` equals the record's, such as product details from a
+/// catalog. The reference fields are set on the record, replacing any of
+/// the same name; when several reference rows share a key, the last one
+/// read wins.
+///
+/// The reference source is read in full by [`LookupTransform::load`] and
+/// kept in memory for the life of the transform, so memory grows with the
+/// size of the reference data. It suits lookup tables of up to a few
+/// hundred thousand rows; join larger datasets in a database.
+pub struct LookupTransform {
+    key_field: String,
+    join_kind: JoinKind,
+    reference_fields: Vec<Field>,
+    rows: HashMap<String, Record>,
+}
+
+impl LookupTransform {
+    /// Reads every row of `reference`, indexing it by `key_field`. Rows
+    /// without a key are skipped.
+    pub async fn load(reference: &dyn Source, key_field: &str) -> Result<Self> {
+        let reference_fields = reference
+            .get_schema()
+            .await?
+            .fields
+            .into_iter()
+            .filter(|field| field.name != key_field)
+            .collect();
+
+        let mut rows = HashMap::new();
+        let mut stream = reference.read().await?;
+        while let Some(row) = stream.next().await {
+            let mut row = row?;
+            if let Some(key) = join_key(row.data.shift_remove(key_field).as_ref()) {
+                rows.insert(key, row);
+            }
+        }
+        reference.close().await?;
+
+        Ok(Self {
+            key_field: key_field.to_string(),
+            join_kind: JoinKind::Left,
+            reference_fields,
+            rows,
+        })
+    }
+
+    pub fn with_join_kind(mut self, join_kind: JoinKind) -> Self {
+        self.join_kind = join_kind;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for LookupTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let matched = join_key(record.get_field(&self.key_field)).and_then(|key| self.rows.get(&key));
+        match (matched, self.join_kind) {
+            (Some(row), _) => {
+                for (name, value) in &row.data {
+                    record.set_field(name.clone(), value.clone());
+                }
+            }
+            (None, JoinKind::Left) => {
+                for field in &self.reference_fields {
+                    record.data.entry(field.name.clone()).or_insert(Value::Null);
+                }
+            }
+            (None, JoinKind::Inner) => return Ok(vec![]),
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in &self.reference_fields {
+            let mut field = field.clone();
+            field.nullable |= self.join_kind == JoinKind::Left;
+            match schema.fields.iter_mut().find(|f| f.name == field.name) {
+                Some(existing) => *existing = field,
+                None => schema.fields.push(field),
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DataType;
+    use crate::source::memory::VecSource;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    async fn catalog() -> LookupTransform {
+        let rows = vec![
+            record(json!({"sku": 1, "name": "kettle", "price": 30})),
+            record(json!({"sku": "2", "name": "toaster", "price": 25})),
+            record(json!({"sku": 2, "name": "toaster v2", "price": 28})),
+            record(json!({"sku": null, "name": "unlisted", "price": 0})),
+        ];
+        LookupTransform::load(&VecSource::new(rows), "sku").await.unwrap()
+    }
+
+    async fn apply(lookup: &LookupTransform, input: Value) -> Vec<Record> {
+        lookup.transform(record(input)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn numbers_and_strings_with_the_same_text_match() {
+        let lookup = catalog().await;
+
+        let output = apply(&lookup, json!({"order": 7, "sku": "1"})).await;
+        assert_eq!(output[0].get_field("name"), Some(&json!("kettle")));
+
+        let output = apply(&lookup, json!({"order": 8, "sku": 1})).await;
+        assert_eq!(output[0].get_field("price"), Some(&json!(30)));
+    }
+
+    #[tokio::test]
+    async fn last_reference_row_wins_for_a_shared_key() {
+        let output = apply(&catalog().await, json!({"sku": "2"})).await;
+        assert_eq!(output[0].get_field("name"), Some(&json!("toaster v2")));
+        assert_eq!(output[0].get_field("price"), Some(&json!(28)));
+    }
+
+    #[tokio::test]
+    async fn left_join_fills_unmatched_records_with_nulls() {
+        let lookup = catalog().await;
+
+        let output = apply(&lookup, json!({"sku": 9, "name": "custom"})).await;
+        assert_eq!(output[0].get_field("name"), Some(&json!("custom")));
+        assert_eq!(output[0].get_field("price"), Some(&Value::Null));
+
+        // A null key never matches, not even the reference row without one.
+        let output = apply(&lookup, json!({"sku": null})).await;
+        assert_eq!(output[0].get_field("name"), Some(&Value::Null));
+    }
+
+    #[tokio::test]
+    async fn inner_join_drops_unmatched_records() {
+        let lookup = catalog().await.with_join_kind(JoinKind::Inner);
+
+        assert!(apply(&lookup, json!({"sku": 9})).await.is_empty());
+        assert_eq!(apply(&lookup, json!({"sku": 1})).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn output_schema_adds_the_reference_fields() {
+        let field = |name: &str, data_type: DataType| Field {
+            name: name.to_string(),
+            data_type,
+            nullable: false,
+            description: None,
+        };
+        let input = Schema::new(vec![field("order", DataType::Integer), field("name", DataType::Json)]);
+        let summary = |schema: Schema| -> Vec<(String, DataType, bool)> {
+            schema.fields.into_iter().map(|f| (f.name, f.data_type, f.nullable)).collect()
+        };
+
+        let left = catalog().await.get_output_schema(&input).await.unwrap();
+        assert_eq!(
+            summary(left),
+            [
+                ("order".to_string(), DataType::Integer, false),
+                ("name".to_string(), DataType::String, true),
+                ("price".to_string(), DataType::Integer, true),
+            ]
+        );
+
+        let inner = catalog().await.with_join_kind(JoinKind::Inner).get_output_schema(&input).await.unwrap();
+        assert_eq!(
+            summary(inner),
+            [
+                ("order".to_string(), DataType::Integer, false),
+                ("name".to_string(), DataType::String, false),
+                ("price".to_string(), DataType::Integer, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::core::{DataType, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+
+type Mapper = Box<dyn Fn(Record) -> Result<Record> + Send + Sync>;
+
+/// Rewrites each record with a closure. An error from the closure fails the
+/// record and is returned as a `PipelineError::Transform`, with any other
+/// variant's message kept.
+pub struct MapTransform {
+    mapper: Mapper,
+    field_types: Vec<(String, DataType)>,
+}
+
+impl MapTransform {
+    pub fn new<F>(mapper: F) -> Self
+    where
+        F: Fn(Record) -> Result<Record> + Send + Sync + 'static,
+    {
+        Self {
+            mapper: Box::new(mapper),
+            field_types: Vec::new(),
+        }
+    }
+
+    /// Rewrites the value of a single field, leaving records without that
+    /// field untouched.
+    pub fn map_field<F>(name: &str, mapper: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value> + Send + Sync + 'static,
+    {
+        let name = name.to_string();
+        Self::new(move |mut record| {
+            if let Some(value) = record.data.get_mut(&name) {
+                *value = mapper(std::mem::take(value))?;
+            }
+            Ok(record)
+        })
+    }
+
+    /// Declares that the mapping changes `field` to `data_type`, for
+    /// `get_output_schema`.
+    pub fn with_field_type(mut self, field: &str, data_type: DataType) -> Self {
+        self.field_types.push((field.to_string(), data_type));
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for MapTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        match (self.mapper)(record) {
+            Ok(record) => Ok(vec![record]),
+            Err(e @ PipelineError::Transform(_)) => Err(e),
+            Err(e) => Err(PipelineError::Transform(e.to_string())),
+        }
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if let Some((_, data_type)) = self.field_types.iter().find(|(name, _)| *name == field.name) {
+                field.data_type = data_type.clone();
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record() -> Record {
+        let mut record = Record::new();
+        record.set_field("email".to_string(), json!("  Jane@Example.COM "));
+        record.set_field("age".to_string(), json!("42"));
+        record
+    }
+
+    #[tokio::test]
+    async fn rewrites_one_field() {
+        let transform = MapTransform::map_field("email", |value| {
+            Ok(json!(value.as_str().unwrap_or_default().trim().to_lowercase()))
+        });
+        let output = transform.transform(record()).await.unwrap();
+        assert_eq!(output[0].get_field("email"), Some(&json!("jane@example.com")));
+        assert_eq!(output[0].get_field("age"), Some(&json!("42")));
+    }
+
+    #[tokio::test]
+    async fn missing_field_is_a_no_op() {
+        let transform = MapTransform::map_field("phone", |_| panic!("not called"));
+        let output = transform.transform(record()).await.unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].data, record().data);
+    }
+
+    #[tokio::test]
+    async fn errors_become_transform_errors() {
+        let transform = MapTransform::map_field("age", |value| {
+            let age: i64 = serde_json::from_value(value)?;
+            Ok(json!(age))
+        });
+        let err = transform.transform(record()).await.unwrap_err();
+        assert!(matches!(err, PipelineError::Transform(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn schema_passes_through_unless_types_are_declared() {
+        let schema = Schema::infer_from_records(&[record()]);
+        let output = MapTransform::new(Ok).get_output_schema(&schema).await.unwrap();
+        assert_eq!(output.get_field("age").map(|f| &f.data_type), Some(&DataType::String));
+
+        let typed = MapTransform::new(Ok).with_field_type("age", DataType::Integer);
+        let output = typed.get_output_schema(&schema).await.unwrap();
+        assert_eq!(output.get_field("age").map(|f| &f.data_type), Some(&DataType::Integer));
+    }
+}
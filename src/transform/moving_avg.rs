@@ -0,0 +1,209 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema, Transform};
+use crate::transform::aggregate::number_to_value;
+use crate::transform::cast::cast_value;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Writes the average of the last `window` values of a numeric field into
+/// `output_field`, optionally tracked separately per key. The result depends
+/// on arrival order, so input must already be ordered (per key). Keys of
+/// different types are different keys, and records without the key field
+/// share the window of a null key.
+///
+/// Records where the field is missing or null do not advance the window but
+/// still receive the current average.
+pub struct MovingAvgTransform {
+    field: String,
+    output_field: String,
+    window: usize,
+    key_field: Option<String>,
+    weighted: bool,
+    windows: Mutex<HashMap<String, VecDeque<f64>>>,
+}
+
+impl MovingAvgTransform {
+    pub fn new(field: &str, window: usize) -> Self {
+        Self {
+            field: field.to_string(),
+            output_field: format!("{}_moving_avg", field),
+            window: window.max(1),
+            key_field: None,
+            weighted: false,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_key(mut self, key_field: &str) -> Self {
+        self.key_field = Some(key_field.to_string());
+        self
+    }
+
+    pub fn with_output_field(mut self, output_field: &str) -> Self {
+        self.output_field = output_field.to_string();
+        self
+    }
+
+    /// Use a linearly weighted average where the newest value has weight
+    /// `window` and the oldest weight 1.
+    pub fn weighted(mut self, weighted: bool) -> Self {
+        self.weighted = weighted;
+        self
+    }
+
+    fn average(&self, values: &VecDeque<f64>) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        if !self.weighted {
+            return Some(values.iter().sum::<f64>() / values.len() as f64);
+        }
+
+        let (total, weights) = values
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(total, weights), (i, value)| {
+                let weight = (i + 1) as f64;
+                (total + value * weight, weights + weight)
+            });
+        Some(total / weights)
+    }
+}
+
+#[async_trait]
+impl Transform for MovingAvgTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let value = match record.get_field(&self.field) {
+            Some(value) => match cast_value(value, &DataType::Float) {
+                Some(Value::Number(n)) => n.as_f64(),
+                Some(_) => None,
+                None => {
+                    return Err(PipelineError::Transform(
+                        format!("Field '{}' is not numeric: {}", self.field, value)
+                    ));
+                }
+            },
+            None => None,
+        };
+
+        // Keyed on the JSON of the value, so `1` and `"1"` stay apart.
+        let key = match self.key_field {
+            Some(ref key_field) => serde_json::to_string(record.get_field(key_field).unwrap_or(&Value::Null))?,
+            None => String::new(),
+        };
+
+        let average = {
+            let mut windows = self.windows.lock().unwrap();
+            let values = windows.entry(key).or_default();
+            if let Some(value) = value {
+                if values.len() == self.window {
+                    values.pop_front();
+                }
+                values.push_back(value);
+            }
+            self.average(values)
+        };
+
+        record.set_field(
+            self.output_field.clone(),
+            average.map(number_to_value).unwrap_or(Value::Null),
+        );
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|f| f.name != self.output_field);
+        schema.fields.push(Field {
+            name: self.output_field.clone(),
+            data_type: DataType::Float,
+            nullable: true,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn averages(transform: &MovingAvgTransform, inputs: &[(&str, Value)]) -> Vec<Value> {
+        let mut averages = Vec::new();
+        for (key, value) in inputs {
+            let mut record = Record::new();
+            record.set_field("sensor".to_string(), json!(key));
+            record.set_field("reading".to_string(), value.clone());
+            let out = transform.transform(record).await.unwrap();
+            averages.push(out[0].get_field("reading_moving_avg").unwrap().clone());
+        }
+        averages
+    }
+
+    #[tokio::test]
+    async fn averages_the_last_window_values() {
+        let transform = MovingAvgTransform::new("reading", 3);
+        let inputs: Vec<(&str, Value)> = [1, 2, 3, 4, 5, 6].iter().map(|n| ("a", json!(n))).collect();
+        assert_eq!(
+            averages(&transform, &inputs).await,
+            [json!(1.0), json!(1.5), json!(2.0), json!(3.0), json!(4.0), json!(5.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn nulls_do_not_advance_the_window() {
+        let transform = MovingAvgTransform::new("reading", 2);
+        let inputs = [("a", Value::Null), ("a", json!(2)), ("a", Value::Null), ("a", json!("4"))];
+        assert_eq!(averages(&transform, &inputs).await, [Value::Null, json!(2.0), json!(2.0), json!(3.0)]);
+    }
+
+    #[tokio::test]
+    async fn windows_are_kept_per_key() {
+        let transform = MovingAvgTransform::new("reading", 2).with_key("sensor");
+        let inputs = [("a", json!(10)), ("b", json!(1)), ("a", json!(20)), ("b", json!(3)), ("a", json!(30))];
+        assert_eq!(
+            averages(&transform, &inputs).await,
+            [json!(10.0), json!(1.0), json!(15.0), json!(2.0), json!(25.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn weighted_favours_recent_values() {
+        let transform = MovingAvgTransform::new("reading", 3).weighted(true);
+        let inputs = [("a", json!(3)), ("a", json!(6)), ("a", json!(9))];
+        // (3*1 + 6*2 + 9*3) / 6
+        assert_eq!(averages(&transform, &inputs).await[2], json!(7.0));
+    }
+
+    #[tokio::test]
+    async fn non_numeric_values_are_errors() {
+        let transform = MovingAvgTransform::new("reading", 3);
+        let mut record = Record::new();
+        record.set_field("reading".to_string(), json!("high"));
+        assert!(matches!(transform.transform(record).await, Err(PipelineError::Transform(_))));
+    }
+
+    #[tokio::test]
+    async fn keys_of_different_types_have_their_own_windows() {
+        let transform = MovingAvgTransform::new("reading", 2).with_key("sensor");
+        let keys = [json!("1"), json!(1), json!(""), Value::Null];
+        let mut averages = Vec::new();
+        for (key, reading) in keys.iter().zip([10, 20, 30, 40]) {
+            let mut record = Record::new();
+            record.set_field("sensor".to_string(), key.clone());
+            record.set_field("reading".to_string(), json!(reading));
+            averages.push(transform.transform(record).await.unwrap()[0].get_field("reading_moving_avg").cloned());
+        }
+        // A record without the key joins the null key's window, not `""`'s.
+        let mut missing = Record::new();
+        missing.set_field("reading".to_string(), json!(60));
+        averages.push(transform.transform(missing).await.unwrap()[0].get_field("reading_moving_avg").cloned());
+
+        assert_eq!(
+            averages,
+            [Some(json!(10.0)), Some(json!(20.0)), Some(json!(30.0)), Some(json!(40.0)), Some(json!(50.0))]
+        );
+    }
+}
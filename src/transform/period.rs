@@ -0,0 +1,154 @@
+use crate::core::{timestamp_millis, DataType, ErrorPolicy, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveDate};
+use serde_json::Value;
+
+const OUTPUT_FIELDS: [(&str, DataType); 3] = [
+    ("iso_week", DataType::String),
+    ("quarter", DataType::Integer),
+    ("fiscal_year", DataType::Integer),
+];
+
+/// Derives reporting periods from a date or timestamp field (anything
+/// [`timestamp_millis`] accepts, taken in UTC):
+///
+/// - `iso_week`: ISO 8601 week with its week-based year, e.g. `2025-W01`
+///   for 2024-12-30
+/// - `quarter`: calendar quarter, 1-4
+/// - `fiscal_year`: the fiscal year starting in `fiscal_start_month`, named
+///   by the calendar year it ends in, so with an October start 2023-10-01
+///   falls in fiscal year 2024
+pub struct PeriodTransform {
+    field: String,
+    fiscal_start_month: u32,
+    on_error: ErrorPolicy,
+}
+
+impl PeriodTransform {
+    pub fn new(field: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            fiscal_start_month: 1,
+            on_error: ErrorPolicy::Fail,
+        }
+    }
+
+    /// Sets the first month (1-12) of the fiscal year.
+    pub fn with_fiscal_start_month(mut self, month: u32) -> Self {
+        self.fiscal_start_month = month.clamp(1, 12);
+        self
+    }
+
+    pub fn with_error_policy(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    fn date(&self, record: &Record) -> Result<NaiveDate> {
+        let value = record.get_field(&self.field).unwrap_or(&Value::Null);
+        timestamp_millis(value)
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|dt| dt.date_naive())
+            .ok_or_else(|| {
+                PipelineError::Transform(format!("Field '{}' is not a valid date: {}", self.field, value))
+            })
+    }
+
+    fn periods(&self, date: NaiveDate) -> [Value; 3] {
+        let week = date.iso_week();
+        let quarter = (date.month0() / 3) + 1;
+        let fiscal_year = if self.fiscal_start_month > 1 && date.month() >= self.fiscal_start_month {
+            date.year() + 1
+        } else {
+            date.year()
+        };
+        [
+            Value::String(format!("{}-W{:02}", week.year(), week.week())),
+            Value::from(quarter),
+            Value::from(fiscal_year),
+        ]
+    }
+}
+
+#[async_trait]
+impl Transform for PeriodTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let values = match (self.date(&record), self.on_error) {
+            (Ok(date), _) => self.periods(date),
+            (Err(e), ErrorPolicy::Fail) => return Err(e),
+            (Err(_), ErrorPolicy::NullFill) => [Value::Null, Value::Null, Value::Null],
+            (Err(e), ErrorPolicy::DeadLetter) => {
+                record.set_metadata("error".to_string(), e.to_string());
+                return Ok(vec![record]);
+            }
+        };
+
+        for ((name, _), value) in OUTPUT_FIELDS.iter().zip(values) {
+            record.set_field(name.to_string(), value);
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for (name, data_type) in OUTPUT_FIELDS {
+            schema.fields.retain(|f| f.name != name);
+            schema.fields.push(Field {
+                name: name.to_string(),
+                data_type,
+                nullable: self.on_error != ErrorPolicy::Fail,
+                description: None,
+            });
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dated(date: &str) -> Record {
+        let mut record = Record::new();
+        record.set_field("date".to_string(), Value::from(date));
+        record
+    }
+
+    #[tokio::test]
+    async fn iso_week_and_quarter_across_year_boundaries() {
+        let transform = PeriodTransform::new("date");
+        for (date, iso_week, quarter) in [
+            ("2024-12-30", "2025-W01", 4),
+            ("2021-01-01", "2020-W53", 1),
+            ("2026-12-31", "2026-W53", 4),
+            ("2023-01-01T23:30:00Z", "2022-W52", 1),
+            ("2024-03-31", "2024-W13", 1),
+            ("2024-04-01", "2024-W14", 2),
+        ] {
+            let out = transform.transform(dated(date)).await.unwrap();
+            assert_eq!(out[0].get_field("iso_week"), Some(&Value::from(iso_week)), "{}", date);
+            assert_eq!(out[0].get_field("quarter"), Some(&Value::from(quarter)), "{}", date);
+        }
+    }
+
+    #[tokio::test]
+    async fn fiscal_year_is_named_by_the_year_it_ends_in() {
+        let transform = PeriodTransform::new("date").with_fiscal_start_month(10);
+        for (date, fiscal_year) in [("2023-09-30", 2023), ("2023-10-01", 2024), ("2024-01-15", 2024)] {
+            let out = transform.transform(dated(date)).await.unwrap();
+            assert_eq!(out[0].get_field("fiscal_year"), Some(&Value::from(fiscal_year)), "{}", date);
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_dates_error_or_null_fill() {
+        assert!(PeriodTransform::new("date").transform(dated("soon")).await.is_err());
+
+        let out = PeriodTransform::new("date")
+            .with_error_policy(ErrorPolicy::NullFill)
+            .transform(dated("soon"))
+            .await
+            .unwrap();
+        assert_eq!(out[0].get_field("iso_week"), Some(&Value::Null));
+    }
+}
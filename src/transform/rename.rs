@@ -0,0 +1,111 @@
+use crate::core::{PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+
+/// Renames fields from old to new names, leaving unmapped fields alone.
+///
+/// Mapping two fields to the same name is rejected when the mapping is
+/// built, and renaming a field onto one that is kept is an error for the
+/// record rather than a silent overwrite. Fields may be swapped, as in
+/// `a -> b` together with `b -> a`.
+pub struct RenameTransform {
+    mapping: HashMap<String, String>,
+}
+
+impl RenameTransform {
+    pub fn new(mapping: HashMap<String, String>) -> Result<Self> {
+        check_mapping(&mapping)?;
+        Ok(Self { mapping })
+    }
+
+    pub fn rename(mut self, from: &str, to: &str) -> Result<Self> {
+        self.mapping.insert(from.to_string(), to.to_string());
+        check_mapping(&self.mapping)?;
+        Ok(self)
+    }
+
+    /// Checks that no two of the `present` fields end up with the same name.
+    fn check_collisions<'a>(&self, present: impl Iterator<Item = &'a str>) -> Result<()> {
+        let mut targets: HashMap<&str, &str> = HashMap::new();
+        for name in present {
+            let target = self.mapping.get(name).map_or(name, String::as_str);
+            if let Some(other) = targets.insert(target, name) {
+                let (first, second) = if other < name { (other, name) } else { (name, other) };
+                return Err(PipelineError::Transform(format!(
+                    "Renaming would give fields '{}' and '{}' the same name '{}'",
+                    first, second, target
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_mapping(mapping: &HashMap<String, String>) -> Result<()> {
+    let mut seen = HashSet::new();
+    let mut duplicates: Vec<&String> = mapping.values().filter(|to| !seen.insert(*to)).collect();
+    duplicates.sort();
+    match duplicates.first() {
+        Some(to) => Err(PipelineError::Config(format!(
+            "More than one field is renamed to '{}'",
+            to
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[async_trait]
+impl Transform for RenameTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        self.check_collisions(record.data.keys().map(String::as_str))?;
+
+        // Rebuilding the map renames fields in place, keeping their order.
+        record.data = std::mem::take(&mut record.data)
+            .into_iter()
+            .map(|(name, value)| match self.mapping.get(&name) {
+                Some(to) => (to.clone(), value),
+                None => (name, value),
+            })
+            .collect();
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        self.check_collisions(input_schema.fields.iter().map(|field| field.name.as_str()))?;
+
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if let Some(to) = self.mapping.get(&field.name) {
+                field.name = to.clone();
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    #[test]
+    fn duplicate_targets_are_rejected_when_built() {
+        let result = RenameTransform::new(HashMap::new()).unwrap().rename("a", "c").unwrap().rename("b", "c");
+        assert!(matches!(result, Err(PipelineError::Config(ref e)) if e.contains("'c'")));
+    }
+
+    #[tokio::test]
+    async fn swaps_fields_in_place() {
+        let transform = RenameTransform::new(HashMap::new())
+            .unwrap()
+            .rename("a", "b")
+            .unwrap()
+            .rename("b", "a")
+            .unwrap();
+        let output = transform.transform(record(json!({"a": 1, "b": 2, "c": 3}))).await.unwrap();
+        let fields: Vec<(&str, i64)> =
+            output[0].data.iter().map(|(name, value)| (name.as_str(), value.as_i64().unwrap())).collect();
+        assert_eq!(fields, [("b", 1), ("a", 2), ("c", 3)]);
+    }
+}
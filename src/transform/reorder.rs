@@ -0,0 +1,167 @@
+use crate::core::{compare_values, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatePolicy {
+    /// Emit a late record immediately, out of order.
+    Emit,
+    /// Emit a late record immediately with the reason in its `error` metadata.
+    DeadLetter,
+}
+
+struct Buffered {
+    key: Value,
+    sequence: u64,
+    record: Record,
+}
+
+impl Ord for Buffered {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_values(&self.key, &other.key).then(self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl PartialOrd for Buffered {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Buffered {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Buffered {}
+
+#[derive(Default)]
+struct ReorderState {
+    buffer: BinaryHeap<Reverse<Buffered>>,
+    last_emitted: Option<Value>,
+    sequence: u64,
+}
+
+/// Sorts nearly-sorted input by `key_field` using a buffer of `window`
+/// records: once the buffer is full, each new record releases the smallest
+/// buffered one. Any record displaced by fewer than `window` positions comes
+/// out in order; equal keys keep their arrival order. A record whose key
+/// sorts before one already emitted is late and handled by the
+/// [`LatePolicy`]. The buffer is drained on `flush`.
+pub struct ReorderTransform {
+    key_field: String,
+    window: usize,
+    late_policy: LatePolicy,
+    state: Mutex<ReorderState>,
+}
+
+impl ReorderTransform {
+    pub fn new(key_field: &str, window: usize) -> Self {
+        Self {
+            key_field: key_field.to_string(),
+            window: window.max(1),
+            late_policy: LatePolicy::Emit,
+            state: Mutex::new(ReorderState::default()),
+        }
+    }
+
+    pub fn with_late_policy(mut self, late_policy: LatePolicy) -> Self {
+        self.late_policy = late_policy;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for ReorderTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let key = record.get_field(&self.key_field).cloned().unwrap_or(Value::Null);
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(ref last) = state.last_emitted
+            && compare_values(&key, last) == Ordering::Less
+        {
+            if self.late_policy == LatePolicy::DeadLetter {
+                record.set_metadata(
+                    "error".to_string(),
+                    format!("Record with {} = {} arrived after the reorder window", self.key_field, key),
+                );
+            }
+            return Ok(vec![record]);
+        }
+
+        let sequence = state.sequence;
+        state.sequence += 1;
+        state.buffer.push(Reverse(Buffered { key, sequence, record }));
+
+        if state.buffer.len() <= self.window {
+            return Ok(vec![]);
+        }
+        let Reverse(smallest) = state.buffer.pop().unwrap();
+        state.last_emitted = Some(smallest.key);
+        Ok(vec![smallest.record])
+    }
+
+    async fn flush(&self) -> Result<Vec<Record>> {
+        let mut state = self.state.lock().unwrap();
+        let buffered = std::mem::take(&mut state.buffer).into_sorted_vec();
+        // `Reverse` sorts descending, so the sorted vec runs largest first.
+        let records: Vec<Buffered> = buffered.into_iter().rev().map(|Reverse(b)| b).collect();
+        if let Some(last) = records.last() {
+            state.last_emitted = Some(last.key.clone());
+        }
+        Ok(records.into_iter().map(|b| b.record).collect())
+    }
+
+    fn buffers(&self) -> bool {
+        true
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seq(n: i64) -> Record {
+        let mut record = Record::new();
+        record.set_field("seq".to_string(), Value::from(n));
+        record
+    }
+
+    fn key(record: &Record) -> i64 {
+        record.get_field("seq").and_then(Value::as_i64).unwrap()
+    }
+
+    async fn run(transform: &ReorderTransform, input: &[i64]) -> Vec<Record> {
+        let mut out = Vec::new();
+        for &n in input {
+            out.extend(transform.transform(seq(n)).await.unwrap());
+        }
+        out.extend(transform.flush().await.unwrap());
+        out
+    }
+
+    #[tokio::test]
+    async fn sorts_records_shuffled_within_the_window() {
+        let transform = ReorderTransform::new("seq", 2);
+        let out = run(&transform, &[2, 1, 3, 5, 4, 6, 9, 7, 8, 10]).await;
+        assert_eq!(out.iter().map(key).collect::<Vec<_>>(), (1..=10).collect::<Vec<_>>());
+        assert!(out.iter().all(|r| r.get_metadata("error").is_none()));
+    }
+
+    #[tokio::test]
+    async fn records_beyond_the_window_are_late() {
+        let transform = ReorderTransform::new("seq", 1).with_late_policy(LatePolicy::DeadLetter);
+        let out = run(&transform, &[3, 4, 1, 5]).await;
+        assert_eq!(out.iter().map(key).collect::<Vec<_>>(), vec![3, 1, 4, 5]);
+        assert!(out[1].get_metadata("error").unwrap().contains("seq = 1"));
+        assert!(out[0].get_metadata("error").is_none());
+    }
+}
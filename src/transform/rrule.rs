@@ -0,0 +1,191 @@
+use crate::core::{timestamp_millis, DataType, ErrorPolicy, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rrule::{RRuleSet, Tz};
+use serde_json::Value;
+
+/// Expands an iCalendar recurrence rule into one record per occurrence
+/// between `window_start` and `window_end` inclusive. Each output is a copy
+/// of the input with the occurrence, as RFC 3339, in `occurrence`.
+///
+/// The rule field may hold a full `DTSTART:...\nRRULE:...` block or just
+/// the `RRULE` value (`FREQ=WEEKLY;COUNT=4`), in which case the start comes
+/// from the `dtstart_field`. Expansion stops after `max_occurrences`.
+pub struct RruleExpandTransform {
+    rule_field: String,
+    dtstart_field: Option<String>,
+    output_field: String,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    max_occurrences: u16,
+    on_error: ErrorPolicy,
+}
+
+impl RruleExpandTransform {
+    pub fn new(rule_field: &str, window_start: DateTime<Utc>, window_end: DateTime<Utc>) -> Self {
+        Self {
+            rule_field: rule_field.to_string(),
+            dtstart_field: None,
+            output_field: "occurrence".to_string(),
+            window_start,
+            window_end,
+            max_occurrences: 1000,
+            on_error: ErrorPolicy::Fail,
+        }
+    }
+
+    pub fn with_dtstart_field(mut self, field: &str) -> Self {
+        self.dtstart_field = Some(field.to_string());
+        self
+    }
+
+    pub fn with_output_field(mut self, field: &str) -> Self {
+        self.output_field = field.to_string();
+        self
+    }
+
+    pub fn with_max_occurrences(mut self, max_occurrences: u16) -> Self {
+        self.max_occurrences = max_occurrences;
+        self
+    }
+
+    /// With `NullFill` or `DeadLetter` an invalid rule passes the record
+    /// through once with a null occurrence.
+    pub fn with_error_policy(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    fn rule_set(&self, record: &Record) -> Result<RRuleSet> {
+        let rule = record
+            .get_field(&self.rule_field)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .ok_or_else(|| PipelineError::Transform(format!("Missing recurrence rule field '{}'", self.rule_field)))?;
+
+        let text = if rule.contains("DTSTART") {
+            rule.to_string()
+        } else {
+            let dtstart = self
+                .dtstart_field
+                .as_ref()
+                .and_then(|field| record.get_field(field))
+                .and_then(timestamp_millis)
+                .and_then(DateTime::<Utc>::from_timestamp_millis)
+                .ok_or_else(|| PipelineError::Transform("Recurrence rule has no valid DTSTART".to_string()))?;
+            let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+            format!("DTSTART:{}\nRRULE:{}", dtstart.format("%Y%m%dT%H%M%SZ"), rule)
+        };
+
+        text.parse::<RRuleSet>()
+            .map_err(|e| PipelineError::Transform(format!("Invalid recurrence rule '{}': {}", rule, e)))
+    }
+}
+
+#[async_trait]
+impl Transform for RruleExpandTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let rule_set = match (self.rule_set(&record), self.on_error) {
+            (Ok(rule_set), _) => rule_set,
+            (Err(e), ErrorPolicy::Fail) => return Err(e),
+            (Err(e), policy) => {
+                record.set_field(self.output_field.clone(), Value::Null);
+                if policy == ErrorPolicy::DeadLetter {
+                    record.set_metadata("error".to_string(), e.to_string());
+                }
+                return Ok(vec![record]);
+            }
+        };
+
+        let occurrences = rule_set
+            .after(self.window_start.with_timezone(&Tz::UTC))
+            .before(self.window_end.with_timezone(&Tz::UTC))
+            .all(self.max_occurrences);
+        if occurrences.limited {
+            tracing::warn!("Recurrence expansion stopped after {} occurrences", occurrences.dates.len());
+        }
+
+        Ok(occurrences
+            .dates
+            .into_iter()
+            .map(|occurrence| {
+                let mut output = record.clone();
+                output.set_field(self.output_field.clone(), Value::String(occurrence.to_rfc3339()));
+                output
+            })
+            .collect())
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|f| f.name != self.output_field);
+        schema.fields.push(Field {
+            name: self.output_field.clone(),
+            data_type: DataType::Timestamp,
+            nullable: self.on_error != ErrorPolicy::Fail,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn january() -> RruleExpandTransform {
+        RruleExpandTransform::new(
+            "rule",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 14, 23, 59, 59).unwrap(),
+        )
+        .with_dtstart_field("start")
+    }
+
+    #[tokio::test]
+    async fn weekly_rule_expands_into_each_occurrence_in_the_window() {
+        let input = record(json!({
+            "event": "standup",
+            "rule": "FREQ=WEEKLY;BYDAY=MO,WE",
+            "start": "2023-12-25T09:00:00Z"
+        }));
+
+        let occurrences: Vec<String> = january()
+            .transform(input)
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| {
+                assert_eq!(r.get_field("event"), Some(&json!("standup")));
+                r.get_field("occurrence").unwrap().as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            occurrences,
+            vec![
+                "2024-01-01T09:00:00+00:00",
+                "2024-01-03T09:00:00+00:00",
+                "2024-01-08T09:00:00+00:00",
+                "2024-01-10T09:00:00+00:00",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_rule_errors_or_passes_through() {
+        let input = json!({"rule": "FREQ=SOMETIMES", "start": "2024-01-01T09:00:00Z"});
+        assert!(january().transform(record(input.clone())).await.is_err());
+
+        let out = january()
+            .with_error_policy(ErrorPolicy::NullFill)
+            .transform(record(input))
+            .await
+            .unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].get_field("occurrence"), Some(&Value::Null));
+    }
+}
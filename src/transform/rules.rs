@@ -0,0 +1,222 @@
+use crate::core::{compare_values, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::mem::discriminant;
+
+/// One side of a rule: a record field or a constant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Field(String),
+    Const(Value),
+}
+
+impl Operand {
+    pub fn field(name: &str) -> Self {
+        Operand::Field(name.to_string())
+    }
+
+    pub fn constant<V: Into<Value>>(value: V) -> Self {
+        Operand::Const(value.into())
+    }
+
+    fn resolve<'a>(&'a self, record: &'a Record) -> Option<&'a Value> {
+        match self {
+            Operand::Field(name) => record.get_field(name),
+            Operand::Const(value) => Some(value),
+        }
+        .filter(|value| !value.is_null())
+    }
+}
+
+impl std::fmt::Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Field(name) => write!(f, "{}", name),
+            Operand::Const(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Eq => ordering == Ordering::Equal,
+            Comparison::Ne => ordering != Ordering::Equal,
+            Comparison::Lt => ordering == Ordering::Less,
+            Comparison::Le => ordering != Ordering::Greater,
+            Comparison::Gt => ordering == Ordering::Greater,
+            Comparison::Ge => ordering != Ordering::Less,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleAction {
+    /// Fail the record with the list of violated rules.
+    Fail,
+    /// Pass the record on with the violations stored in its `error` metadata.
+    DeadLetter,
+    /// Pass the record on with the violated rule names, comma-separated, in
+    /// its `rule_violations` metadata.
+    Tag,
+}
+
+struct Rule {
+    name: String,
+    left: Operand,
+    comparison: Comparison,
+    right: Operand,
+}
+
+/// Checks business rules that compare two fields, or a field and a constant,
+/// such as `end_date >= start_date`. Values are ordered with
+/// `compare_values`, so ISO-8601 date strings compare chronologically.
+///
+/// A rule whose operand is missing or null is skipped; nullability is left
+/// to schema enforcement. Operands of different kinds never satisfy a rule.
+pub struct CrossFieldRuleTransform {
+    rules: Vec<Rule>,
+    action: RuleAction,
+}
+
+impl CrossFieldRuleTransform {
+    pub fn new(action: RuleAction) -> Self {
+        Self {
+            rules: Vec::new(),
+            action,
+        }
+    }
+
+    /// Adds a rule that holds when `left comparison right`.
+    pub fn rule(mut self, name: &str, left: Operand, comparison: Comparison, right: Operand) -> Self {
+        self.rules.push(Rule {
+            name: name.to_string(),
+            left,
+            comparison,
+            right,
+        });
+        self
+    }
+
+    fn violations(&self, record: &Record) -> Vec<&Rule> {
+        self.rules
+            .iter()
+            .filter(|rule| {
+                let (Some(left), Some(right)) = (rule.left.resolve(record), rule.right.resolve(record)) else {
+                    return false;
+                };
+                discriminant(left) != discriminant(right)
+                    || !rule.comparison.holds(compare_values(left, right))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Transform for CrossFieldRuleTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let violations = self.violations(&record);
+        if violations.is_empty() {
+            return Ok(vec![record]);
+        }
+
+        match self.action {
+            RuleAction::Tag => {
+                let names: Vec<&str> = violations.iter().map(|rule| rule.name.as_str()).collect();
+                record.set_metadata("rule_violations".to_string(), names.join(","));
+            }
+            RuleAction::Fail | RuleAction::DeadLetter => {
+                let report = format!(
+                    "{} rule(s) violated: {}",
+                    violations.len(),
+                    violations
+                        .iter()
+                        .map(|rule| format!(
+                            "'{}' ({} {} {})",
+                            rule.name,
+                            rule.left,
+                            rule.comparison.symbol(),
+                            rule.right
+                        ))
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                );
+                if self.action == RuleAction::Fail {
+                    return Err(PipelineError::Transform(report));
+                }
+                record.set_metadata("error".to_string(), report);
+            }
+        }
+
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn date_rule(action: RuleAction) -> CrossFieldRuleTransform {
+        CrossFieldRuleTransform::new(action).rule(
+            "ends_after_start",
+            Operand::field("end_date"),
+            Comparison::Ge,
+            Operand::field("start_date"),
+        )
+    }
+
+    #[tokio::test]
+    async fn end_date_on_or_after_start_date_passes() {
+        let transform = date_rule(RuleAction::Fail);
+        for end_date in ["2024-03-01", "2024-03-15"] {
+            let out = transform
+                .transform(record(json!({"start_date": "2024-03-01", "end_date": end_date})))
+                .await
+                .unwrap();
+            assert_eq!(out.len(), 1);
+            assert!(out[0].get_metadata("error").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn end_date_before_start_date_is_a_violation() {
+        let input = json!({"start_date": "2024-03-01", "end_date": "2024-02-28"});
+
+        let err = date_rule(RuleAction::Fail).transform(record(input.clone())).await.unwrap_err();
+        assert!(err.to_string().contains("'ends_after_start' (end_date >= start_date)"));
+
+        let out = date_rule(RuleAction::DeadLetter).transform(record(input.clone())).await.unwrap();
+        assert!(out[0].get_metadata("error").unwrap().contains("ends_after_start"));
+
+        let out = date_rule(RuleAction::Tag).transform(record(input)).await.unwrap();
+        assert_eq!(out[0].get_metadata("rule_violations"), Some("ends_after_start"));
+    }
+}
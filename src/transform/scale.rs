@@ -0,0 +1,182 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema, Source, Transform};
+use crate::transform::aggregate::{number_to_value, NanPolicy, NullPolicy, NumericAccumulator};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScaleMethod {
+    /// `(x - min) / (max - min)`, mapping the column range onto `[0, 1]`.
+    MinMax,
+    /// `(x - mean) / std_dev`.
+    ZScore,
+}
+
+/// Column statistics a [`ScaleTransform`] scales against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+/// Normalizes numeric fields with min-max or z-score scaling. The
+/// statistics are either given per field or computed by [`ScaleTransform::fit`]
+/// in a first pass over a source. A constant column scales to `0`; missing
+/// and null values are left untouched.
+pub struct ScaleTransform {
+    method: ScaleMethod,
+    fields: Vec<(String, FieldStats)>,
+    output_suffix: Option<String>,
+}
+
+impl ScaleTransform {
+    pub fn new(method: ScaleMethod) -> Self {
+        Self {
+            method,
+            fields: Vec::new(),
+            output_suffix: None,
+        }
+    }
+
+    /// Scales `field` using precomputed `stats`.
+    pub fn with_stats(mut self, field: &str, stats: FieldStats) -> Self {
+        self.fields.retain(|(name, _)| name != field);
+        self.fields.push((field.to_string(), stats));
+        self
+    }
+
+    /// Writes each scaled value to `{field}{suffix}` instead of replacing it.
+    pub fn with_output_suffix(mut self, suffix: &str) -> Self {
+        self.output_suffix = Some(suffix.to_string());
+        self
+    }
+
+    /// Reads `source` once to compute the statistics of `fields`. The source
+    /// is read again when the pipeline runs, so it must be replayable.
+    pub async fn fit(method: ScaleMethod, source: &dyn Source, fields: &[&str]) -> Result<Self> {
+        let mut accumulators: Vec<NumericAccumulator> = fields
+            .iter()
+            .map(|_| NumericAccumulator::new(NullPolicy::Skip, NanPolicy::Ignore))
+            .collect();
+
+        let mut stream = source.read().await?;
+        while let Some(record) = stream.next().await {
+            let record = record?;
+            for (field, accumulator) in fields.iter().zip(accumulators.iter_mut()) {
+                accumulator.add(record.get_field(field))?;
+            }
+        }
+
+        let mut transform = Self::new(method);
+        for (field, accumulator) in fields.iter().zip(accumulators) {
+            let (Some(min), Some(max), Some(mean), Some(std_dev)) =
+                (accumulator.min(), accumulator.max(), accumulator.mean(), accumulator.std_dev())
+            else {
+                return Err(PipelineError::Transform(format!("Field '{}' has no numeric values to fit", field)));
+            };
+            transform = transform.with_stats(field, FieldStats { min, max, mean, std_dev });
+        }
+        Ok(transform)
+    }
+
+    fn scale(&self, value: f64, stats: &FieldStats) -> f64 {
+        let (offset, spread) = match self.method {
+            ScaleMethod::MinMax => (stats.min, stats.max - stats.min),
+            ScaleMethod::ZScore => (stats.mean, stats.std_dev),
+        };
+        if spread == 0.0 { 0.0 } else { (value - offset) / spread }
+    }
+
+    fn output_field(&self, field: &str) -> String {
+        match self.output_suffix {
+            Some(ref suffix) => format!("{}{}", field, suffix),
+            None => field.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transform for ScaleTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        for (field, stats) in &self.fields {
+            let value = match record.get_field(field) {
+                None | Some(Value::Null) => continue,
+                Some(Value::Number(n)) => n.as_f64(),
+                Some(Value::String(s)) => s.trim().parse::<f64>().ok(),
+                Some(_) => None,
+            }
+            .ok_or_else(|| PipelineError::Transform(format!("Cannot scale non-numeric field '{}'", field)))?;
+
+            let scaled = number_to_value(self.scale(value, stats));
+            record.set_field(self.output_field(field), scaled);
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for (field, _) in &self.fields {
+            let output = self.output_field(field);
+            let nullable = schema.get_field(field).is_none_or(|f| f.nullable);
+            match schema.fields.iter_mut().find(|f| f.name == output) {
+                Some(existing) => {
+                    existing.data_type = DataType::Float;
+                    existing.nullable = nullable;
+                }
+                None => schema.fields.push(Field {
+                    name: output,
+                    data_type: DataType::Float,
+                    nullable,
+                    description: None,
+                }),
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::memory::VecSource;
+
+    fn priced(price: f64) -> Record {
+        let mut record = Record::new();
+        record.set_field("price".to_string(), Value::from(price));
+        record
+    }
+
+    #[tokio::test]
+    async fn min_max_maps_the_column_range_to_unit_interval() {
+        let source = VecSource::new([10.0, 15.0, 20.0, 30.0].map(priced).to_vec());
+        let transform = ScaleTransform::fit(ScaleMethod::MinMax, &source, &["price"])
+            .await
+            .unwrap()
+            .with_output_suffix("_scaled");
+
+        let mut scaled = Vec::new();
+        for price in [10.0, 15.0, 20.0, 30.0] {
+            let out = transform.transform(priced(price)).await.unwrap();
+            assert_eq!(out[0].get_field("price").and_then(Value::as_f64), Some(price));
+            scaled.push(out[0].get_field("price_scaled").and_then(Value::as_f64).unwrap());
+        }
+        assert_eq!(scaled, vec![0.0, 0.25, 0.5, 1.0]);
+    }
+
+    #[tokio::test]
+    async fn constant_column_scales_to_zero() {
+        let stats = FieldStats {
+            min: 5.0,
+            max: 5.0,
+            mean: 5.0,
+            std_dev: 0.0,
+        };
+        for method in [ScaleMethod::MinMax, ScaleMethod::ZScore] {
+            let transform = ScaleTransform::new(method).with_stats("price", stats);
+            let out = transform.transform(priced(5.0)).await.unwrap();
+            assert_eq!(out[0].get_field("price").and_then(Value::as_f64), Some(0.0));
+        }
+    }
+}
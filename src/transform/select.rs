@@ -0,0 +1,187 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+fn missing_column(name: &str) -> PipelineError {
+    PipelineError::Transform(format!("Column '{}' does not exist", name))
+}
+
+/// `columns` with repeated names left out after their first appearance.
+fn unique(columns: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    columns.into_iter().filter(|name| seen.insert(name.clone())).collect()
+}
+
+/// Keeps only the named fields, dropping the rest.
+///
+/// The output schema lists the columns in the order given, with a repeated
+/// name kept once. A column the input lacks is set to `Value::Null`, or is
+/// an error when strict.
+pub struct SelectTransform {
+    columns: Vec<String>,
+    strict: bool,
+}
+
+impl SelectTransform {
+    pub fn new(columns: Vec<String>) -> Self {
+        Self { columns: unique(columns), strict: false }
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for SelectTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let mut data = IndexMap::with_capacity(self.columns.len());
+        for name in &self.columns {
+            let value = match record.data.swap_remove(name) {
+                Some(value) => value,
+                None if self.strict => return Err(missing_column(name)),
+                None => Value::Null,
+            };
+            data.insert(name.clone(), value);
+        }
+        record.data = data;
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let fields = self
+            .columns
+            .iter()
+            .map(|name| match input_schema.get_field(name) {
+                Some(field) => Ok(field.clone()),
+                None if self.strict => Err(missing_column(name)),
+                None => Ok(Field {
+                    name: name.clone(),
+                    data_type: DataType::Json,
+                    nullable: true,
+                    description: None,
+                }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Schema::new(fields).with_metadata(input_schema.metadata.clone()))
+    }
+}
+
+/// Removes the named fields, keeping the rest; a repeated name counts once.
+/// Naming a column the input lacks is ignored, or is an error when strict.
+pub struct DropTransform {
+    columns: Vec<String>,
+    strict: bool,
+}
+
+impl DropTransform {
+    pub fn new(columns: Vec<String>) -> Self {
+        Self { columns: unique(columns), strict: false }
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for DropTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        for name in &self.columns {
+            if record.data.shift_remove(name).is_none() && self.strict {
+                return Err(missing_column(name));
+            }
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        if self.strict
+            && let Some(name) = self.columns.iter().find(|name| input_schema.get_field(name).is_none())
+        {
+            return Err(missing_column(name));
+        }
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|field| !self.columns.contains(&field.name));
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn names(record: &Record) -> Vec<&str> {
+        record.data.keys().map(String::as_str).collect()
+    }
+
+    fn schema(names: &[&str]) -> Schema {
+        Schema::new(
+            names
+                .iter()
+                .map(|name| Field {
+                    name: name.to_string(),
+                    data_type: DataType::String,
+                    nullable: false,
+                    description: None,
+                })
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn select_outputs_the_columns_in_the_order_given() {
+        let select = SelectTransform::new(columns(&["email", "id", "email"]));
+        let input = record(json!({"id": 1, "name": "a", "email": "a@example.com"}));
+
+        let output = select.transform(input).await.unwrap();
+        assert_eq!(names(&output[0]), ["email", "id"]);
+        assert_eq!(output[0].get_field("email"), Some(&json!("a@example.com")));
+
+        let schema = select.get_output_schema(&schema(&["id", "name", "email"])).await.unwrap();
+        assert_eq!(schema.field_names(), ["email", "id"]);
+    }
+
+    #[tokio::test]
+    async fn select_fills_missing_columns_with_null_unless_strict() {
+        let input = record(json!({"id": 1}));
+
+        let lenient = SelectTransform::new(columns(&["id", "phone"]));
+        let output = lenient.transform(input.clone()).await.unwrap();
+        assert_eq!(output[0].get_field("phone"), Some(&Value::Null));
+        let phone = lenient.get_output_schema(&schema(&["id"])).await.unwrap().fields[1].clone();
+        assert_eq!((phone.data_type, phone.nullable), (DataType::Json, true));
+
+        let strict = SelectTransform::new(columns(&["id", "phone"])).strict(true);
+        let error = strict.transform(input).await.unwrap_err();
+        assert!(matches!(error, PipelineError::Transform(ref e) if e.contains("'phone'")), "{}", error);
+        assert!(strict.get_output_schema(&schema(&["id"])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn drop_removes_columns_and_ignores_missing_ones_unless_strict() {
+        let input = record(json!({"id": 1, "name": "a", "email": "a@example.com"}));
+
+        let lenient = DropTransform::new(columns(&["name", "phone", "name"]));
+        let output = lenient.transform(input.clone()).await.unwrap();
+        assert_eq!(names(&output[0]), ["id", "email"]);
+        let dropped = lenient.get_output_schema(&schema(&["id", "name", "email"])).await.unwrap();
+        assert_eq!(dropped.field_names(), ["id", "email"]);
+
+        let strict = DropTransform::new(columns(&["name", "phone"])).strict(true);
+        let error = strict.transform(input.clone()).await.unwrap_err();
+        assert!(matches!(error, PipelineError::Transform(ref e) if e.contains("'phone'")), "{}", error);
+        // A repeated name is only removed once, so it is not missing the second time.
+        let repeated = DropTransform::new(columns(&["name", "name"])).strict(true);
+        assert_eq!(names(&repeated.transform(input).await.unwrap()[0]), ["id", "email"]);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::core::{timestamp_millis, DataType, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Assigns each event a session id, starting a new session for a user when
+/// more than `timeout` of event time has passed since their previous event.
+/// Ids have the form `<user>-<n>` with `n` counting that user's sessions from
+/// 1, so they are unique across users.
+///
+/// Input must be ordered by time per user; an event older than the user's
+/// latest one joins the current session. Timestamps are parsed with
+/// [`timestamp_millis`].
+pub struct SessionizeTransform {
+    user_field: String,
+    timestamp_field: String,
+    timeout_millis: i64,
+    output_field: String,
+    // user -> (latest event time, session number)
+    sessions: Mutex<HashMap<String, (i64, u64)>>,
+}
+
+impl SessionizeTransform {
+    pub fn new(user_field: &str, timestamp_field: &str, timeout: Duration) -> Self {
+        Self {
+            user_field: user_field.to_string(),
+            timestamp_field: timestamp_field.to_string(),
+            timeout_millis: timeout.as_millis() as i64,
+            output_field: "session_id".to_string(),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_output_field(mut self, output_field: &str) -> Self {
+        self.output_field = output_field.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for SessionizeTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let timestamp = record
+            .get_field(&self.timestamp_field)
+            .and_then(timestamp_millis)
+            .ok_or_else(|| {
+                PipelineError::Transform(format!("Missing or invalid timestamp field '{}'", self.timestamp_field))
+            })?;
+        let user = match record.get_field(&self.user_field) {
+            Some(Value::String(s)) => s.clone(),
+            Some(value) if !value.is_null() => value.to_string(),
+            _ => {
+                return Err(PipelineError::Transform(format!("Missing user field '{}'", self.user_field)));
+            }
+        };
+
+        let session = {
+            let mut sessions = self.sessions.lock().unwrap();
+            let (latest, session) = sessions.entry(user.clone()).or_insert((timestamp, 1));
+            if timestamp - *latest > self.timeout_millis {
+                *session += 1;
+            }
+            *latest = (*latest).max(timestamp);
+            *session
+        };
+
+        record.set_field(self.output_field.clone(), Value::String(format!("{}-{}", user, session)));
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|f| f.name != self.output_field);
+        schema.fields.push(Field {
+            name: self.output_field.clone(),
+            data_type: DataType::String,
+            nullable: false,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn event(user: &str, ts: &str) -> Record {
+        record(json!({"user": user, "ts": ts}))
+    }
+
+    #[tokio::test]
+    async fn gap_beyond_the_timeout_starts_a_new_session() {
+        let transform = SessionizeTransform::new("user", "ts", Duration::from_secs(30 * 60));
+        let mut sessions = Vec::new();
+        for (user, ts) in [
+            ("ada", "2024-01-01T09:00:00Z"),
+            ("bob", "2024-01-01T09:05:00Z"),
+            ("ada", "2024-01-01T09:29:00Z"),
+            // 30 minutes after the previous event: still the same session.
+            ("ada", "2024-01-01T09:59:00Z"),
+            ("ada", "2024-01-01T10:30:00Z"),
+            ("bob", "2024-01-01T10:31:00Z"),
+        ] {
+            let out = transform.transform(event(user, ts)).await.unwrap();
+            sessions.push(out[0].get_field("session_id").unwrap().as_str().unwrap().to_string());
+        }
+        assert_eq!(sessions, ["ada-1", "bob-1", "ada-1", "ada-1", "ada-2", "bob-2"]);
+    }
+
+    #[tokio::test]
+    async fn missing_user_or_timestamp_errors() {
+        let transform = SessionizeTransform::new("user", "ts", Duration::from_secs(60));
+        let mut untimed = Record::new();
+        untimed.set_field("user".to_string(), json!("ada"));
+        assert!(transform.transform(untimed).await.is_err());
+
+        let mut anonymous = Record::new();
+        anonymous.set_field("ts".to_string(), json!("2024-01-01T09:00:00Z"));
+        assert!(transform.transform(anonymous).await.is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::core::{DataType, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShardMode {
+    /// Deal records out round-robin over `n` shards.
+    ByCount(usize),
+    /// Send each record to whichever of `n` shards has received the fewest
+    /// serialized bytes so far.
+    BySize(usize),
+    /// Hash a key field into one of `n` shards, so equal keys always share a
+    /// shard across runs.
+    ByKeyHash(String, usize),
+}
+
+/// Tags each record with a shard id in `0..n`, written both to the `__shard`
+/// field and to the `shard` metadata key, so downstream stages can split the
+/// data evenly.
+pub struct ShardTransform {
+    mode: ShardMode,
+    next: AtomicU64,
+    shard_bytes: Mutex<Vec<u64>>,
+}
+
+impl ShardTransform {
+    pub fn new(mode: ShardMode) -> Result<Self> {
+        let shards = match mode {
+            ShardMode::ByCount(n) | ShardMode::BySize(n) | ShardMode::ByKeyHash(_, n) => n,
+        };
+        if shards == 0 {
+            return Err(PipelineError::Config("Shard count must be at least 1".to_string()));
+        }
+        Ok(Self {
+            mode,
+            next: AtomicU64::new(0),
+            shard_bytes: Mutex::new(vec![0; shards]),
+        })
+    }
+
+    fn shard(&self, record: &Record) -> Result<u64> {
+        match self.mode {
+            ShardMode::ByCount(n) => Ok(self.next.fetch_add(1, Ordering::Relaxed) % n as u64),
+            ShardMode::BySize(_) => {
+                let size = serde_json::to_vec(&record.data)?.len() as u64;
+                let mut shard_bytes = self.shard_bytes.lock().unwrap();
+                let (shard, bytes) = shard_bytes
+                    .iter_mut()
+                    .enumerate()
+                    .min_by_key(|(_, bytes)| **bytes)
+                    .unwrap();
+                *bytes += size;
+                Ok(shard as u64)
+            }
+            ShardMode::ByKeyHash(ref field, n) => {
+                let key = match record.get_field(field) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(value) => value.to_string(),
+                    None => {
+                        return Err(PipelineError::Transform(format!("Missing shard key field '{}'", field)));
+                    }
+                };
+                // SHA-256 rather than the std hasher, whose seed varies per process.
+                let digest = Sha256::digest(key.as_bytes());
+                Ok(u64::from_le_bytes(digest[0..8].try_into().unwrap()) % n as u64)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transform for ShardTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let shard = self.shard(&record)?;
+        record.set_field("__shard".to_string(), Value::from(shard));
+        record.set_metadata("shard".to_string(), shard.to_string());
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        schema.fields.retain(|f| f.name != "__shard");
+        schema.fields.push(Field {
+            name: "__shard".to_string(),
+            data_type: DataType::Integer,
+            nullable: false,
+            description: None,
+        });
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyed(key: &str) -> Record {
+        let mut record = Record::new();
+        record.set_field("customer".to_string(), Value::from(key));
+        record
+    }
+
+    async fn shard_of(transform: &ShardTransform, record: Record) -> u64 {
+        let out = transform.transform(record).await.unwrap();
+        out[0].get_field("__shard").and_then(Value::as_u64).unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_robin_balances_the_shards() {
+        let transform = ShardTransform::new(ShardMode::ByCount(4)).unwrap();
+        let mut counts = [0; 4];
+        for _ in 0..100 {
+            counts[shard_of(&transform, Record::new()).await as usize] += 1;
+        }
+        assert_eq!(counts, [25; 4]);
+    }
+
+    #[tokio::test]
+    async fn key_hash_assigns_equal_keys_to_the_same_shard() {
+        let first = ShardTransform::new(ShardMode::ByKeyHash("customer".to_string(), 8)).unwrap();
+        let second = ShardTransform::new(ShardMode::ByKeyHash("customer".to_string(), 8)).unwrap();
+        for key in ["alice", "bob", "carol", "dave"] {
+            let shard = shard_of(&first, keyed(key)).await;
+            assert!(shard < 8);
+            assert_eq!(shard_of(&first, keyed(key)).await, shard);
+            assert_eq!(shard_of(&second, keyed(key)).await, shard);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::core::{compare_values, Record, RecordStream, Result, Schema, Transform};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::cmp::Ordering;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub field: String,
+    pub ascending: bool,
+}
+
+impl SortKey {
+    pub fn asc(field: &str) -> Self {
+        Self { field: field.to_string(), ascending: true }
+    }
+
+    pub fn desc(field: &str) -> Self {
+        Self { field: field.to_string(), ascending: false }
+    }
+}
+
+/// Where null and missing values sort, whatever the key's direction.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NullOrder {
+    First,
+    #[default]
+    Last,
+}
+
+/// Sorts records by the given keys in turn, comparing values with
+/// [`compare_values`]: numbers numerically, strings lexicographically, and
+/// values of different JSON types by type. Records with equal keys keep
+/// their input order.
+///
+/// This is not a streaming stage. Every record is held in memory until the
+/// input ends, so memory grows with the input. As a pipeline transform,
+/// nothing is emitted until `flush`. [`sort`](SortStage::sort) runs it over a
+/// stream directly. For input that is already nearly sorted, a
+/// [`ReorderTransform`](crate::transform::reorder::ReorderTransform) needs
+/// only a bounded window.
+pub struct SortStage {
+    keys: Vec<SortKey>,
+    nulls: NullOrder,
+    buffer: Mutex<Vec<Record>>,
+}
+
+impl SortStage {
+    pub fn new(keys: Vec<SortKey>) -> Self {
+        Self {
+            keys,
+            nulls: NullOrder::default(),
+            buffer: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_nulls(mut self, nulls: NullOrder) -> Self {
+        self.nulls = nulls;
+        self
+    }
+
+    fn compare(&self, a: &Record, b: &Record) -> Ordering {
+        for key in &self.keys {
+            let a = a.get_field(&key.field).filter(|v| !v.is_null());
+            let b = b.get_field(&key.field).filter(|v| !v.is_null());
+            let ordering = match (a, b) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) if self.nulls == NullOrder::First => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) if self.nulls == NullOrder::First => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) if key.ascending => compare_values(a, b),
+                (Some(a), Some(b)) => compare_values(b, a),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Returns the buffered records in order, leaving the stage empty.
+    fn take_sorted(&self) -> Vec<Record> {
+        let mut records = std::mem::take(&mut *self.buffer.lock().unwrap());
+        records.sort_by(|a, b| self.compare(a, b));
+        records
+    }
+
+    /// Consumes `records` and returns a stream of them in order.
+    pub async fn sort(&self, mut records: RecordStream) -> Result<RecordStream> {
+        while let Some(record) = records.next().await {
+            self.buffer.lock().unwrap().push(record?);
+        }
+        Ok(Box::pin(futures::stream::iter(self.take_sorted().into_iter().map(Ok))))
+    }
+}
+
+#[async_trait]
+impl Transform for SortStage {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        self.buffer.lock().unwrap().push(record);
+        Ok(vec![])
+    }
+
+    async fn flush(&self) -> Result<Vec<Record>> {
+        Ok(self.take_sorted())
+    }
+
+    fn buffers(&self) -> bool {
+        true
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::{json, Value};
+
+    async fn sorted(stage: &SortStage, values: Vec<Value>) -> Vec<Value> {
+        for value in values {
+            assert!(stage.transform(record(value)).await.unwrap().is_empty());
+        }
+        stage.flush().await.unwrap().into_iter().map(|r| Value::Object(r.data.into_iter().collect())).collect()
+    }
+
+    #[tokio::test]
+    async fn numbers_sort_numerically() {
+        let stage = SortStage::new(vec![SortKey::asc("n")]);
+        let output = sorted(&stage, vec![json!({"n": 10}), json!({"n": 2}), json!({"n": 1.5}), json!({"n": 33})]).await;
+        assert_eq!(output, [json!({"n": 1.5}), json!({"n": 2}), json!({"n": 10}), json!({"n": 33})]);
+    }
+
+    #[tokio::test]
+    async fn ties_keep_input_order_and_nulls_sort_last() {
+        let stage = SortStage::new(vec![SortKey::desc("group")]);
+        let output = sorted(
+            &stage,
+            vec![
+                json!({"group": "a", "id": 1}),
+                json!({"group": null, "id": 2}),
+                json!({"group": "b", "id": 3}),
+                json!({"group": "a", "id": 4}),
+                json!({"id": 5}),
+            ],
+        )
+        .await;
+        let ids: Vec<&Value> = output.iter().map(|value| &value["id"]).collect();
+        assert_eq!(ids, [&json!(3), &json!(1), &json!(4), &json!(2), &json!(5)]);
+
+        let stage = SortStage::new(vec![SortKey::asc("group")]).with_nulls(NullOrder::First);
+        let output = sorted(&stage, vec![json!({"group": "a"}), json!({"group": null})]).await;
+        assert_eq!(output[0], json!({"group": null}));
+    }
+
+    #[tokio::test]
+    async fn objects_sort_by_their_contents() {
+        let stage = SortStage::new(vec![SortKey::asc("o")]);
+        let output = sorted(
+            &stage,
+            vec![json!({"o": {"x": 2, "y": 0}}), json!({"o": {"y": 9, "x": 1}}), json!({"o": {"x": 1, "y": 3}})],
+        )
+        .await;
+        assert_eq!(
+            output,
+            [json!({"o": {"x": 1, "y": 3}}), json!({"o": {"y": 9, "x": 1}}), json!({"o": {"x": 2, "y": 0}})]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::core::{DataType, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Splits a delimited string field, such as `"a|b|c"`, into an array of
+/// strings. By default elements are trimmed and empty elements dropped.
+/// Values that are not strings are left unchanged.
+pub struct SplitToArrayTransform {
+    field: String,
+    delimiter: String,
+    trim: bool,
+    drop_empty: bool,
+}
+
+impl SplitToArrayTransform {
+    pub fn new(field: &str, delimiter: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            delimiter: delimiter.to_string(),
+            trim: true,
+            drop_empty: true,
+        }
+    }
+
+    pub fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    pub fn with_drop_empty(mut self, drop_empty: bool) -> Self {
+        self.drop_empty = drop_empty;
+        self
+    }
+}
+
+#[async_trait]
+impl Transform for SplitToArrayTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        if let Some(Value::String(s)) = record.data.get(&self.field) {
+            let items: Vec<Value> = s
+                .split(self.delimiter.as_str())
+                .map(|item| if self.trim { item.trim() } else { item })
+                .filter(|item| !(self.drop_empty && item.is_empty()))
+                .map(|item| Value::String(item.to_string()))
+                .collect();
+            record.data.insert(self.field.clone(), Value::Array(items));
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        for field in schema.fields.iter_mut() {
+            if field.name == self.field {
+                field.data_type = DataType::Array(Box::new(DataType::String));
+            }
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Field;
+    use serde_json::json;
+
+    fn tagged(tags: &str) -> Record {
+        let mut record = Record::new();
+        record.set_field("tags".to_string(), Value::from(tags));
+        record
+    }
+
+    #[tokio::test]
+    async fn splits_a_pipe_delimited_field_into_an_array() {
+        let transform = SplitToArrayTransform::new("tags", "|");
+        let out = transform.transform(tagged(" a | b||c ")).await.unwrap();
+        assert_eq!(out[0].get_field("tags"), Some(&json!(["a", "b", "c"])));
+
+        let raw = SplitToArrayTransform::new("tags", "|").with_trim(false).with_drop_empty(false);
+        let out = raw.transform(tagged(" a | b||c ")).await.unwrap();
+        assert_eq!(out[0].get_field("tags"), Some(&json!([" a ", " b", "", "c "])));
+    }
+
+    #[tokio::test]
+    async fn output_schema_makes_the_field_a_string_array() {
+        let input = Schema::new(vec![Field {
+            name: "tags".to_string(),
+            data_type: DataType::String,
+            nullable: true,
+            description: None,
+        }]);
+        let schema = SplitToArrayTransform::new("tags", "|").get_output_schema(&input).await.unwrap();
+        assert_eq!(schema.fields[0].data_type, DataType::Array(Box::new(DataType::String)));
+    }
+}
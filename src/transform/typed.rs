@@ -0,0 +1,162 @@
+use crate::core::{PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::marker::PhantomData;
+
+/// Round-trips each record through the typed struct `T`, validating its
+/// structure and normalising the data (serde defaults applied, unknown
+/// fields dropped unless `T` keeps them).
+///
+/// The fields of `T` cannot be read from the type itself, so the output
+/// schema is the one given to [`with_schema`](Self::with_schema), or the
+/// input schema passed through unchanged when none is.
+pub struct TypedParseTransform<T> {
+    dead_letter: bool,
+    schema: Option<Schema>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedParseTransform<T> {
+    pub fn new() -> Self {
+        Self {
+            dead_letter: false,
+            schema: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Instead of failing, pass records that do not deserialize through
+    /// unchanged with the serde message in their `error` metadata.
+    pub fn with_dead_letter(mut self, dead_letter: bool) -> Self {
+        self.dead_letter = dead_letter;
+        self
+    }
+
+    /// The schema of `T` as serialized, reported as the output schema.
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+}
+
+impl<T> Default for TypedParseTransform<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalize<T: DeserializeOwned + Serialize>(record: &Record) -> Result<serde_json::Map<String, Value>> {
+    let object: serde_json::Map<String, Value> = record.data.clone().into_iter().collect();
+    let typed: T = serde_json::from_value(Value::Object(object))
+        .map_err(|e| PipelineError::Transform(format!("Typed parse failed: {}", e)))?;
+
+    match serde_json::to_value(typed)? {
+        Value::Object(obj) => Ok(obj),
+        _ => Err(PipelineError::Transform(
+            "Typed parse target did not serialize to a JSON object".to_string()
+        )),
+    }
+}
+
+#[async_trait]
+impl<T> Transform for TypedParseTransform<T>
+where
+    T: DeserializeOwned + Serialize + 'static,
+{
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        match normalize::<T>(&record) {
+            Ok(obj) => {
+                record.data = obj.into_iter().collect();
+                Ok(vec![record])
+            }
+            Err(e) if self.dead_letter => {
+                record.set_metadata("error".to_string(), e.to_string());
+                Ok(vec![record])
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(self.schema.clone().unwrap_or_else(|| input_schema.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DataType, Field};
+    use crate::test_support::record;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize)]
+    struct Order {
+        id: u64,
+        customer: String,
+        #[serde(default)]
+        quantity: u32,
+        #[serde(default = "default_currency")]
+        currency: String,
+    }
+
+    fn default_currency() -> String {
+        "USD".to_string()
+    }
+
+    #[tokio::test]
+    async fn applies_defaults_and_drops_unknown_fields() {
+        let transform = TypedParseTransform::<Order>::new();
+        let out = transform
+            .transform(record(json!({"id": 7, "customer": "acme", "note": "ignored"})))
+            .await
+            .unwrap();
+        assert_eq!(
+            out[0].data,
+            record(json!({"id": 7, "customer": "acme", "quantity": 0, "currency": "USD"})).data
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_required_field_is_an_error() {
+        let transform = TypedParseTransform::<Order>::new();
+        match transform.transform(record(json!({"id": 7}))).await {
+            Err(PipelineError::Transform(message)) => assert!(message.contains("missing field `customer`"), "{}", message),
+            other => panic!("expected a transform error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn dead_letter_keeps_the_record_with_the_serde_message() {
+        let transform = TypedParseTransform::<Order>::new().with_dead_letter(true);
+        let original = record(json!({"id": "seven", "customer": "acme"}));
+        let out = transform.transform(original.clone()).await.unwrap();
+        assert_eq!(out[0].data, original.data);
+        assert!(out[0].get_metadata("error").unwrap().contains("invalid type"));
+    }
+
+    #[tokio::test]
+    async fn output_schema_is_the_given_one_or_the_input() {
+        let field = |name: &str, data_type: DataType| Field {
+            name: name.to_string(),
+            data_type,
+            nullable: false,
+            description: None,
+        };
+        let input = Schema::new(vec![field("id", DataType::Integer), field("note", DataType::String)]);
+        let order = Schema::new(vec![
+            field("id", DataType::Integer),
+            field("customer", DataType::String),
+            field("quantity", DataType::Integer),
+            field("currency", DataType::String),
+        ]);
+
+        let passed_through = TypedParseTransform::<Order>::new().get_output_schema(&input).await.unwrap();
+        assert_eq!(passed_through.field_names(), ["id", "note"]);
+
+        let typed = TypedParseTransform::<Order>::new().with_schema(order).get_output_schema(&input).await.unwrap();
+        assert_eq!(typed.field_names(), ["id", "customer", "quantity", "currency"]);
+    }
+}
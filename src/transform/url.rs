@@ -0,0 +1,161 @@
+use crate::core::{DataType, ErrorPolicy, Field, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use url::Url;
+
+/// Validates and normalizes a URL field. Parsing lowercases the scheme and
+/// host and drops the scheme's default port; on top of that query
+/// parameters are sorted by name (keeping the order of repeated names) and
+/// an empty query is removed. Strings that are not absolute URLs are handled
+/// by the [`ErrorPolicy`].
+///
+/// The host and path can also be written to their own fields.
+pub struct UrlNormalizeTransform {
+    field: String,
+    host_field: Option<String>,
+    path_field: Option<String>,
+    on_error: ErrorPolicy,
+}
+
+impl UrlNormalizeTransform {
+    pub fn new(field: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            host_field: None,
+            path_field: None,
+            on_error: ErrorPolicy::Fail,
+        }
+    }
+
+    pub fn with_host_field(mut self, host_field: &str) -> Self {
+        self.host_field = Some(host_field.to_string());
+        self
+    }
+
+    pub fn with_path_field(mut self, path_field: &str) -> Self {
+        self.path_field = Some(path_field.to_string());
+        self
+    }
+
+    pub fn with_error_policy(mut self, on_error: ErrorPolicy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    fn normalize(&self, value: &Value) -> Result<Url> {
+        let text = value.as_str().ok_or_else(|| {
+            PipelineError::Transform(format!("Field '{}' must be a string URL", self.field))
+        })?;
+        let mut url = Url::parse(text.trim())
+            .map_err(|e| PipelineError::Transform(format!("Invalid URL in field '{}': {}", self.field, e)))?;
+
+        let mut pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        if pairs.is_empty() {
+            url.set_query(None);
+        } else {
+            pairs.sort_by(|a, b| a.0.cmp(&b.0));
+            url.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl Transform for UrlNormalizeTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let Some(value) = record.data.get(&self.field).filter(|v| !v.is_null()) else {
+            return Ok(vec![record]);
+        };
+
+        let (normalized, host, path) = match (self.normalize(value), self.on_error) {
+            (Ok(url), _) => (
+                Value::String(url.to_string()),
+                url.host_str().map_or(Value::Null, |h| Value::String(h.to_string())),
+                Value::String(url.path().to_string()),
+            ),
+            (Err(e), ErrorPolicy::Fail) => return Err(e),
+            (Err(_), ErrorPolicy::NullFill) => (Value::Null, Value::Null, Value::Null),
+            (Err(e), ErrorPolicy::DeadLetter) => {
+                record.set_metadata("error".to_string(), e.to_string());
+                return Ok(vec![record]);
+            }
+        };
+
+        record.set_field(self.field.clone(), normalized);
+        if let Some(ref host_field) = self.host_field {
+            record.set_field(host_field.clone(), host);
+        }
+        if let Some(ref path_field) = self.path_field {
+            record.set_field(path_field.clone(), path);
+        }
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        let mut schema = input_schema.clone();
+        if self.on_error == ErrorPolicy::NullFill {
+            for field in schema.fields.iter_mut() {
+                if field.name == self.field {
+                    field.nullable = true;
+                }
+            }
+        }
+        for extra in [&self.host_field, &self.path_field].into_iter().flatten() {
+            schema.fields.retain(|f| &f.name != extra);
+            schema.fields.push(Field {
+                name: extra.clone(),
+                data_type: DataType::String,
+                nullable: true,
+                description: None,
+            });
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linked(url: &str) -> Record {
+        let mut record = Record::new();
+        record.set_field("url".to_string(), Value::from(url));
+        record
+    }
+
+    #[tokio::test]
+    async fn normalizes_a_messy_url() {
+        let transform = UrlNormalizeTransform::new("url").with_host_field("host").with_path_field("path");
+        let out = transform
+            .transform(linked("  HTTPS://Example.COM:443/a/b?z=1&a=2&m=x&a=1  "))
+            .await
+            .unwrap();
+
+        assert_eq!(out[0].get_field("url"), Some(&Value::from("https://example.com/a/b?a=2&a=1&m=x&z=1")));
+        assert_eq!(out[0].get_field("host"), Some(&Value::from("example.com")));
+        assert_eq!(out[0].get_field("path"), Some(&Value::from("/a/b")));
+
+        let out = transform.transform(linked("http://example.com:8080?")).await.unwrap();
+        assert_eq!(out[0].get_field("url"), Some(&Value::from("http://example.com:8080/")));
+    }
+
+    #[tokio::test]
+    async fn flags_an_invalid_url() {
+        assert!(UrlNormalizeTransform::new("url").transform(linked("not a url")).await.is_err());
+
+        let out = UrlNormalizeTransform::new("url")
+            .with_error_policy(ErrorPolicy::DeadLetter)
+            .transform(linked("not a url"))
+            .await
+            .unwrap();
+        assert_eq!(out[0].get_field("url"), Some(&Value::from("not a url")));
+        assert!(out[0].get_metadata("error").unwrap().contains("Invalid URL"));
+
+        let out = UrlNormalizeTransform::new("url")
+            .with_error_policy(ErrorPolicy::NullFill)
+            .transform(linked("/relative/path"))
+            .await
+            .unwrap();
+        assert_eq!(out[0].get_field("url"), Some(&Value::Null));
+    }
+}
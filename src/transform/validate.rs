@@ -0,0 +1,99 @@
+use crate::core::{Record, Result, Schema, Transform};
+use async_trait::async_trait;
+
+/// What a [`ValidateTransform`] does with a record that does not match its
+/// schema.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvalidPolicy {
+    /// Drop the record.
+    Drop,
+    /// Pass the record on with `valid` metadata set to `false` and the
+    /// reason in `validation_error`.
+    Tag,
+    /// Return the schema error. With a dead-letter sink the record goes
+    /// there; otherwise the run stops.
+    Fail,
+}
+
+/// Checks each record against `schema` with
+/// [`Record::validate_against_schema`], so records can be routed by
+/// validity anywhere in the transform chain rather than only at the source.
+pub struct ValidateTransform {
+    schema: Schema,
+    policy: InvalidPolicy,
+}
+
+impl ValidateTransform {
+    pub fn new(schema: Schema, policy: InvalidPolicy) -> Self {
+        Self { schema, policy }
+    }
+}
+
+#[async_trait]
+impl Transform for ValidateTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let Err(e) = record.validate_against_schema(&self.schema) else {
+            return Ok(vec![record]);
+        };
+        match self.policy {
+            InvalidPolicy::Drop => Ok(vec![]),
+            InvalidPolicy::Tag => {
+                record.set_metadata("valid".to_string(), "false".to_string());
+                record.set_metadata("validation_error".to_string(), e.to_string());
+                Ok(vec![record])
+            }
+            InvalidPolicy::Fail => Err(e),
+        }
+    }
+
+    async fn get_output_schema(&self, _input_schema: &Schema) -> Result<Schema> {
+        Ok(self.schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DataType, Field, PipelineError};
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        let field = |name: &str, nullable| Field {
+            name: name.to_string(),
+            data_type: DataType::String,
+            nullable,
+            description: None,
+        };
+        Schema::new(vec![field("id", false), field("email", true)])
+    }
+
+    #[tokio::test]
+    async fn missing_required_field_is_rejected_by_name() {
+        let missing_id = record(json!({"email": "a@example.com"}));
+
+        let error = ValidateTransform::new(schema(), InvalidPolicy::Fail)
+            .transform(missing_id.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, PipelineError::Schema(ref e) if e.contains("'id'")), "{}", error);
+
+        let tagged = ValidateTransform::new(schema(), InvalidPolicy::Tag)
+            .transform(missing_id.clone())
+            .await
+            .unwrap();
+        assert_eq!(tagged[0].get_metadata("valid"), Some("false"));
+        assert!(tagged[0].get_metadata("validation_error").unwrap().contains("'id'"));
+
+        let dropped = ValidateTransform::new(schema(), InvalidPolicy::Drop).transform(missing_id).await.unwrap();
+        assert!(dropped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_nullable_field_passes() {
+        let transform = ValidateTransform::new(schema(), InvalidPolicy::Fail);
+        let output = transform.transform(record(json!({"id": "1"}))).await.unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].get_metadata("valid"), None);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::core::{PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::Path;
+use wasmtime::{Config, Engine, InstancePre, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+const DEFAULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+const DEFAULT_FUEL_LIMIT: u64 = 100_000_000;
+
+/// Runs each record through a user-supplied WASM module.
+///
+/// The module must export `memory`, `alloc(len: i32) -> i32` and
+/// `transform(ptr: i32, len: i32) -> i64`. The record's data is written as
+/// JSON into memory returned by `alloc`, and `transform` returns the output
+/// JSON location packed as `(ptr << 32) | len`. Every record runs in a fresh
+/// instance with a memory cap and a fuel budget bounding execution time.
+pub struct WasmTransform {
+    engine: Engine,
+    instance_pre: InstancePre<StoreLimits>,
+    memory_limit: usize,
+    fuel_limit: u64,
+}
+
+impl WasmTransform {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Accepts either a binary module or its WAT text form.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(wasm_error)?;
+        let module = Module::new(&engine, bytes).map_err(wasm_error)?;
+        let linker = Linker::new(&engine);
+        let instance_pre = linker.instantiate_pre(&module).map_err(wasm_error)?;
+
+        Ok(Self {
+            engine,
+            instance_pre,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            fuel_limit: DEFAULT_FUEL_LIMIT,
+        })
+    }
+
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    pub fn with_fuel_limit(mut self, fuel: u64) -> Self {
+        self.fuel_limit = fuel;
+        self
+    }
+
+    fn call(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let limits = StoreLimitsBuilder::new().memory_size(self.memory_limit).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.fuel_limit).map_err(wasm_error)?;
+
+        let instance = self.instance_pre.instantiate(&mut store).map_err(wasm_error)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| PipelineError::Transform("WASM module does not export 'memory'".to_string()))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(wasm_error)?;
+        let transform = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "transform")
+            .map_err(wasm_error)?;
+
+        let input_len = i32::try_from(input.len())
+            .map_err(|_| PipelineError::Transform("Record is too large for WASM memory".to_string()))?;
+        let input_ptr = alloc.call(&mut store, input_len).map_err(wasm_error)?;
+        memory
+            .write(&mut store, input_ptr as u32 as usize, input)
+            .map_err(wasm_error)?;
+
+        let packed = transform
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(wasm_error)? as u64;
+        let output_ptr = (packed >> 32) as usize;
+        let output_len = (packed & 0xffff_ffff) as usize;
+
+        // The location comes from the guest, so check it lies within its
+        // memory before allocating a buffer of the size it claims.
+        if output_ptr
+            .checked_add(output_len)
+            .is_none_or(|end| end > memory.data_size(&store))
+        {
+            return Err(PipelineError::Transform(format!(
+                "WASM transform returned {} bytes at {}, outside its {}-byte memory",
+                output_len,
+                output_ptr,
+                memory.data_size(&store)
+            )));
+        }
+        let mut output = vec![0u8; output_len];
+        memory
+            .read(&store, output_ptr, &mut output)
+            .map_err(wasm_error)?;
+        Ok(output)
+    }
+}
+
+fn wasm_error(e: impl std::fmt::Display) -> PipelineError {
+    PipelineError::Transform(format!("WASM error: {}", e))
+}
+
+#[async_trait]
+impl Transform for WasmTransform {
+    async fn transform(&self, mut record: Record) -> Result<Vec<Record>> {
+        let input = serde_json::to_vec(&record.data)?;
+        let output = self.call(&input)?;
+
+        match serde_json::from_slice::<Value>(&output)? {
+            Value::Object(obj) => {
+                record.data = obj.into_iter().collect();
+                Ok(vec![record])
+            }
+            _ => Err(PipelineError::Transform(
+                "WASM transform did not return a JSON object".to_string()
+            )),
+        }
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    /// Copies the input JSON object and appends `"wasm":true` before its
+    /// closing brace; expects a non-empty object.
+    const ADD_FIELD: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) ",\"wasm\":true}")
+          (func (export "alloc") (param i32) (result i32)
+            i32.const 1024)
+          (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+            (memory.copy (i32.const 4096) (local.get $ptr) (i32.sub (local.get $len) (i32.const 1)))
+            (memory.copy (i32.add (i32.const 4095) (local.get $len)) (i32.const 0) (i32.const 13))
+            (i64.or
+              (i64.shl (i64.const 4096) (i64.const 32))
+              (i64.extend_i32_u (i32.add (local.get $len) (i32.const 12))))))
+    "#;
+
+    /// Claims a 4 GiB output.
+    const OUT_OF_BOUNDS: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32)
+            i32.const 0)
+          (func (export "transform") (param i32 i32) (result i64)
+            i64.const 0xffffffff))
+    "#;
+
+    const SPIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32)
+            i32.const 0)
+          (func (export "transform") (param i32 i32) (result i64)
+            (loop $l (br $l))
+            i64.const 0))
+    "#;
+
+    #[tokio::test]
+    async fn adds_a_field() {
+        let transform = WasmTransform::from_bytes(ADD_FIELD.as_bytes()).unwrap();
+        let output = transform.transform(record(json!({"id": 7}))).await.unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].get_field("id"), Some(&json!(7)));
+        assert_eq!(output[0].get_field("wasm"), Some(&json!(true)));
+    }
+
+    #[tokio::test]
+    async fn rejects_output_outside_memory() {
+        let transform = WasmTransform::from_bytes(OUT_OF_BOUNDS.as_bytes()).unwrap();
+        let err = transform.transform(record(json!({"id": 7}))).await.unwrap_err();
+        assert!(matches!(err, PipelineError::Transform(ref m) if m.contains("outside")), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn stops_when_fuel_runs_out() {
+        let transform = WasmTransform::from_bytes(SPIN.as_bytes()).unwrap().with_fuel_limit(10_000);
+        assert!(transform.transform(record(json!({"id": 7}))).await.is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::core::{timestamp_millis, PipelineError, Record, Result, Schema, Transform};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Default)]
+struct SeenKeys {
+    /// Event time each key was last let through.
+    last_emitted: HashMap<String, i64>,
+    /// `(time, key)` in emission order, used to expire old keys.
+    order: VecDeque<(i64, String)>,
+    watermark: i64,
+}
+
+/// Drops a record when a record with the same key was let through less than
+/// `window` earlier in event time, read from `timestamp_field`. Once the
+/// window has elapsed the key is accepted again, and keys that can no longer
+/// match are forgotten, so memory is bounded by the keys seen per window.
+///
+/// The window is measured from the last record let through; dropped
+/// duplicates do not extend it. Timestamps are parsed with
+/// [`timestamp_millis`].
+pub struct WindowedDedupTransform {
+    key_fields: Vec<String>,
+    timestamp_field: String,
+    window_millis: i64,
+    seen: Mutex<SeenKeys>,
+}
+
+impl WindowedDedupTransform {
+    pub fn new(key_fields: Vec<String>, timestamp_field: &str, window: Duration) -> Self {
+        Self {
+            key_fields,
+            timestamp_field: timestamp_field.to_string(),
+            window_millis: window.as_millis() as i64,
+            seen: Mutex::new(SeenKeys::default()),
+        }
+    }
+
+    fn key(&self, record: &Record) -> String {
+        self.key_fields
+            .iter()
+            .map(|field| record.get_field(field).unwrap_or(&Value::Null).to_string())
+            .collect::<Vec<_>>()
+            .join("\u{1f}")
+    }
+}
+
+#[async_trait]
+impl Transform for WindowedDedupTransform {
+    async fn transform(&self, record: Record) -> Result<Vec<Record>> {
+        let timestamp = record
+            .get_field(&self.timestamp_field)
+            .and_then(timestamp_millis)
+            .ok_or_else(|| {
+                PipelineError::Transform(format!("Missing or invalid timestamp field '{}'", self.timestamp_field))
+            })?;
+        let key = self.key(&record);
+
+        let mut seen = self.seen.lock().unwrap();
+        seen.watermark = seen.watermark.max(timestamp);
+
+        let expired_before = seen.watermark.saturating_sub(self.window_millis);
+        while let Some((time, _)) = seen.order.front()
+            && *time <= expired_before
+        {
+            let (time, key) = seen.order.pop_front().unwrap();
+            if seen.last_emitted.get(&key) == Some(&time) {
+                seen.last_emitted.remove(&key);
+            }
+        }
+
+        if let Some(&last) = seen.last_emitted.get(&key)
+            && timestamp - last < self.window_millis
+        {
+            return Ok(vec![]);
+        }
+
+        seen.last_emitted.insert(key.clone(), timestamp);
+        seen.order.push_back((timestamp, key));
+        Ok(vec![record])
+    }
+
+    async fn get_output_schema(&self, input_schema: &Schema) -> Result<Schema> {
+        Ok(input_schema.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record;
+    use serde_json::json;
+
+    fn event(id: &str, ts: &str) -> Record {
+        record(json!({"id": id, "ts": ts}))
+    }
+
+    #[tokio::test]
+    async fn repeated_key_is_dropped_inside_the_window_and_accepted_after() {
+        let transform = WindowedDedupTransform::new(vec!["id".to_string()], "ts", Duration::from_secs(60));
+        let mut kept = Vec::new();
+        for (id, ts) in [
+            ("a", "2024-01-01T00:00:00Z"),
+            ("a", "2024-01-01T00:00:30Z"),
+            ("b", "2024-01-01T00:00:45Z"),
+            ("a", "2024-01-01T00:00:59Z"),
+            ("a", "2024-01-01T00:01:00Z"),
+            ("a", "2024-01-01T00:01:10Z"),
+        ] {
+            for record in transform.transform(event(id, ts)).await.unwrap() {
+                kept.push((
+                    record.get_field("id").unwrap().as_str().unwrap().to_string(),
+                    record.get_field("ts").unwrap().as_str().unwrap().to_string(),
+                ));
+            }
+        }
+        assert_eq!(
+            kept,
+            vec![
+                ("a".to_string(), "2024-01-01T00:00:00Z".to_string()),
+                ("b".to_string(), "2024-01-01T00:00:45Z".to_string()),
+                ("a".to_string(), "2024-01-01T00:01:00Z".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_timestamp_errors() {
+        let transform = WindowedDedupTransform::new(vec!["id".to_string()], "ts", Duration::from_secs(60));
+        let mut record = Record::new();
+        record.set_field("id".to_string(), Value::from("a"));
+        assert!(transform.transform(record).await.is_err());
+    }
+}